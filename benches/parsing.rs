@@ -0,0 +1,205 @@
+//! Response parsing benchmarks.
+//!
+//! Every provider response is deserialized with serde before it is handed back to the caller,
+//! so parsing cost is paid on each request. These benchmarks parse large synthetic payloads
+//! shaped like real provider responses, and are part of the crate's performance contract:
+//! a significant regression here is treated as a bug.
+//!
+//! Run them with `cargo bench`.
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use geocoding::geoadmin::GeoAdminForwardResponse;
+use geocoding::opencage::OpencageResponse;
+use geocoding::openstreetmap::OpenstreetmapResponse;
+
+const RESULTS: usize = 1000;
+
+/// An OpenCage response with `RESULTS` fully-annotated results
+fn opencage_payload() -> String {
+    let results: Vec<String> = (0..RESULTS)
+        .map(|i| {
+            format!(
+                r#"{{
+                "annotations": {{
+                    "DMS": {{ "lat": "41° 24' 5.06412'' N", "lng": "2° 7' 43.40064'' E" }},
+                    "MGRS": "31TDF2717083684",
+                    "Maidenhead": "JN11bj56ki",
+                    "Mercator": {{ "x": 236968.295, "y": 5043465.71 }},
+                    "OSM": {{
+                        "edit_url": "https://www.openstreetmap.org/edit?way={i}#map=17/41.40141/2.12872",
+                        "url": "https://www.openstreetmap.org/?mlat=41.40141&mlon=2.12872#map=17/41.40141/2.12872"
+                    }},
+                    "callingcode": 34,
+                    "currency": {{
+                        "alternate_symbols": [],
+                        "decimal_mark": ",",
+                        "html_entity": "&#x20AC;",
+                        "iso_code": "EUR",
+                        "iso_numeric": "978",
+                        "name": "Euro",
+                        "smallest_denomination": 1,
+                        "subunit": "Cent",
+                        "subunit_to_unit": 100,
+                        "symbol": "€",
+                        "symbol_first": 1,
+                        "thousands_separator": "."
+                    }},
+                    "flag": "🇪🇸",
+                    "geohash": "sp3e82yhdvd7p5x1mbdv",
+                    "qibla": 110.53,
+                    "sun": {{
+                        "rise": {{ "apparent": 1523251260, "astronomical": 1523245440, "civil": 1523249580, "nautical": 1523247540 }},
+                        "set": {{ "apparent": 1523298360, "astronomical": 1523304180, "civil": 1523300040, "nautical": 1523302080 }}
+                    }},
+                    "timezone": {{
+                        "name": "Europe/Madrid",
+                        "now_in_dst": 1,
+                        "offset_sec": 7200,
+                        "offset_string": "+0200",
+                        "short_name": "CEST"
+                    }},
+                    "what3words": {{ "words": "chins.pictures.passes" }},
+                    "wikidata": "Q{i}"
+                }},
+                "bounds": {{
+                    "northeast": {{ "lat": 41.4015815, "lng": 2.128952 }},
+                    "southwest": {{ "lat": 41.401227, "lng": 2.1284918 }}
+                }},
+                "components": {{
+                    "ISO_3166-1_alpha-2": "ES",
+                    "_type": "building",
+                    "city": "Barcelona",
+                    "country": "Spain",
+                    "country_code": "es",
+                    "house_number": "{i}",
+                    "postcode": "08017",
+                    "road": "Carrer de Calatrava",
+                    "state": "Catalonia"
+                }},
+                "confidence": 10,
+                "formatted": "Carrer de Calatrava, {i}, 08017 Barcelona, Spain",
+                "geometry": {{ "lat": 41.4014067, "lng": 2.1287224 }}
+            }}"#
+            )
+        })
+        .collect();
+    format!(
+        r#"{{
+        "documentation": "https://opencagedata.com/api",
+        "licenses": [{{ "name": "see attribution guide", "url": "https://opencagedata.com/credits" }}],
+        "rate": {{ "limit": 2500, "remaining": 2499, "reset": 1523318400 }},
+        "results": [{}],
+        "status": {{ "code": 200, "message": "OK" }},
+        "stay_informed": {{ "blog": "https://blog.opencagedata.com", "twitter": "https://twitter.com/opencagedata" }},
+        "thanks": "For using an OpenCage API",
+        "timestamp": {{ "created_http": "Mon, 09 Apr 2018 12:33:01 GMT", "created_unix": 1523277181 }},
+        "total_results": {}
+    }}"#,
+        results.join(","),
+        RESULTS
+    )
+}
+
+/// A Nominatim GeoJSON response with `RESULTS` features including address details
+fn nominatim_payload() -> String {
+    let features: Vec<String> = (0..RESULTS)
+        .map(|i| {
+            format!(
+                r#"{{
+                "type": "Feature",
+                "properties": {{
+                    "place_id": {i},
+                    "osm_type": "way",
+                    "osm_id": {i},
+                    "display_name": "{i}, Carrer de Calatrava, les Tres Torres, Sarrià - Sant Gervasi, Barcelona, BCN, Catalonia, 08017, Spain",
+                    "place_rank": 30,
+                    "category": "building",
+                    "type": "apartments",
+                    "importance": 0.7409999999999999,
+                    "address": {{
+                        "house_number": "{i}",
+                        "road": "Carrer de Calatrava",
+                        "suburb": "les Tres Torres",
+                        "city_district": "Sarrià - Sant Gervasi",
+                        "city": "Barcelona",
+                        "county": "BCN",
+                        "state": "Catalonia",
+                        "postcode": "08017",
+                        "country": "Spain",
+                        "country_code": "es"
+                    }}
+                }},
+                "bbox": [2.1284918, 41.401227, 2.128952, 41.4015815],
+                "geometry": {{ "type": "Point", "coordinates": [2.12872241167437, 41.40140675] }}
+            }}"#
+            )
+        })
+        .collect();
+    format!(
+        r#"{{
+        "type": "FeatureCollection",
+        "licence": "Data © OpenStreetMap contributors, ODbL 1.0. https://osm.org/copyright",
+        "features": [{}]
+    }}"#,
+        features.join(",")
+    )
+}
+
+/// A GeoAdmin search response with `RESULTS` locations
+fn geoadmin_payload() -> String {
+    let features: Vec<String> = (0..RESULTS)
+        .map(|i| {
+            format!(
+                r#"{{
+                "properties": {{
+                    "origin": "address",
+                    "geom_quadindex": "021300220302203002031",
+                    "weight": 1512,
+                    "zoomlevel": 10,
+                    "lon": 7.451352119445801,
+                    "detail": "seftigenstrasse {i} 3084 wabern 355 koeniz ch be",
+                    "rank": 7,
+                    "lat": 46.92793655395508,
+                    "num": {i},
+                    "y": 2600968.75,
+                    "x": 1197427.0,
+                    "label": "Seftigenstrasse {i} <b>3084 Wabern</b>",
+                    "id": {i}
+                }}
+            }}"#
+            )
+        })
+        .collect();
+    format!(
+        r#"{{ "type": "FeatureCollection", "features": [{}] }}"#,
+        features.join(",")
+    )
+}
+
+fn parsing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+
+    let payload = opencage_payload();
+    group.throughput(Throughput::Bytes(payload.len() as u64));
+    group.bench_function("opencage_annotated_1k", |b| {
+        b.iter(|| serde_json::from_str::<OpencageResponse<f64>>(black_box(&payload)).unwrap())
+    });
+
+    let payload = nominatim_payload();
+    group.throughput(Throughput::Bytes(payload.len() as u64));
+    group.bench_function("nominatim_addressdetails_1k", |b| {
+        b.iter(|| serde_json::from_str::<OpenstreetmapResponse<f64>>(black_box(&payload)).unwrap())
+    });
+
+    let payload = geoadmin_payload();
+    group.throughput(Throughput::Bytes(payload.len() as u64));
+    group.bench_function("geoadmin_1k", |b| {
+        b.iter(|| {
+            serde_json::from_str::<GeoAdminForwardResponse<f64>>(black_box(&payload)).unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, parsing);
+criterion_main!(benches);
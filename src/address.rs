@@ -0,0 +1,368 @@
+//! A provider-independent [`Address`](struct.Address.html), and the tables translating
+//! provider-specific address component keys into its fields.
+//!
+//! Providers disagree on how they name address components: a Nominatim `town`, an OpenCage
+//! `_normalized_city` and a Pelias `locality` all end up in [`Address::city`](struct.Address.html#structfield.city).
+//! Each table lists `(provider key, field)` pairs in order of precedence: when several keys map
+//! to the same field, the first one present in the result wins.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::Address;
+//! use geocoding::address::NOMINATIM;
+//!
+//! let components = vec![("village", "Grindelwald"), ("country_code", "ch")];
+//! let address = Address::from_components(NOMINATIM, components);
+//! assert_eq!(address.city.as_deref(), Some("Grindelwald"));
+//! ```
+use crate::opencage;
+use crate::openstreetmap::AddressDetails;
+use crate::pelias::PeliasProperties;
+use crate::{Deserialize, Serialize};
+use num_traits::Float;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// A normalized field of an [`Address`](struct.Address.html)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AddressField {
+    HouseNumber,
+    Road,
+    Neighbourhood,
+    Suburb,
+    City,
+    County,
+    State,
+    Postcode,
+    Country,
+    CountryCode,
+}
+
+/// A provider-independent postal address
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Address {
+    pub house_number: Option<String>,
+    pub road: Option<String>,
+    pub neighbourhood: Option<String>,
+    /// A subdivision of a city, such as a suburb, borough or city district
+    pub suburb: Option<String>,
+    /// The locality, whether it is a city, a town, a village or a hamlet
+    pub city: Option<String>,
+    pub county: Option<String>,
+    /// The first-level administrative division, such as a state, province or region
+    pub state: Option<String>,
+    pub postcode: Option<String>,
+    pub country: Option<String>,
+    /// The ISO 3166-1 alpha-2 country code, in lower case
+    pub country_code: Option<String>,
+}
+
+/// Nominatim `address` keys, also used by LocationIQ
+pub static NOMINATIM: &[(&str, AddressField)] = &[
+    ("house_number", AddressField::HouseNumber),
+    ("road", AddressField::Road),
+    ("pedestrian", AddressField::Road),
+    ("footway", AddressField::Road),
+    ("cycleway", AddressField::Road),
+    ("path", AddressField::Road),
+    ("neighbourhood", AddressField::Neighbourhood),
+    ("quarter", AddressField::Neighbourhood),
+    ("residential", AddressField::Neighbourhood),
+    ("suburb", AddressField::Suburb),
+    ("borough", AddressField::Suburb),
+    ("city_district", AddressField::Suburb),
+    ("district", AddressField::Suburb),
+    ("city", AddressField::City),
+    ("town", AddressField::City),
+    ("village", AddressField::City),
+    ("hamlet", AddressField::City),
+    ("isolated_dwelling", AddressField::City),
+    ("municipality", AddressField::City),
+    ("county", AddressField::County),
+    ("state_district", AddressField::County),
+    ("state", AddressField::State),
+    ("region", AddressField::State),
+    ("province", AddressField::State),
+    ("postcode", AddressField::Postcode),
+    ("country", AddressField::Country),
+    ("country_code", AddressField::CountryCode),
+];
+
+/// OpenCage `components` keys
+pub static OPENCAGE: &[(&str, AddressField)] = &[
+    ("house_number", AddressField::HouseNumber),
+    ("road", AddressField::Road),
+    ("street", AddressField::Road),
+    ("pedestrian", AddressField::Road),
+    ("footway", AddressField::Road),
+    ("neighbourhood", AddressField::Neighbourhood),
+    ("quarter", AddressField::Neighbourhood),
+    ("suburb", AddressField::Suburb),
+    ("borough", AddressField::Suburb),
+    ("city_district", AddressField::Suburb),
+    ("_normalized_city", AddressField::City),
+    ("city", AddressField::City),
+    ("town", AddressField::City),
+    ("village", AddressField::City),
+    ("hamlet", AddressField::City),
+    ("municipality", AddressField::City),
+    ("county", AddressField::County),
+    ("state", AddressField::State),
+    ("province", AddressField::State),
+    ("region", AddressField::State),
+    ("postcode", AddressField::Postcode),
+    ("country", AddressField::Country),
+    ("country_code", AddressField::CountryCode),
+    ("ISO_3166-1_alpha-2", AddressField::CountryCode),
+];
+
+/// Pelias feature `properties` keys
+///
+/// Pelias only returns ISO 3166-1 alpha-3 country codes, so `country_code` is never set.
+pub static PELIAS: &[(&str, AddressField)] = &[
+    ("housenumber", AddressField::HouseNumber),
+    ("street", AddressField::Road),
+    ("neighbourhood", AddressField::Neighbourhood),
+    ("borough", AddressField::Suburb),
+    ("locality", AddressField::City),
+    ("localadmin", AddressField::City),
+    ("county", AddressField::County),
+    ("region", AddressField::State),
+    ("macroregion", AddressField::State),
+    ("postalcode", AddressField::Postcode),
+    ("country", AddressField::Country),
+];
+
+/// [libpostal](https://github.com/openvenues/libpostal) address parser labels
+pub static LIBPOSTAL: &[(&str, AddressField)] = &[
+    ("house_number", AddressField::HouseNumber),
+    ("road", AddressField::Road),
+    ("suburb", AddressField::Neighbourhood),
+    ("city_district", AddressField::Suburb),
+    ("city", AddressField::City),
+    ("state_district", AddressField::County),
+    ("state", AddressField::State),
+    ("postcode", AddressField::Postcode),
+    ("country", AddressField::Country),
+];
+
+impl Address {
+    /// Build an address from provider-specific `(key, value)` components, using a translation table
+    pub fn from_components<'a, I>(table: &[(&str, AddressField)], components: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        let components: HashMap<&str, &str> = components.into_iter().collect();
+        let mut address = Address::default();
+        for (key, field) in table {
+            if let Some(value) = components.get(key) {
+                let slot = address.field_mut(*field);
+                if slot.is_none() {
+                    *slot = Some(value.to_string());
+                }
+            }
+        }
+        if let Some(country_code) = address.country_code.as_mut() {
+            *country_code = country_code.to_lowercase();
+        }
+        address
+    }
+
+    /// The value of a normalized field
+    pub fn field(&self, field: AddressField) -> Option<&str> {
+        match field {
+            AddressField::HouseNumber => self.house_number.as_deref(),
+            AddressField::Road => self.road.as_deref(),
+            AddressField::Neighbourhood => self.neighbourhood.as_deref(),
+            AddressField::Suburb => self.suburb.as_deref(),
+            AddressField::City => self.city.as_deref(),
+            AddressField::County => self.county.as_deref(),
+            AddressField::State => self.state.as_deref(),
+            AddressField::Postcode => self.postcode.as_deref(),
+            AddressField::Country => self.country.as_deref(),
+            AddressField::CountryCode => self.country_code.as_deref(),
+        }
+    }
+
+    fn field_mut(&mut self, field: AddressField) -> &mut Option<String> {
+        match field {
+            AddressField::HouseNumber => &mut self.house_number,
+            AddressField::Road => &mut self.road,
+            AddressField::Neighbourhood => &mut self.neighbourhood,
+            AddressField::Suburb => &mut self.suburb,
+            AddressField::City => &mut self.city,
+            AddressField::County => &mut self.county,
+            AddressField::State => &mut self.state,
+            AddressField::Postcode => &mut self.postcode,
+            AddressField::Country => &mut self.country,
+            AddressField::CountryCode => &mut self.country_code,
+        }
+    }
+
+    // Struct-typed components are translated through their serialized keys, so that
+    // fields added to them are picked up by the tables without further changes
+    fn from_serializable<S>(table: &[(&str, AddressField)], components: &S) -> Self
+    where
+        S: Serialize,
+    {
+        let value = serde_json::to_value(components).unwrap_or_default();
+        let components = value
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter_map(|(key, value)| Some((key.as_str(), value.as_str()?)));
+        Address::from_components(table, components)
+    }
+}
+
+impl From<&AddressDetails> for Address {
+    fn from(details: &AddressDetails) -> Self {
+        Address::from_serializable(NOMINATIM, details)
+    }
+}
+
+impl<T> From<&opencage::Results<T>> for Address
+where
+    T: Float + Debug,
+{
+    fn from(result: &opencage::Results<T>) -> Self {
+        Address::from_serializable(OPENCAGE, &result.components)
+    }
+}
+
+impl From<&PeliasProperties> for Address {
+    fn from(properties: &PeliasProperties) -> Self {
+        Address::from_serializable(PELIAS, properties)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nominatim_test() {
+        let details: AddressDetails = serde_json::from_str(
+            r#"{
+                "house_number": "68",
+                "road": "Carrer de Calatrava",
+                "suburb": "les Tres Torres",
+                "city_district": "Sarrià - Sant Gervasi",
+                "city": "Barcelona",
+                "state": "Catalonia",
+                "postcode": "08017",
+                "country": "Spain",
+                "country_code": "es"
+            }"#,
+        )
+        .unwrap();
+        let address = Address::from(&details);
+        assert_eq!(address.house_number.as_deref(), Some("68"));
+        assert_eq!(address.road.as_deref(), Some("Carrer de Calatrava"));
+        assert_eq!(address.suburb.as_deref(), Some("les Tres Torres"));
+        assert_eq!(address.city.as_deref(), Some("Barcelona"));
+        assert_eq!(address.country_code.as_deref(), Some("es"));
+    }
+
+    #[test]
+    fn nominatim_extra_test() {
+        let details: AddressDetails = serde_json::from_str(
+            r#"{
+                "town": "Interlaken",
+                "state": "Bern",
+                "ISO3166-2-lvl4": "CH-BE",
+                "country_code": "ch"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(details.town.as_deref(), Some("Interlaken"));
+        assert_eq!(details.extra["ISO3166-2-lvl4"], "CH-BE");
+        let address = Address::from(&details);
+        assert_eq!(address.city.as_deref(), Some("Interlaken"));
+    }
+
+    #[test]
+    fn opencage_test() {
+        let result: opencage::Results<f64> = serde_json::from_str(
+            r#"{
+                "components": {
+                    "ISO_3166-1_alpha-2": "DE",
+                    "_category": "place",
+                    "_normalized_city": "Berlin",
+                    "_type": "neighbourhood",
+                    "borough": "Mitte",
+                    "city": "Berlin",
+                    "country": "Germany",
+                    "postcode": "10559",
+                    "state": "Berlin",
+                    "suburb": "Moabit"
+                },
+                "confidence": 7,
+                "formatted": "Moabit, Berlin, Germany",
+                "geometry": { "lat": 52.5301, "lng": 13.3426 }
+            }"#,
+        )
+        .unwrap();
+        let address = Address::from(&result);
+        assert_eq!(address.suburb.as_deref(), Some("Moabit"));
+        assert_eq!(address.city.as_deref(), Some("Berlin"));
+        assert_eq!(address.postcode.as_deref(), Some("10559"));
+        assert_eq!(address.country_code.as_deref(), Some("de"));
+    }
+
+    #[test]
+    fn pelias_test() {
+        let properties: PeliasProperties = serde_json::from_str(
+            r#"{
+                "id": "us/ny/city_of_newyork:1",
+                "gid": "openaddresses:address:us/ny/city_of_newyork:1",
+                "layer": "address",
+                "source": "openaddresses",
+                "source_id": "us/ny/city_of_newyork:1",
+                "housenumber": "30",
+                "street": "West 26th Street",
+                "postalcode": "10010",
+                "country": "United States",
+                "country_a": "USA",
+                "region": "New York",
+                "county": "New York County",
+                "borough": "Manhattan",
+                "locality": "New York",
+                "neighbourhood": "Flatiron District"
+            }"#,
+        )
+        .unwrap();
+        let address = Address::from(&properties);
+        assert_eq!(address.house_number.as_deref(), Some("30"));
+        assert_eq!(address.road.as_deref(), Some("West 26th Street"));
+        assert_eq!(address.suburb.as_deref(), Some("Manhattan"));
+        assert_eq!(address.city.as_deref(), Some("New York"));
+        assert_eq!(address.state.as_deref(), Some("New York"));
+        assert_eq!(address.country_code, None);
+    }
+
+    #[test]
+    fn libpostal_test() {
+        let labels = vec![
+            ("house_number", "781"),
+            ("road", "franklin ave"),
+            ("suburb", "crown heights"),
+            ("city_district", "brooklyn"),
+            ("city", "nyc"),
+            ("state", "ny"),
+            ("postcode", "11216"),
+        ];
+        let address = Address::from_components(LIBPOSTAL, labels);
+        assert_eq!(address.neighbourhood.as_deref(), Some("crown heights"));
+        assert_eq!(address.suburb.as_deref(), Some("brooklyn"));
+        assert_eq!(address.city.as_deref(), Some("nyc"));
+    }
+
+    #[test]
+    fn precedence_test() {
+        let components = vec![("village", "Grindelwald"), ("town", "Interlaken")];
+        let address = Address::from_components(NOMINATIM, components);
+        assert_eq!(address.field(AddressField::City), Some("Interlaken"));
+    }
+}
@@ -0,0 +1,437 @@
+//! The [AMap (Gaode)](https://lbs.amap.com/) provider.
+//!
+//! Geocoding methods are implemented on the [`Amap`](struct.Amap.html) struct.
+//! Please see the [API documentation](https://lbs.amap.com/api/webservice/guide/api/georegeo)
+//! for details. A Web Service API key is required.
+//!
+//! ### A Note on Coordinate Systems
+//! AMap uses the GCJ-02 coordinate system. The `Forward` and `Reverse` implementations convert
+//! to and from WGS84, like every other provider; the full responses returned by
+//! [`forward_full`](struct.Amap.html#method.forward_full) and
+//! [`reverse_full`](struct.Amap.html#method.reverse_full) are left in GCJ-02. Their
+//! `wgs84_location()` methods return converted points.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Amap, Forward, Point};
+//!
+//! let amap = Amap::new("your-api-key".to_string());
+//! let address = "北京市朝阳区阜通东大街6号";
+//! let res: Result<Vec<Point<f64>>, _> = amap.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::transform::{gcj02_to_wgs84, wgs84_to_gcj02};
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Deserializer, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+
+/// An instance of the AMap geocoding service
+pub struct Amap {
+    api_key: String,
+    client: Client,
+    endpoint: String,
+}
+
+impl Amap {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "AMap",
+        docs_url: "https://lbs.amap.com/api/webservice/guide/api/georegeo",
+        terms_url: Some("https://lbs.amap.com/pages/terms/"),
+        attribution: Some("© AutoNavi"),
+        rate_limit: None,
+    };
+
+    /// Create a new AMap geocoding instance using the default endpoint
+    pub fn new(api_key: String) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Amap {
+            api_key,
+            client,
+            endpoint: "https://restapi.amap.com/v3/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of an AMap geocoding instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://restapi.amap.com/v3/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A forward-geocoding lookup of an address, returning a full detailed response
+    ///
+    /// Coordinates in the response are in GCJ-02. An optional `city` (name, citycode or adcode)
+    /// narrows the search.
+    ///
+    /// Please see [the documentation](https://lbs.amap.com/api/webservice/guide/api/georegeo#geo) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Amap;
+    /// use geocoding::amap::AmapResponse;
+    ///
+    /// let amap = Amap::new("your-api-key".to_string());
+    /// let res: Result<AmapResponse, _> = amap.forward_full("阜通东大街6号", Some("北京"));
+    /// ```
+    pub fn forward_full(
+        &self,
+        address: &str,
+        city: Option<&str>,
+    ) -> Result<AmapResponse, GeocodingError> {
+        let mut query = vec![("address", address.to_string())];
+        if let Some(city) = city {
+            query.push(("city", city.to_string()));
+        }
+        let res: AmapResponse = self.get("geocode/geo", query)?;
+        match res.status.as_str() {
+            "1" => Ok(res),
+            _ => Err(GeocodingError::Forward),
+        }
+    }
+
+    /// A reverse lookup of a GCJ-02 point, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://lbs.amap.com/api/webservice/guide/api/georegeo#regeo) for details.
+    pub fn reverse_full<T>(&self, point: &Point<T>) -> Result<AmapReverseResponse, GeocodingError>
+    where
+        T: Float + Debug,
+    {
+        let query = vec![(
+            "location",
+            format!(
+                "{:.6},{:.6}",
+                point.x().to_f64().unwrap(),
+                point.y().to_f64().unwrap()
+            ),
+        )];
+        let res: AmapReverseResponse = self.get("geocode/regeo", query)?;
+        match res.status.as_str() {
+            "1" => Ok(res),
+            _ => Err(GeocodingError::Reverse),
+        }
+    }
+
+    // AMap reports errors with a 200 status and a `status` of "0"
+    fn get<R>(
+        &self,
+        path: &str,
+        mut query: Vec<(&'static str, String)>,
+    ) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        query.push(("output", String::from("JSON")));
+        query.push(("key", self.api_key.clone()));
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl<T> Forward<T> for Amap
+where
+    T: Float + Debug,
+{
+    /// A forward-geocoding lookup of an address, returning WGS84 points.
+    /// Please see [the documentation](https://lbs.amap.com/api/webservice/guide/api/georegeo#geo) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(place, None)?;
+        Ok(res
+            .geocodes
+            .iter()
+            .filter_map(|geocode| geocode.wgs84_location())
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for Amap
+where
+    T: Float + Debug,
+{
+    /// A reverse lookup of a WGS84 point, returning its `formatted_address`.
+    /// Please see [the documentation](https://lbs.amap.com/api/webservice/guide/api/georegeo#regeo) for details.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(&wgs84_to_gcj02(point))?;
+        Ok(res
+            .regeocode
+            .and_then(|regeocode| regeocode.formatted_address))
+    }
+}
+
+/// The top-level full JSON response returned by a geocoding request
+///
+/// AMap returns empty values as empty arrays (`[]`); these are deserialized as `None`.
+///
+/// See [the documentation](https://lbs.amap.com/api/webservice/guide/api/georegeo#geo) for more details
+///
+///```json
+///{
+///  "status": "1",
+///  "info": "OK",
+///  "infocode": "10000",
+///  "count": "1",
+///  "geocodes": [
+///    {
+///      "formatted_address": "北京市朝阳区阜通东大街6号",
+///      "country": "中国",
+///      "province": "北京市",
+///      "citycode": "010",
+///      "city": "北京市",
+///      "district": "朝阳区",
+///      "township": [],
+///      "adcode": "110105",
+///      "street": "阜通东大街",
+///      "number": "6号",
+///      "location": "116.483038,39.990633",
+///      "level": "门牌号"
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AmapResponse {
+    /// `"1"` on success, `"0"` on failure
+    pub status: String,
+    pub info: String,
+    pub infocode: String,
+    #[serde(default)]
+    pub geocodes: Vec<Geocode>,
+}
+
+/// A geocoding result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Geocode {
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub formatted_address: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub country: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub province: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub citycode: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub city: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub district: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub township: Option<String>,
+    /// The administrative division code
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub adcode: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub street: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub number: Option<String>,
+    /// The GCJ-02 location, as `"lng,lat"`
+    pub location: String,
+    /// The kind of the matched place, e.g. `门牌号` (house number) or `道路` (road)
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub level: Option<String>,
+}
+
+impl Geocode {
+    /// The location, in GCJ-02
+    pub fn location<T>(&self) -> Option<Point<T>>
+    where
+        T: Float + Debug,
+    {
+        parse_location(&self.location)
+    }
+
+    /// The location, converted to WGS84
+    pub fn wgs84_location<T>(&self) -> Option<Point<T>>
+    where
+        T: Float + Debug,
+    {
+        self.location().map(|location| gcj02_to_wgs84(&location))
+    }
+}
+
+/// The top-level full JSON response returned by a reverse geocoding request
+///
+/// See [the documentation](https://lbs.amap.com/api/webservice/guide/api/georegeo#regeo) for more details
+///
+///```json
+///{
+///  "status": "1",
+///  "info": "OK",
+///  "infocode": "10000",
+///  "regeocode": {
+///    "formatted_address": "北京市朝阳区望京街道方恒国际中心B座",
+///    "addressComponent": {
+///      "country": "中国",
+///      "province": "北京市",
+///      "city": [],
+///      "citycode": "010",
+///      "district": "朝阳区",
+///      "adcode": "110105",
+///      "township": "望京街道",
+///      "towncode": "110105026000",
+///      "streetNumber": {
+///        "street": "阜通东大街",
+///        "number": "6号",
+///        "location": "116.482005,39.990056",
+///        "direction": "东南",
+///        "distance": "60.6621"
+///      }
+///    }
+///  }
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AmapReverseResponse {
+    pub status: String,
+    pub info: String,
+    pub infocode: String,
+    pub regeocode: Option<Regeocode>,
+}
+
+/// A reverse geocoding result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Regeocode {
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub formatted_address: Option<String>,
+    #[serde(rename = "addressComponent")]
+    pub address_component: AddressComponent,
+}
+
+/// The structured address of a reverse geocoding result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddressComponent {
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub country: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub province: Option<String>,
+    /// Empty for the municipalities directly under the central government, such as Beijing
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub city: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub citycode: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub district: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub adcode: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub township: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub towncode: Option<String>,
+    #[serde(rename = "streetNumber")]
+    pub street_number: Option<StreetNumber>,
+}
+
+/// The nearest house number of a reverse geocoding result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StreetNumber {
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub street: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub number: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub location: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub direction: Option<String>,
+    /// The distance from the queried point, in meters
+    #[serde(default, deserialize_with = "deserialize_string_or_empty")]
+    pub distance: Option<String>,
+}
+
+// AMap returns missing values as empty arrays rather than omitting them
+fn deserialize_string_or_empty<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(value
+        .as_str()
+        .filter(|value| !value.is_empty())
+        .map(str::to_string))
+}
+
+fn parse_location<T>(location: &str) -> Option<Point<T>>
+where
+    T: Float + Debug,
+{
+    let mut parts = location.split(',');
+    let lng = T::from(parts.next()?.trim().parse::<f64>().ok()?)?;
+    let lat = T::from(parts.next()?.trim().parse::<f64>().ok()?)?;
+    Some(Point::new(lng, lat))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn forward_response_test() {
+        let res: AmapResponse = serde_json::from_str(
+            r#"{
+                "status": "1",
+                "info": "OK",
+                "infocode": "10000",
+                "count": "1",
+                "geocodes": [
+                    {
+                        "formatted_address": "北京市朝阳区阜通东大街6号",
+                        "country": "中国",
+                        "province": "北京市",
+                        "citycode": "010",
+                        "city": "北京市",
+                        "district": "朝阳区",
+                        "township": [],
+                        "neighborhood": { "name": [], "type": [] },
+                        "adcode": "110105",
+                        "street": "阜通东大街",
+                        "number": "6号",
+                        "location": "116.483038,39.990633",
+                        "level": "门牌号"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let geocode = &res.geocodes[0];
+        assert_eq!(geocode.township, None);
+        assert_eq!(geocode.adcode.as_deref(), Some("110105"));
+        assert_eq!(geocode.location(), Some(Point::new(116.483038, 39.990633)));
+        let wgs84: Point<f64> = geocode.wgs84_location().unwrap();
+        assert!((wgs84.x() - 116.4769).abs() < 1e-3);
+    }
+
+    #[test]
+    fn reverse_response_test() {
+        let res: AmapReverseResponse = serde_json::from_str(
+            r#"{
+                "status": "1",
+                "info": "OK",
+                "infocode": "10000",
+                "regeocode": {
+                    "formatted_address": [],
+                    "addressComponent": { "country": [], "province": [], "city": [], "district": [], "adcode": [] }
+                }
+            }"#,
+        )
+        .unwrap();
+        let regeocode = res.regeocode.unwrap();
+        assert_eq!(regeocode.formatted_address, None);
+        assert!(regeocode.address_component.street_number.is_none());
+    }
+}
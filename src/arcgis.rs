@@ -0,0 +1,549 @@
+//! The [ArcGIS World Geocoding Service](https://developers.arcgis.com/rest/geocode/api-reference/overview-world-geocoding-service.htm) provider.
+//!
+//! Geocoding methods are implemented on the [`ArcGis`](struct.ArcGis.html) struct.
+//! The service may be used anonymously, in which case results may not be stored,
+//! or with a token, which also allows requesting storable results using
+//! [`with_for_storage`](struct.ArcGis.html#method.with_for_storage).
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{ArcGis, Forward, Point};
+//!
+//! let arcgis = ArcGis::new();
+//! let address = "380 New York St, Redlands, CA 92373";
+//! let res: Result<Vec<Point<f64>>, _> = arcgis.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::GeocodingError;
+use crate::InputBounds;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+
+/// An instance of the ArcGIS World Geocoding Service
+pub struct ArcGis {
+    client: Client,
+    endpoint: String,
+    token: Option<String>,
+    for_storage: bool,
+}
+
+/// An instance of a parameter builder for ArcGIS `findAddressCandidates` queries
+pub struct ArcGisParams<'a, T>
+where
+    T: Float + Debug,
+{
+    single_line: &'a str,
+    max_locations: Option<u8>,
+    category: Option<&'a str>,
+    source_country: Option<&'a str>,
+    location: Option<&'a Point<T>>,
+    search_extent: Option<&'a InputBounds<T>>,
+}
+
+impl<'a, T> ArcGisParams<'a, T>
+where
+    T: Float + Debug,
+{
+    /// Create a new ArcGIS parameter builder
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::{InputBounds, Point};
+    /// use geocoding::arcgis::ArcGisParams;
+    ///
+    /// let extent = InputBounds::new((-117.2, 34.0), (-117.1, 34.1));
+    /// let params = ArcGisParams::new(&"380 New York St")
+    ///     .with_source_country("USA")
+    ///     .with_search_extent(&extent)
+    ///     .with_max_locations(5)
+    ///     .build();
+    /// ```
+    pub fn new(single_line: &'a str) -> ArcGisParams<'a, T> {
+        ArcGisParams {
+            single_line,
+            max_locations: None,
+            category: None,
+            source_country: None,
+            location: None,
+            search_extent: None,
+        }
+    }
+
+    /// Set the `maxLocations` property, the maximum number of candidates to return
+    pub fn with_max_locations(&mut self, max_locations: u8) -> &mut Self {
+        self.max_locations = Some(max_locations);
+        self
+    }
+
+    /// Set the `category` property, e.g. `Address` or `POI`
+    pub fn with_category(&mut self, category: &'a str) -> &mut Self {
+        self.category = Some(category);
+        self
+    }
+
+    /// Set the `sourceCountry` property, a comma-separated list of ISO 3166-1 country codes
+    pub fn with_source_country(&mut self, source_country: &'a str) -> &mut Self {
+        self.source_country = Some(source_country);
+        self
+    }
+
+    /// Set the `location` property, favouring candidates close to a point
+    pub fn with_location(&mut self, location: &'a Point<T>) -> &mut Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Set the `searchExtent` property, restricting candidates to a bounding box
+    pub fn with_search_extent(&mut self, search_extent: &'a InputBounds<T>) -> &mut Self {
+        self.search_extent = Some(search_extent);
+        self
+    }
+
+    /// Build and return an instance of ArcGisParams
+    pub fn build(&self) -> ArcGisParams<'a, T> {
+        ArcGisParams {
+            single_line: self.single_line,
+            max_locations: self.max_locations,
+            category: self.category,
+            source_country: self.source_country,
+            location: self.location,
+            search_extent: self.search_extent,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("SingleLine", self.single_line.to_string())];
+        if let Some(max_locations) = self.max_locations {
+            query.push(("maxLocations", max_locations.to_string()));
+        }
+        if let Some(category) = self.category {
+            query.push(("category", category.to_string()));
+        }
+        if let Some(source_country) = self.source_country {
+            query.push(("sourceCountry", source_country.to_string()));
+        }
+        if let Some(location) = self.location {
+            query.push((
+                "location",
+                format!(
+                    "{},{}",
+                    location.x().to_f64().unwrap(),
+                    location.y().to_f64().unwrap()
+                ),
+            ));
+        }
+        if let Some(search_extent) = self.search_extent {
+            query.push(("searchExtent", String::from(*search_extent)));
+        }
+        query
+    }
+}
+
+impl ArcGis {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "ArcGIS World Geocoding Service",
+        docs_url: "https://developers.arcgis.com/rest/geocode/api-reference/overview-world-geocoding-service.htm",
+        terms_url: Some("https://www.esri.com/en-us/legal/terms/full-master-agreement"),
+        attribution: Some("Powered by Esri"),
+        rate_limit: None,
+    };
+
+    /// Create a new anonymous ArcGIS geocoding instance using the default endpoint
+    pub fn new() -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        ArcGis {
+            client,
+            endpoint: "https://geocode.arcgis.com/arcgis/rest/services/World/GeocodeServer/"
+                .to_string(),
+            token: None,
+            for_storage: false,
+        }
+    }
+
+    /// Authenticate requests with an ArcGIS API key or OAuth access token
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_owned());
+        self
+    }
+
+    /// Request results which may be stored, which requires a token
+    ///
+    /// Storing results is a billable operation. Please see [the documentation](https://developers.arcgis.com/rest/geocode/api-reference/geocoding-free-vs-paid.htm) for details.
+    pub fn with_for_storage(mut self, for_storage: bool) -> Self {
+        self.for_storage = for_storage;
+        self
+    }
+
+    /// Set a custom endpoint of an ArcGIS geocoding instance, e.g. a self-hosted geocode service
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://geocode.arcgis.com/arcgis/rest/services/World/GeocodeServer/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A forward-geocoding lookup of an address, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://developers.arcgis.com/rest/geocode/api-reference/geocoding-find-address-candidates.htm) for details.
+    ///
+    /// This method passes the `f`, `outFields` and `forStorage` parameters to the API.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::ArcGis;
+    /// use geocoding::arcgis::{ArcGisParams, ArcGisResponse};
+    ///
+    /// let arcgis = ArcGis::new();
+    /// let params = ArcGisParams::new(&"380 New York St, Redlands")
+    ///     .with_max_locations(1)
+    ///     .build();
+    /// let res: Result<ArcGisResponse<f64>, _> = arcgis.forward_full(&params);
+    /// ```
+    pub fn forward_full<T>(
+        &self,
+        params: &ArcGisParams<T>,
+    ) -> Result<ArcGisResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = params.as_query();
+        query.push(("outFields", String::from("*")));
+        match self.get("findAddressCandidates", query)? {
+            ArcGisReply::Ok(res) => Ok(res),
+            ArcGisReply::Error { .. } => Err(GeocodingError::Forward),
+        }
+    }
+
+    /// A reverse lookup of a point, returning a full detailed response
+    ///
+    /// Returns `None` if no address could be found near the point.
+    ///
+    /// Please see [the documentation](https://developers.arcgis.com/rest/geocode/api-reference/geocoding-reverse-geocode.htm) for details.
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+    ) -> Result<Option<ArcGisReverseResponse<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let query = vec![(
+            "location",
+            format!(
+                "{},{}",
+                point.x().to_f64().unwrap(),
+                point.y().to_f64().unwrap()
+            ),
+        )];
+        match self.get("reverseGeocode", query)? {
+            ArcGisReply::Ok(res) => Ok(Some(res)),
+            // "Unable to find address for the specified location."
+            ArcGisReply::Error { error } if error.code == 400 => Ok(None),
+            ArcGisReply::Error { .. } => Err(GeocodingError::Reverse),
+        }
+    }
+
+    // ArcGIS reports most errors with a 200 status and an `error` object
+    fn get<R>(
+        &self,
+        path: &str,
+        mut query: Vec<(&'static str, String)>,
+    ) -> Result<ArcGisReply<R>, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        query.push(("f", String::from("json")));
+        query.push(("forStorage", self.for_storage.to_string()));
+        if let Some(token) = &self.token {
+            query.push(("token", token.clone()));
+        }
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: ArcGisReply<R> = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl Default for ArcGis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Forward<T> for ArcGis
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of an address. Please see [the documentation](https://developers.arcgis.com/rest/geocode/api-reference/geocoding-find-address-candidates.htm) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(&ArcGisParams::new(place))?;
+        Ok(res
+            .candidates
+            .iter()
+            .map(|candidate| Point::new(candidate.location.x, candidate.location.y))
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for ArcGis
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the `Match_addr` of the nearest address.
+    /// Please see [the documentation](https://developers.arcgis.com/rest/geocode/api-reference/geocoding-reverse-geocode.htm) for details.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        Ok(self
+            .reverse_full(point)?
+            .and_then(|res| res.address.match_addr))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ArcGisReply<R> {
+    Error { error: ArcGisError },
+    Ok(R),
+}
+
+/// An error reported by the service
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArcGisError {
+    pub code: u16,
+    pub message: String,
+    #[serde(default)]
+    pub details: Vec<String>,
+}
+
+/// The top-level full JSON response returned by a `findAddressCandidates` request
+///
+/// See [the documentation](https://developers.arcgis.com/rest/geocode/api-reference/geocoding-find-address-candidates.htm#ESRI_SECTION1_42D7D3D0231241E9B656C01438209440) for more details
+///
+///```json
+///{
+///  "spatialReference": { "wkid": 4326, "latestWkid": 4326 },
+///  "candidates": [
+///    {
+///      "address": "380 New York St, Redlands, California, 92373",
+///      "location": { "x": -117.19567031799994, "y": 34.056488119000051 },
+///      "score": 100,
+///      "attributes": {
+///        "Loc_name": "World",
+///        "Status": "M",
+///        "Score": 100,
+///        "Match_addr": "380 New York St, Redlands, California, 92373",
+///        "Addr_type": "PointAddress",
+///        "AddNum": "380",
+///        "StName": "New York",
+///        "StType": "St",
+///        "City": "Redlands",
+///        "Region": "California",
+///        "RegionAbbr": "CA",
+///        "Postal": "92373",
+///        "Country": "USA",
+///        "CntryName": "United States"
+///      },
+///      "extent": {
+///        "xmin": -117.19667031799994,
+///        "ymin": 34.055488119000051,
+///        "xmax": -117.19467031799994,
+///        "ymax": 34.057488119000051
+///      }
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArcGisResponse<T>
+where
+    T: Float + Debug,
+{
+    pub spatial_reference: SpatialReference,
+    pub candidates: Vec<Candidate<T>>,
+}
+
+/// The spatial reference of returned coordinates
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpatialReference {
+    pub wkid: u32,
+    pub latest_wkid: Option<u32>,
+}
+
+/// An address candidate
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Candidate<T>
+where
+    T: Float + Debug,
+{
+    pub address: String,
+    pub location: Location<T>,
+    /// How well the candidate matches the input, from 0 to 100
+    pub score: f64,
+    pub attributes: Attributes,
+    pub extent: Option<Extent<T>>,
+}
+
+/// A coordinate pair
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Location<T>
+where
+    T: Float + Debug,
+{
+    pub x: T,
+    pub y: T,
+}
+
+/// A bounding box
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Extent<T>
+where
+    T: Float + Debug,
+{
+    pub xmin: T,
+    pub ymin: T,
+    pub xmax: T,
+    pub ymax: T,
+}
+
+/// Address attributes of a candidate or reverse geocoding result
+///
+/// Only a subset of the [output fields](https://developers.arcgis.com/rest/geocode/api-reference/geocoding-service-output.htm) is typed;
+/// fields which aren't returned for a given result are `None`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Attributes {
+    #[serde(rename = "Match_addr")]
+    pub match_addr: Option<String>,
+    pub long_label: Option<String>,
+    pub short_label: Option<String>,
+    /// The match level, e.g. `PointAddress`, `StreetAddress` or `Locality`
+    #[serde(rename = "Addr_type")]
+    pub addr_type: Option<String>,
+    pub r#type: Option<String>,
+    pub place_name: Option<String>,
+    pub add_num: Option<String>,
+    pub address: Option<String>,
+    pub st_name: Option<String>,
+    pub st_type: Option<String>,
+    pub neighborhood: Option<String>,
+    pub nbrhd: Option<String>,
+    pub district: Option<String>,
+    pub city: Option<String>,
+    pub metro_area: Option<String>,
+    pub subregion: Option<String>,
+    pub region: Option<String>,
+    pub region_abbr: Option<String>,
+    pub postal: Option<String>,
+    pub postal_ext: Option<String>,
+    /// The ISO 3166-1 alpha-3 country code of a candidate
+    pub country: Option<String>,
+    /// The ISO 3166-1 alpha-3 country code of a reverse geocoding result
+    pub country_code: Option<String>,
+    pub cntry_name: Option<String>,
+}
+
+/// The top-level full JSON response returned by a `reverseGeocode` request
+///
+/// See [the documentation](https://developers.arcgis.com/rest/geocode/api-reference/geocoding-reverse-geocode.htm#ESRI_SECTION1_8F2B8A4AFFDE4A4E93E8E4D8C8BB3C88) for more details
+///
+///```json
+///{
+///  "address": {
+///    "Match_addr": "380 New York St, Redlands, California, 92373",
+///    "LongLabel": "380 New York St, Redlands, CA, 92373, USA",
+///    "ShortLabel": "380 New York St",
+///    "Addr_type": "PointAddress",
+///    "AddNum": "380",
+///    "Address": "380 New York St",
+///    "City": "Redlands",
+///    "Region": "California",
+///    "RegionAbbr": "CA",
+///    "Postal": "92373",
+///    "CntryName": "United States",
+///    "CountryCode": "USA"
+///  },
+///  "location": {
+///    "x": -117.19566766299997,
+///    "y": 34.056450453000054,
+///    "spatialReference": { "wkid": 4326, "latestWkid": 4326 }
+///  }
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArcGisReverseResponse<T>
+where
+    T: Float + Debug,
+{
+    pub address: Attributes,
+    pub location: Location<T>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn candidates_test() {
+        let res: ArcGisReply<ArcGisResponse<f64>> = serde_json::from_str(
+            r#"{
+                "spatialReference": { "wkid": 4326, "latestWkid": 4326 },
+                "candidates": [
+                    {
+                        "address": "380 New York St, Redlands, California, 92373",
+                        "location": { "x": -117.195670318, "y": 34.056488119 },
+                        "score": 100,
+                        "attributes": { "Match_addr": "380 New York St, Redlands, California, 92373", "Addr_type": "PointAddress", "AddNum": "380", "RegionAbbr": "CA" }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let res = match res {
+            ArcGisReply::Ok(res) => res,
+            ArcGisReply::Error { .. } => panic!("expected candidates"),
+        };
+        let candidate = &res.candidates[0];
+        assert_eq!(candidate.score, 100.0);
+        assert_eq!(
+            candidate.attributes.addr_type.as_deref(),
+            Some("PointAddress")
+        );
+        assert_eq!(candidate.attributes.region_abbr.as_deref(), Some("CA"));
+    }
+
+    #[test]
+    fn error_test() {
+        let res: ArcGisReply<ArcGisReverseResponse<f64>> = serde_json::from_str(
+            r#"{
+                "error": {
+                    "code": 400,
+                    "message": "Cannot perform query. Invalid query parameters.",
+                    "details": ["Unable to find address for the specified location."]
+                }
+            }"#,
+        )
+        .unwrap();
+        assert!(matches!(res, ArcGisReply::Error { error } if error.code == 400));
+    }
+}
@@ -0,0 +1,362 @@
+//! The [Baidu Maps](https://lbsyun.baidu.com/) provider.
+//!
+//! Geocoding methods are implemented on the [`Baidu`](struct.Baidu.html) struct.
+//! Please see the [geocoding](https://lbsyun.baidu.com/faq/api?title=webapi/guide/webservice-geocoding)
+//! and [reverse geocoding](https://lbsyun.baidu.com/faq/api?title=webapi/guide/webservice-geocoding-abroad)
+//! API documentation for details. An application key (`ak`) is required.
+//!
+//! ### A Note on Coordinate Systems
+//! Baidu returns coordinates in its own BD-09 coordinate system. The `Forward` and `Reverse`
+//! implementations convert to and from WGS84, like every other provider; the full responses
+//! returned by [`forward_full`](struct.Baidu.html#method.forward_full) and
+//! [`reverse_full`](struct.Baidu.html#method.reverse_full) are left in BD-09, and may be converted
+//! using the [`transform`](../transform/index.html) helpers.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Baidu, Forward, Point};
+//!
+//! let baidu = Baidu::new("your-application-key".to_string());
+//! let address = "北京市海淀区上地十街10号";
+//! let res: Result<Vec<Point<f64>>, _> = baidu.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::transform::{bd09_to_wgs84, wgs84_to_bd09};
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+
+/// An instance of the Baidu Maps geocoding service
+pub struct Baidu {
+    api_key: String,
+    client: Client,
+    endpoint: String,
+}
+
+impl Baidu {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "Baidu Maps",
+        docs_url: "https://lbsyun.baidu.com/faq/api?title=webapi/guide/webservice-geocoding",
+        terms_url: Some("https://lbsyun.baidu.com/index.php?title=open/law"),
+        attribution: Some("© Baidu"),
+        rate_limit: None,
+    };
+
+    /// Create a new Baidu geocoding instance using the default endpoint
+    pub fn new(api_key: String) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Baidu {
+            api_key,
+            client,
+            endpoint: "https://api.map.baidu.com/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of a Baidu geocoding instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://api.map.baidu.com/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A forward-geocoding lookup of an address, returning a full detailed response
+    ///
+    /// Coordinates in the response are in BD-09. An optional `city` narrows the search. Returns
+    /// `None` if the address couldn't be found.
+    ///
+    /// Please see [the documentation](https://lbsyun.baidu.com/faq/api?title=webapi/guide/webservice-geocoding) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Baidu;
+    /// use geocoding::baidu::BaiduResponse;
+    ///
+    /// let baidu = Baidu::new("your-application-key".to_string());
+    /// let res: Result<Option<BaiduResponse<f64>>, _> =
+    ///     baidu.forward_full("上地十街10号", Some("北京市"));
+    /// ```
+    pub fn forward_full<T>(
+        &self,
+        address: &str,
+        city: Option<&str>,
+    ) -> Result<Option<BaiduResponse<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = vec![("address", address.to_string())];
+        if let Some(city) = city {
+            query.push(("city", city.to_string()));
+        }
+        let res: BaiduReply<BaiduResponse<T>> = self.get("geocoding/v3/", query)?;
+        res.into_result()
+    }
+
+    /// A reverse lookup of a BD-09 point, returning a full detailed response
+    ///
+    /// Coordinates in the response are in BD-09. Returns `None` if nothing was found.
+    ///
+    /// Please see [the documentation](https://lbsyun.baidu.com/faq/api?title=webapi/guide/webservice-geocoding-abroad) for details.
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+    ) -> Result<Option<BaiduReverseResponse<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        // Baidu expects lat, lng order
+        let query = vec![
+            (
+                "location",
+                format!(
+                    "{},{}",
+                    point.y().to_f64().unwrap(),
+                    point.x().to_f64().unwrap()
+                ),
+            ),
+            ("coordtype", String::from("bd09ll")),
+        ];
+        let res: BaiduReply<BaiduReverseResponse<T>> = self.get("reverse_geocoding/v3/", query)?;
+        res.into_result()
+    }
+
+    // Baidu reports errors with a 200 status and a non-zero `status`
+    fn get<R>(
+        &self,
+        path: &str,
+        mut query: Vec<(&'static str, String)>,
+    ) -> Result<BaiduReply<R>, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        query.push(("output", String::from("json")));
+        query.push(("ak", self.api_key.clone()));
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: BaiduReply<R> = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl<T> Forward<T> for Baidu
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of an address, returning a WGS84 point.
+    /// Please see [the documentation](https://lbsyun.baidu.com/faq/api?title=webapi/guide/webservice-geocoding) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(place, None)?;
+        Ok(res
+            .map(|res| bd09_to_wgs84(&Point::new(res.location.lng, res.location.lat)))
+            .into_iter()
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for Baidu
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a WGS84 point, returning its `formatted_address`.
+    /// Please see [the documentation](https://lbsyun.baidu.com/faq/api?title=webapi/guide/webservice-geocoding-abroad) for details.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(&wgs84_to_bd09(point))?;
+        Ok(res
+            .map(|res| res.formatted_address)
+            .filter(|address| !address.is_empty()))
+    }
+}
+
+// Valid requests matching nothing get an internal error status, telling them apart by message
+const NO_RESULT: i32 = 1;
+const NO_RESULT_MESSAGE: &str = "无相关结果";
+
+#[derive(Debug, Deserialize)]
+struct BaiduReply<R> {
+    status: i32,
+    #[serde(default, alias = "msg")]
+    message: String,
+    result: Option<R>,
+}
+
+impl<R> BaiduReply<R> {
+    fn into_result(self) -> Result<Option<R>, GeocodingError> {
+        match self.status {
+            0 => Ok(self.result),
+            NO_RESULT if self.message.contains(NO_RESULT_MESSAGE) => Ok(None),
+            status => Err(GeocodingError::Provider {
+                status,
+                message: self.message,
+            }),
+        }
+    }
+}
+
+/// The `result` of a geocoding request
+///
+/// See [the documentation](https://lbsyun.baidu.com/faq/api?title=webapi/guide/webservice-geocoding) for more details
+///
+///```json
+///{
+///  "location": { "lng": 116.3076223267197, "lat": 40.05682848596073 },
+///  "precise": 1,
+///  "confidence": 80,
+///  "comprehension": 100,
+///  "level": "门址"
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BaiduResponse<T>
+where
+    T: Float + Debug,
+{
+    /// The location, in BD-09
+    pub location: LngLat<T>,
+    /// 1 for an exact match, 0 for an approximate (fuzzy) one
+    pub precise: u8,
+    /// The absolute accuracy, from 0 to 100; 80 or more means an error of less than 100 meters
+    pub confidence: u8,
+    /// How well the address was understood, from 0 to 100
+    pub comprehension: u8,
+    /// The kind of the matched place, e.g. `门址` (house number) or `道路` (road)
+    pub level: String,
+}
+
+/// A BD-09 coordinate pair
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LngLat<T>
+where
+    T: Float + Debug,
+{
+    pub lng: T,
+    pub lat: T,
+}
+
+/// The `result` of a reverse geocoding request
+///
+/// See [the documentation](https://lbsyun.baidu.com/faq/api?title=webapi/guide/webservice-geocoding-abroad) for more details
+///
+///```json
+///{
+///  "location": { "lng": 116.30762232672, "lat": 40.056828485961 },
+///  "formatted_address": "北京市海淀区上地十街10号",
+///  "business": "上地,马连洼,西北旺",
+///  "addressComponent": {
+///    "country": "中国",
+///    "country_code": 0,
+///    "country_code_iso": "CHN",
+///    "province": "北京市",
+///    "city": "北京市",
+///    "district": "海淀区",
+///    "town": "",
+///    "adcode": "110108",
+///    "street": "上地十街",
+///    "street_number": "10号"
+///  },
+///  "cityCode": 131
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BaiduReverseResponse<T>
+where
+    T: Float + Debug,
+{
+    /// The location, in BD-09
+    pub location: LngLat<T>,
+    pub formatted_address: String,
+    pub business: Option<String>,
+    #[serde(rename = "addressComponent")]
+    pub address_component: AddressComponent,
+    #[serde(rename = "cityCode")]
+    pub city_code: Option<i32>,
+}
+
+/// The structured address of a reverse geocoding result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddressComponent {
+    pub country: String,
+    pub country_code_iso: Option<String>,
+    pub province: String,
+    pub city: String,
+    pub district: String,
+    pub town: Option<String>,
+    /// The administrative division code
+    pub adcode: String,
+    pub street: String,
+    pub street_number: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn forward_response_test() {
+        let res: BaiduReply<BaiduResponse<f64>> = serde_json::from_str(
+            r#"{
+                "status": 0,
+                "result": {
+                    "location": { "lng": 116.3076223267197, "lat": 40.05682848596073 },
+                    "precise": 1,
+                    "confidence": 80,
+                    "comprehension": 100,
+                    "level": "门址"
+                }
+            }"#,
+        )
+        .unwrap();
+        let res = res.into_result().unwrap().unwrap();
+        assert_eq!(res.precise, 1);
+        let wgs84 = bd09_to_wgs84(&Point::new(res.location.lng, res.location.lat));
+        assert!((wgs84.x() - 116.2948).abs() < 1e-3);
+        assert!((wgs84.y() - 40.0498).abs() < 1e-3);
+    }
+
+    #[test]
+    fn error_response_test() {
+        let res: BaiduReply<BaiduResponse<f64>> = serde_json::from_str(
+            r#"{ "status": 200, "message": "APP不存在，AK有误请检查再重试" }"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            res.into_result(),
+            Err(GeocodingError::Provider { status: 200, message }) if message == "APP不存在，AK有误请检查再重试"
+        ));
+    }
+
+    #[test]
+    fn no_result_response_test() {
+        let res: BaiduReply<BaiduResponse<f64>> = serde_json::from_str(
+            r#"{ "status": 1, "msg": "Internal Service Error:无相关结果", "results": [] }"#,
+        )
+        .unwrap();
+        assert!(res.into_result().unwrap().is_none());
+        let res: BaiduReply<BaiduResponse<f64>> =
+            serde_json::from_str(r#"{ "status": 1, "message": "服务器内部错误" }"#).unwrap();
+        assert!(matches!(
+            res.into_result(),
+            Err(GeocodingError::Provider { status: 1, .. })
+        ));
+    }
+}
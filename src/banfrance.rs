@@ -0,0 +1,549 @@
+//! The French [Base Adresse Nationale](https://adresse.data.gouv.fr/) (BAN) provider.
+//!
+//! Geocoding methods are implemented on the [`BanFrance`](struct.BanFrance.html) struct,
+//! using the `api-adresse.data.gouv.fr` API. The BAN is the reference source for French
+//! addresses, and its API is free and requires no key. Please see the
+//! [API documentation](https://adresse.data.gouv.fr/outils/api-doc/adresse) for details.
+//!
+//! The API is rate-limited to 50 requests per second and per IP address. Large numbers of
+//! addresses should be geocoded using [`forward_batch`](struct.BanFrance.html#method.forward_batch),
+//! which uploads them as a single CSV file.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{BanFrance, Forward, Point};
+//!
+//! let ban = BanFrance::new();
+//! let address = "8 bd du port, Amiens";
+//! let res: Result<Vec<Point<f64>>, _> = ban.forward(address);
+//! ```
+use crate::info::{ProviderInfo, RateLimit};
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use reqwest::blocking::multipart::{Form, Part};
+use std::fmt::Debug;
+
+/// An instance of the Base Adresse Nationale geocoding service
+pub struct BanFrance {
+    client: Client,
+    endpoint: String,
+}
+
+/// The kind of a BAN result
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BanType {
+    /// An address, down to the house number
+    Housenumber,
+    /// A street
+    Street,
+    /// A named place (lieu-dit)
+    Locality,
+    /// A municipality
+    Municipality,
+}
+
+impl BanType {
+    fn as_str(self) -> &'static str {
+        match self {
+            BanType::Housenumber => "housenumber",
+            BanType::Street => "street",
+            BanType::Locality => "locality",
+            BanType::Municipality => "municipality",
+        }
+    }
+}
+
+/// An instance of a parameter builder for BAN searches
+pub struct BanParams<'a, T>
+where
+    T: Float + Debug,
+{
+    query: &'a str,
+    limit: Option<u8>,
+    autocomplete: Option<bool>,
+    r#type: Option<BanType>,
+    postcode: Option<&'a str>,
+    citycode: Option<&'a str>,
+    bias: Option<&'a Point<T>>,
+}
+
+impl<'a, T> BanParams<'a, T>
+where
+    T: Float + Debug,
+{
+    /// Create a new BAN parameter builder
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::banfrance::{BanParams, BanType};
+    ///
+    /// let params = BanParams::<f64>::new(&"8 bd du port")
+    ///     .with_type(BanType::Housenumber)
+    ///     .with_citycode("80021")
+    ///     .with_limit(1)
+    ///     .build();
+    /// ```
+    pub fn new(query: &'a str) -> BanParams<'a, T> {
+        BanParams {
+            query,
+            limit: None,
+            autocomplete: None,
+            r#type: None,
+            postcode: None,
+            citycode: None,
+            bias: None,
+        }
+    }
+
+    /// Set the `limit` property, the maximum number of results to return
+    pub fn with_limit(&mut self, limit: u8) -> &mut Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Set the `autocomplete` property, treating the query as partial input
+    pub fn with_autocomplete(&mut self, autocomplete: bool) -> &mut Self {
+        self.autocomplete = Some(autocomplete);
+        self
+    }
+
+    /// Set the `type` property, only returning results of the given kind
+    pub fn with_type(&mut self, r#type: BanType) -> &mut Self {
+        self.r#type = Some(r#type);
+        self
+    }
+
+    /// Set the `postcode` property, only returning results with the given postcode
+    pub fn with_postcode(&mut self, postcode: &'a str) -> &mut Self {
+        self.postcode = Some(postcode);
+        self
+    }
+
+    /// Set the `citycode` property, only returning results in the municipality with the given INSEE code
+    pub fn with_citycode(&mut self, citycode: &'a str) -> &mut Self {
+        self.citycode = Some(citycode);
+        self
+    }
+
+    /// Set the `lat` and `lon` properties, favouring results close to a point
+    pub fn with_bias(&mut self, bias: &'a Point<T>) -> &mut Self {
+        self.bias = Some(bias);
+        self
+    }
+
+    /// Build and return an instance of BanParams
+    pub fn build(&self) -> BanParams<'a, T> {
+        BanParams {
+            query: self.query,
+            limit: self.limit,
+            autocomplete: self.autocomplete,
+            r#type: self.r#type,
+            postcode: self.postcode,
+            citycode: self.citycode,
+            bias: self.bias,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("q", self.query.to_string())];
+        if let Some(limit) = self.limit {
+            query.push(("limit", limit.to_string()));
+        }
+        if let Some(autocomplete) = self.autocomplete {
+            query.push((
+                "autocomplete",
+                String::from(if autocomplete { "1" } else { "0" }),
+            ));
+        }
+        if let Some(r#type) = self.r#type {
+            query.push(("type", r#type.as_str().to_string()));
+        }
+        if let Some(postcode) = self.postcode {
+            query.push(("postcode", postcode.to_string()));
+        }
+        if let Some(citycode) = self.citycode {
+            query.push(("citycode", citycode.to_string()));
+        }
+        if let Some(bias) = self.bias {
+            query.push(("lat", bias.y().to_f64().unwrap().to_string()));
+            query.push(("lon", bias.x().to_f64().unwrap().to_string()));
+        }
+        query
+    }
+}
+
+impl BanFrance {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "Base Adresse Nationale",
+        docs_url: "https://adresse.data.gouv.fr/outils/api-doc/adresse",
+        terms_url: Some("https://adresse.data.gouv.fr/cgu"),
+        attribution: Some("Base Adresse Nationale, Licence Ouverte 2.0"),
+        rate_limit: Some(RateLimit::per_second(50)),
+    };
+
+    /// Create a new BAN geocoding instance using the default endpoint
+    pub fn new() -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        BanFrance {
+            client,
+            endpoint: "https://api-adresse.data.gouv.fr/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of a BAN geocoding instance, e.g. a self-hosted `addok` instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://api-adresse.data.gouv.fr/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A forward-geocoding lookup of an address, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://adresse.data.gouv.fr/outils/api-doc/adresse) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::BanFrance;
+    /// use geocoding::banfrance::{BanParams, BanResponse};
+    ///
+    /// let ban = BanFrance::new();
+    /// let params = BanParams::new(&"8 bd du port, Amiens").with_limit(1).build();
+    /// let res: Result<BanResponse<f64>, _> = ban.forward_full(&params);
+    /// ```
+    pub fn forward_full<T>(&self, params: &BanParams<T>) -> Result<BanResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get("search/", params.as_query())
+    }
+
+    /// A reverse lookup of a point, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://adresse.data.gouv.fr/outils/api-doc/adresse) for details.
+    pub fn reverse_full<T>(&self, point: &Point<T>) -> Result<BanResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let query = vec![
+            ("lon", point.x().to_f64().unwrap().to_string()),
+            ("lat", point.y().to_f64().unwrap().to_string()),
+        ];
+        self.get("reverse/", query)
+    }
+
+    /// Forward-geocode many addresses at once, using the CSV batch endpoint
+    ///
+    /// Results are returned in the order of `addresses`; addresses which couldn't be geocoded
+    /// have a `result_status` other than `ok`, and no coordinates.
+    ///
+    /// Please see [the documentation](https://adresse.data.gouv.fr/outils/api-doc/adresse) for details.
+    pub fn forward_batch<T>(
+        &self,
+        addresses: &[&str],
+    ) -> Result<Vec<BanBatchResult<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut writer = csv::Writer::from_writer(vec![]);
+        writer.write_record(["q"])?;
+        for address in addresses {
+            writer.write_record([address])?;
+        }
+        let data = writer
+            .into_inner()
+            .map_err(|err| GeocodingError::Csv(err.into_error().into()))?;
+        let form = Form::new()
+            .part(
+                "data",
+                Part::bytes(data)
+                    .file_name("addresses.csv")
+                    .mime_str("text/csv")?,
+            )
+            .text("columns", "q");
+        let resp = self
+            .client
+            .post(format!("{}search/csv/", self.endpoint))
+            .multipart(form)
+            .send()?
+            .error_for_status()?;
+        parse_batch(&resp.bytes()?)
+    }
+
+    fn get<R>(&self, path: &str, query: Vec<(&'static str, String)>) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl Default for BanFrance {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse_batch<T>(data: &[u8]) -> Result<Vec<BanBatchResult<T>>, GeocodingError>
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    let mut reader = csv::Reader::from_reader(data);
+    let results = reader.deserialize().collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+impl<T> Forward<T> for BanFrance
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of an address. Please see [the documentation](https://adresse.data.gouv.fr/outils/api-doc/adresse) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(&BanParams::new(place))?;
+        Ok(res
+            .features
+            .iter()
+            .map(|feature| {
+                Point::new(
+                    feature.geometry.coordinates.0,
+                    feature.geometry.coordinates.1,
+                )
+            })
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for BanFrance
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the `label` of the nearest address.
+    /// Please see [the documentation](https://adresse.data.gouv.fr/outils/api-doc/adresse) for details.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point)?;
+        Ok(res
+            .features
+            .into_iter()
+            .next()
+            .map(|feature| feature.properties.label))
+    }
+}
+
+/// The top-level full GeoJSON response returned by a search or reverse request
+///
+/// See [the documentation](https://adresse.data.gouv.fr/outils/api-doc/adresse) for more details
+///
+///```json
+///{
+///  "type": "FeatureCollection",
+///  "version": "draft",
+///  "features": [
+///    {
+///      "type": "Feature",
+///      "geometry": { "type": "Point", "coordinates": [2.290084, 49.897443] },
+///      "properties": {
+///        "label": "8 Boulevard du Port 80000 Amiens",
+///        "score": 0.49159121588068583,
+///        "housenumber": "8",
+///        "id": "80021_6590_00008",
+///        "type": "housenumber",
+///        "name": "8 Boulevard du Port",
+///        "postcode": "80000",
+///        "citycode": "80021",
+///        "x": 648952.58,
+///        "y": 6977867.25,
+///        "city": "Amiens",
+///        "context": "80, Somme, Hauts-de-France",
+///        "importance": 0.6706612694243868,
+///        "street": "Boulevard du Port"
+///      }
+///    }
+///  ],
+///  "attribution": "BAN",
+///  "licence": "ETALAB-2.0",
+///  "query": "8 bd du port",
+///  "limit": 1
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BanResponse<T>
+where
+    T: Float + Debug,
+{
+    pub r#type: String,
+    pub version: String,
+    pub features: Vec<BanFeature<T>>,
+    pub attribution: String,
+    pub licence: String,
+    pub query: Option<String>,
+    pub limit: Option<u32>,
+}
+
+/// A search or reverse result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BanFeature<T>
+where
+    T: Float + Debug,
+{
+    pub r#type: String,
+    pub geometry: BanGeometry<T>,
+    pub properties: BanProperties,
+}
+
+/// The location of a result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BanGeometry<T>
+where
+    T: Float + Debug,
+{
+    pub r#type: String,
+    pub coordinates: (T, T),
+}
+
+/// The properties of a result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BanProperties {
+    pub label: String,
+    /// How well the result matches the query, from 0 to 1
+    pub score: f64,
+    pub id: String,
+    pub r#type: BanType,
+    pub name: String,
+    pub housenumber: Option<String>,
+    pub street: Option<String>,
+    pub postcode: Option<String>,
+    /// The INSEE code of the municipality
+    pub citycode: String,
+    pub city: String,
+    pub district: Option<String>,
+    /// The department number, department and region names, comma-separated
+    pub context: String,
+    pub importance: Option<f64>,
+    /// The Lambert-93 (EPSG:2154) easting
+    pub x: f64,
+    /// The Lambert-93 (EPSG:2154) northing
+    pub y: f64,
+}
+
+/// A result of a CSV batch request
+///
+/// Empty columns are deserialized as `None`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BanBatchResult<T>
+where
+    T: Float + Debug,
+{
+    /// The input address
+    pub q: String,
+    pub latitude: Option<T>,
+    pub longitude: Option<T>,
+    pub result_label: Option<String>,
+    pub result_score: Option<f64>,
+    pub result_type: Option<BanType>,
+    pub result_id: Option<String>,
+    pub result_housenumber: Option<String>,
+    pub result_name: Option<String>,
+    pub result_street: Option<String>,
+    pub result_postcode: Option<String>,
+    pub result_city: Option<String>,
+    pub result_context: Option<String>,
+    pub result_citycode: Option<String>,
+    /// `ok`, `not-found`, `skipped` or `error`
+    pub result_status: Option<String>,
+}
+
+impl<T> BanBatchResult<T>
+where
+    T: Float + Debug,
+{
+    /// The location of the result, if the address could be geocoded
+    pub fn point(&self) -> Option<Point<T>> {
+        Some(Point::new(self.longitude?, self.latitude?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn response_test() {
+        let res: BanResponse<f64> = serde_json::from_str(
+            r#"{
+                "type": "FeatureCollection",
+                "version": "draft",
+                "features": [
+                    {
+                        "type": "Feature",
+                        "geometry": { "type": "Point", "coordinates": [2.290084, 49.897443] },
+                        "properties": {
+                            "label": "8 Boulevard du Port 80000 Amiens",
+                            "score": 0.49159121588068583,
+                            "housenumber": "8",
+                            "id": "80021_6590_00008",
+                            "type": "housenumber",
+                            "name": "8 Boulevard du Port",
+                            "postcode": "80000",
+                            "citycode": "80021",
+                            "x": 648952.58,
+                            "y": 6977867.25,
+                            "city": "Amiens",
+                            "context": "80, Somme, Hauts-de-France",
+                            "importance": 0.6706612694243868,
+                            "street": "Boulevard du Port"
+                        }
+                    }
+                ],
+                "attribution": "BAN",
+                "licence": "ETALAB-2.0",
+                "query": "8 bd du port",
+                "limit": 1
+            }"#,
+        )
+        .unwrap();
+        let properties = &res.features[0].properties;
+        assert_eq!(properties.r#type, BanType::Housenumber);
+        assert_eq!(properties.citycode, "80021");
+        assert_eq!(properties.housenumber.as_deref(), Some("8"));
+    }
+
+    #[test]
+    fn batch_test() {
+        let csv = "q,latitude,longitude,result_label,result_score,result_score_next,result_type,result_id,result_housenumber,result_name,result_street,result_postcode,result_city,result_context,result_citycode,result_oldcitycode,result_oldcity,result_district,result_status\n\
+            8 bd du port amiens,49.897443,2.290084,8 Boulevard du Port 80000 Amiens,0.96,,housenumber,80021_6590_00008,8,8 Boulevard du Port,Boulevard du Port,80000,Amiens,\"80, Somme, Hauts-de-France\",80021,,,,ok\n\
+            nowhere at all,,,,,,,,,,,,,,,,,,not-found\n";
+        let results: Vec<BanBatchResult<f64>> = parse_batch(csv.as_bytes()).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].point(), Some(Point::new(2.290084, 49.897443)));
+        assert_eq!(results[0].result_type, Some(BanType::Housenumber));
+        assert_eq!(results[1].point(), None);
+        assert_eq!(results[1].result_status.as_deref(), Some("not-found"));
+    }
+}
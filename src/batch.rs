@@ -0,0 +1,182 @@
+//! Batch reverse-geocoding of many points, reported as a [`ReverseMatrix`](struct.ReverseMatrix.html).
+//!
+//! Each entry of the matrix is joined to its input point by index, and records the address
+//! found (if any), the errors encountered along the way and the provider that answered.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Openstreetmap, Point, ReverseMatrix};
+//!
+//! let osm = Openstreetmap::new();
+//! let points = vec![Point::new(2.12870, 41.40139), Point::new(-0.13, 51.52)];
+//! let matrix = ReverseMatrix::from_provider("openstreetmap", &osm, &points);
+//! for entry in matrix.iter() {
+//!     println!("{}: {:?}", entry.index, entry.address);
+//! }
+//! ```
+use crate::GeocodingError;
+use crate::Point;
+use crate::Reverse;
+use num_traits::Float;
+use std::fmt::Debug;
+
+/// The reverse-geocoding outcome for a single input point
+#[derive(Debug)]
+pub struct ReverseMatrixEntry<T>
+where
+    T: Float + Debug,
+{
+    /// The index of the input point
+    pub index: usize,
+    /// The input point
+    pub point: Point<T>,
+    /// The address found for the point, if any
+    pub address: Option<String>,
+    /// The errors returned while resolving the point, in the order the providers were tried
+    pub errors: Vec<GeocodingError>,
+    /// The name of the provider that produced `address`
+    pub provider: Option<String>,
+}
+
+impl<T> ReverseMatrixEntry<T>
+where
+    T: Float + Debug,
+{
+    /// Whether an address was found for this point
+    pub fn is_resolved(&self) -> bool {
+        self.address.is_some()
+    }
+}
+
+/// The result of reverse-geocoding a list of points, with one entry per input point
+#[derive(Debug)]
+pub struct ReverseMatrix<T>
+where
+    T: Float + Debug,
+{
+    pub entries: Vec<ReverseMatrixEntry<T>>,
+}
+
+impl<T> ReverseMatrix<T>
+where
+    T: Float + Debug,
+{
+    /// Reverse-geocode each point using a single provider, identified by `name`
+    pub fn from_provider<R>(name: &str, provider: &R, points: &[Point<T>]) -> Self
+    where
+        R: Reverse<T>,
+    {
+        Self::chained(&[(name, provider as &dyn Reverse<T>)], points)
+    }
+
+    /// Reverse-geocode each point using a chain of providers
+    ///
+    /// Providers are tried in order until one of them returns an address. Errors returned by
+    /// earlier providers are kept in the entry, so a fallback result never hides a failure.
+    pub fn chained(providers: &[(&str, &dyn Reverse<T>)], points: &[Point<T>]) -> Self {
+        let entries = points
+            .iter()
+            .enumerate()
+            .map(|(index, point)| {
+                let mut entry = ReverseMatrixEntry {
+                    index,
+                    point: *point,
+                    address: None,
+                    errors: vec![],
+                    provider: None,
+                };
+                for (name, provider) in providers {
+                    match provider.reverse(point) {
+                        Ok(Some(address)) => {
+                            entry.address = Some(address);
+                            entry.provider = Some(name.to_string());
+                            break;
+                        }
+                        Ok(None) => {}
+                        Err(err) => entry.errors.push(err),
+                    }
+                }
+                entry
+            })
+            .collect();
+        ReverseMatrix { entries }
+    }
+
+    /// The entry for the input point at `index`
+    pub fn get(&self, index: usize) -> Option<&ReverseMatrixEntry<T>> {
+        self.entries.get(index)
+    }
+
+    /// Iterate over all entries, in input order
+    pub fn iter(&self) -> impl Iterator<Item = &ReverseMatrixEntry<T>> {
+        self.entries.iter()
+    }
+
+    /// Iterate over the entries for which no address was found
+    pub fn unresolved(&self) -> impl Iterator<Item = &ReverseMatrixEntry<T>> {
+        self.entries.iter().filter(|entry| !entry.is_resolved())
+    }
+
+    /// The number of entries, which is always the number of input points
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the matrix has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Fixed(Option<&'static str>);
+
+    impl Reverse<f64> for Fixed {
+        fn reverse(&self, _point: &Point<f64>) -> Result<Option<String>, GeocodingError> {
+            Ok(self.0.map(String::from))
+        }
+    }
+
+    struct Failing;
+
+    impl Reverse<f64> for Failing {
+        fn reverse(&self, _point: &Point<f64>) -> Result<Option<String>, GeocodingError> {
+            Err(GeocodingError::Reverse)
+        }
+    }
+
+    #[test]
+    fn from_provider_test() {
+        let points = vec![Point::new(2.12870, 41.40139), Point::new(0.0, 0.0)];
+        let matrix = ReverseMatrix::from_provider("fixed", &Fixed(Some("Barcelona")), &points);
+        assert_eq!(matrix.len(), 2);
+        let entry = matrix.get(1).unwrap();
+        assert_eq!(entry.index, 1);
+        assert_eq!(entry.point, Point::new(0.0, 0.0));
+        assert_eq!(entry.address.as_deref(), Some("Barcelona"));
+        assert_eq!(entry.provider.as_deref(), Some("fixed"));
+    }
+
+    #[test]
+    fn chained_test() {
+        let points = vec![Point::new(2.12870, 41.40139)];
+        let failing = Failing;
+        let empty = Fixed(None);
+        let fallback = Fixed(Some("Barcelona"));
+        let providers: [(&str, &dyn Reverse<f64>); 3] = [
+            ("failing", &failing),
+            ("empty", &empty),
+            ("fallback", &fallback),
+        ];
+        let matrix = ReverseMatrix::chained(&providers, &points);
+        let entry = matrix.get(0).unwrap();
+        assert_eq!(entry.address.as_deref(), Some("Barcelona"));
+        assert_eq!(entry.provider.as_deref(), Some("fallback"));
+        assert_eq!(entry.errors.len(), 1);
+        assert_eq!(matrix.unresolved().count(), 0);
+    }
+}
@@ -0,0 +1,429 @@
+//! The German [BKG](https://gdz.bkg.bund.de/) geocoding provider.
+//!
+//! Geocoding methods are implemented on the [`Bkg`](struct.Bkg.html) struct, using the
+//! `gdz_geokodierung` service of the Federal Agency for Cartography and Geodesy. It covers
+//! every German address, with official municipality keys. Please see the
+//! [service documentation](https://sg.geodatenzentrum.de/web_public/gdz/dokumentation/deu/geokodierungsdienst.pdf) for details.
+//!
+//! Access is granted to federal agencies and licensees, who are given a UUID which is part of
+//! the service URL.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Bkg, Forward, Point};
+//!
+//! let bkg = Bkg::new("your-uuid".to_string());
+//! let address = "Richard-Strauss-Allee 11, Frankfurt am Main";
+//! let res: Result<Vec<Point<f64>>, _> = bkg.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::GeocodingError;
+use crate::InputBounds;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+
+/// An instance of the BKG geocoding service
+pub struct Bkg {
+    uuid: String,
+    client: Client,
+    endpoint: String,
+}
+
+/// The kind of a BKG result
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BkgType {
+    /// An address, down to the house number
+    Haus,
+    /// A street
+    Strasse,
+    /// A postcode area
+    Postleitzahl,
+    /// A locality
+    Ort,
+    /// A part of a locality
+    Ortsteil,
+    /// A municipality
+    Gemeinde,
+    /// A group of municipalities sharing an administration
+    Verwaltungsgemeinschaft,
+    /// A district
+    Kreis,
+    /// A government region
+    Regierungsbezirk,
+    /// A federal state
+    Bundesland,
+    /// A geographical name, e.g. a mountain or a lake
+    Geoname,
+    /// A type added after this crate was released
+    #[serde(other)]
+    Other,
+}
+
+/// An instance of a parameter builder for BKG searches
+pub struct BkgParams<'a, T>
+where
+    T: Float + Debug,
+{
+    query: &'a str,
+    count: Option<u16>,
+    filter: Option<&'a str>,
+    bbox: Option<InputBounds<T>>,
+}
+
+impl<'a, T> BkgParams<'a, T>
+where
+    T: Float + Debug,
+{
+    /// Create a new BKG parameter builder
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::InputBounds;
+    /// use geocoding::bkg::BkgParams;
+    ///
+    /// let bbox = InputBounds::new((8.5, 50.0), (8.8, 50.2));
+    /// let params = BkgParams::new(&"Richard-Strauss-Allee 11")
+    ///     .with_filter(&"typ:haus")
+    ///     .with_bbox(bbox)
+    ///     .with_count(5)
+    ///     .build();
+    /// ```
+    pub fn new(query: &'a str) -> BkgParams<'a, T> {
+        BkgParams {
+            query,
+            count: None,
+            filter: None,
+            bbox: None,
+        }
+    }
+
+    /// Set the `count` property, the maximum number of results to return (10 by default)
+    pub fn with_count(&mut self, count: u16) -> &mut Self {
+        self.count = Some(count);
+        self
+    }
+
+    /// Set the `filter` property, a Lucene filter such as `typ:haus` or `ags:06412000`
+    pub fn with_filter(&mut self, filter: &'a str) -> &mut Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Set the `bbox` property, restricting results to a WGS84 bounding box
+    pub fn with_bbox(&mut self, bbox: InputBounds<T>) -> &mut Self {
+        self.bbox = Some(bbox);
+        self
+    }
+
+    /// Build and return an instance of BkgParams
+    pub fn build(&self) -> BkgParams<'a, T> {
+        BkgParams {
+            query: self.query,
+            count: self.count,
+            filter: self.filter,
+            bbox: self.bbox,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("query", self.query.to_string())];
+        if let Some(count) = self.count {
+            query.push(("count", count.to_string()));
+        }
+        if let Some(filter) = self.filter {
+            query.push(("filter", filter.to_string()));
+        }
+        if let Some(bbox) = self.bbox {
+            query.push(("bbox", String::from(bbox)));
+        }
+        query
+    }
+}
+
+impl Bkg {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "BKG Geokodierungsdienst",
+        docs_url: "https://sg.geodatenzentrum.de/web_public/gdz/dokumentation/deu/geokodierungsdienst.pdf",
+        terms_url: Some("https://gdz.bkg.bund.de/index.php/default/geokodierungsdienst-fur-adressen-und-geonamen-wms-g.html"),
+        attribution: Some("© GeoBasis-DE / BKG"),
+        rate_limit: None,
+    };
+
+    /// Create a new BKG geocoding instance using the default endpoint
+    pub fn new(uuid: String) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Bkg {
+            uuid,
+            client,
+            endpoint: "https://sg.geodatenzentrum.de/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of a BKG geocoding instance
+    ///
+    /// The service path, including the UUID, is appended to the endpoint, which should include
+    /// a trailing slash (i.e. "https://sg.geodatenzentrum.de/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A forward-geocoding lookup of an address, returning a full detailed response
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Bkg;
+    /// use geocoding::bkg::{BkgParams, BkgResponse};
+    ///
+    /// let bkg = Bkg::new("your-uuid".to_string());
+    /// let params = BkgParams::<f64>::new(&"Richard-Strauss-Allee 11, Frankfurt").build();
+    /// let res: Result<BkgResponse<f64>, _> = bkg.forward_full(&params);
+    /// ```
+    pub fn forward_full<T>(&self, params: &BkgParams<T>) -> Result<BkgResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get("geosearch.json", params.as_query())
+    }
+
+    /// A reverse lookup of a point, returning a full detailed response
+    ///
+    /// Results are the addresses closest to the point, nearest first.
+    pub fn reverse_full<T>(&self, point: &Point<T>) -> Result<BkgResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let query = vec![
+            ("lat", point.y().to_f64().unwrap().to_string()),
+            ("lon", point.x().to_f64().unwrap().to_string()),
+        ];
+        self.get("reverse.json", query)
+    }
+
+    fn get<R>(
+        &self,
+        path: &str,
+        mut query: Vec<(&'static str, String)>,
+    ) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        query.push(("srsName", String::from("EPSG:4326")));
+        let resp = self
+            .client
+            .get(format!(
+                "{}gdz_geokodierung__{}/{}",
+                self.endpoint, self.uuid, path
+            ))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl<T> Forward<T> for Bkg
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of an address.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(&BkgParams::new(place))?;
+        Ok(res
+            .features
+            .iter()
+            .map(|feature| {
+                Point::new(
+                    feature.geometry.coordinates.0,
+                    feature.geometry.coordinates.1,
+                )
+            })
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for Bkg
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the `text` of the nearest address.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point)?;
+        Ok(res
+            .features
+            .into_iter()
+            .next()
+            .map(|feature| feature.properties.text))
+    }
+}
+
+/// The top-level full GeoJSON response returned by a search or reverse request
+///
+///```json
+///{
+///  "type": "FeatureCollection",
+///  "features": [
+///    {
+///      "type": "Feature",
+///      "geometry": { "type": "Point", "coordinates": [8.65452, 50.12779] },
+///      "properties": {
+///        "text": "Richard-Strauss-Allee 11, 60598 Frankfurt am Main - Sachsenhausen",
+///        "typ": "Haus",
+///        "score": 1.0,
+///        "bundesland": "Hessen",
+///        "regbezirk": "Darmstadt",
+///        "kreis": "Frankfurt am Main",
+///        "gemeinde": "Frankfurt am Main",
+///        "ort": "Frankfurt am Main",
+///        "ortsteil": "Sachsenhausen",
+///        "strasse": "Richard-Strauss-Allee",
+///        "haus": "11",
+///        "plz": "60598",
+///        "ags": "06412000",
+///        "rs": "064120000000"
+///      }
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BkgResponse<T>
+where
+    T: Float + Debug,
+{
+    pub r#type: String,
+    pub features: Vec<BkgFeature<T>>,
+}
+
+/// A search or reverse result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BkgFeature<T>
+where
+    T: Float + Debug,
+{
+    pub r#type: String,
+    pub geometry: BkgGeometry<T>,
+    pub properties: BkgProperties,
+}
+
+/// The location of a result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BkgGeometry<T>
+where
+    T: Float + Debug,
+{
+    pub r#type: String,
+    pub coordinates: (T, T),
+}
+
+/// The properties of a result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BkgProperties {
+    /// The formatted result
+    pub text: String,
+    pub typ: BkgType,
+    /// How well the result matches the query
+    pub score: Option<f64>,
+    /// The distance from the queried point in metres, for reverse requests
+    pub distance: Option<f64>,
+    pub bundesland: Option<String>,
+    pub regbezirk: Option<String>,
+    pub kreis: Option<String>,
+    pub verwaltungsgemeinschaft: Option<String>,
+    pub gemeinde: Option<String>,
+    pub ort: Option<String>,
+    pub ortsteil: Option<String>,
+    pub strasse: Option<String>,
+    pub haus: Option<String>,
+    pub plz: Option<String>,
+    /// The official municipality key
+    pub ags: Option<Ags>,
+    /// The 12-digit regional key, which also identifies the administrative association
+    pub rs: Option<String>,
+}
+
+/// An official municipality key (Amtlicher Gemeindeschlüssel)
+///
+/// The eight digits encode the state, government region, district and municipality.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Ags(pub String);
+
+impl Ags {
+    /// The two-digit state key, e.g. `06` for Hessen
+    pub fn state(&self) -> Option<&str> {
+        self.0.get(..2)
+    }
+
+    /// The three-digit government region key
+    pub fn region(&self) -> Option<&str> {
+        self.0.get(..3)
+    }
+
+    /// The five-digit district key
+    pub fn district(&self) -> Option<&str> {
+        self.0.get(..5)
+    }
+
+    /// The full eight-digit municipality key
+    pub fn municipality(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn response_test() {
+        let res: BkgResponse<f64> = serde_json::from_str(
+            r#"{
+                "type": "FeatureCollection",
+                "features": [
+                    {
+                        "type": "Feature",
+                        "geometry": { "type": "Point", "coordinates": [8.65452, 50.12779] },
+                        "properties": {
+                            "text": "Richard-Strauss-Allee 11, 60598 Frankfurt am Main - Sachsenhausen",
+                            "typ": "Haus",
+                            "score": 1.0,
+                            "bundesland": "Hessen",
+                            "kreis": "Frankfurt am Main",
+                            "gemeinde": "Frankfurt am Main",
+                            "strasse": "Richard-Strauss-Allee",
+                            "haus": "11",
+                            "plz": "60598",
+                            "ags": "06412000",
+                            "rs": "064120000000"
+                        }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let properties = &res.features[0].properties;
+        assert_eq!(properties.typ, BkgType::Haus);
+        let ags = properties.ags.as_ref().unwrap();
+        assert_eq!(ags.state(), Some("06"));
+        assert_eq!(ags.district(), Some("06412"));
+    }
+}
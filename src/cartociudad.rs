@@ -0,0 +1,342 @@
+//! The Spanish [CartoCiudad](https://www.cartociudad.es/) provider.
+//!
+//! Geocoding methods are implemented on the [`CartoCiudad`](struct.CartoCiudad.html) struct.
+//! CartoCiudad is the official street map of Spain, published by the Instituto Geográfico
+//! Nacional; its geocoding services are free and require no key. Please see the
+//! [API documentation](https://www.cartociudad.es/web/portal/herramientas-calculos/geocodificador) for details.
+//!
+//! Searches are made in two steps: `candidates` returns a list of matching entities, and
+//! `find` resolves a query or a candidate to a single result, down to the portal (house
+//! number) where possible. The `Forward` implementation calls `find` directly.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{CartoCiudad, Forward, Point};
+//!
+//! let cartociudad = CartoCiudad::new();
+//! let address = "Calle General Ibáñez de Ibero 3, Madrid";
+//! let res: Result<Vec<Point<f64>>, _> = cartociudad.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+
+/// An instance of the CartoCiudad geocoding service
+pub struct CartoCiudad {
+    client: Client,
+    endpoint: String,
+}
+
+impl CartoCiudad {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "CartoCiudad",
+        docs_url: "https://www.cartociudad.es/web/portal/herramientas-calculos/geocodificador",
+        terms_url: Some("https://www.ign.es/resources/licencia/Condiciones_licenciaUso_IGN.pdf"),
+        attribution: Some("CartoCiudad CC BY 4.0 ign.es"),
+        rate_limit: None,
+    };
+
+    /// Create a new CartoCiudad geocoding instance using the default endpoint
+    pub fn new() -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        CartoCiudad {
+            client,
+            endpoint: "https://www.cartociudad.es/geocoder/api/geocoder/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of a CartoCiudad geocoding instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://www.cartociudad.es/geocoder/api/geocoder/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// Search for entities matching a query, returning at most `limit` candidates
+    ///
+    /// Candidates may be streets, portals, municipalities, postcodes or place names; they are
+    /// resolved to a location by [`find_candidate`](#method.find_candidate).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::CartoCiudad;
+    /// use geocoding::cartociudad::CartoCiudadResult;
+    ///
+    /// let cartociudad = CartoCiudad::new();
+    /// let res: Result<Vec<CartoCiudadResult<f64>>, _> =
+    ///     cartociudad.candidates("General Ibáñez de Ibero 3", Some(5));
+    /// ```
+    pub fn candidates<T>(
+        &self,
+        query: &str,
+        limit: Option<u16>,
+    ) -> Result<Vec<CartoCiudadResult<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = vec![("q", query.to_string())];
+        if let Some(limit) = limit {
+            query.push(("limit", limit.to_string()));
+        }
+        self.get("candidates", query)
+    }
+
+    /// Find the single best match of a query, returning a full detailed response
+    ///
+    /// Returns `None` if nothing matches.
+    pub fn forward_full<T>(
+        &self,
+        query: &str,
+    ) -> Result<Option<CartoCiudadResult<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let res: CartoCiudadResult<T> = self.get("find", vec![("q", query.to_string())])?;
+        Ok(res.into_found())
+    }
+
+    /// Resolve a candidate returned by [`candidates`](#method.candidates) to its location
+    pub fn find_candidate<T, U>(
+        &self,
+        candidate: &CartoCiudadResult<U>,
+    ) -> Result<Option<CartoCiudadResult<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+        U: Float + Debug,
+    {
+        let mut query = vec![];
+        if let Some(id) = &candidate.id {
+            query.push(("id", id.clone()));
+        }
+        if let Some(r#type) = &candidate.r#type {
+            query.push(("type", r#type.clone()));
+        }
+        if let Some(address) = &candidate.address {
+            query.push(("q", address.clone()));
+        }
+        if let Some(tip_via) = &candidate.tip_via {
+            query.push(("tip_via", tip_via.clone()));
+        }
+        if let Some(portal_number) = candidate.portal_number {
+            query.push(("portal", portal_number.to_string()));
+        }
+        let res: CartoCiudadResult<T> = self.get("find", query)?;
+        Ok(res.into_found())
+    }
+
+    /// A reverse lookup of a point, returning the nearest portal
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+    ) -> Result<Option<CartoCiudadResult<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let query = vec![
+            ("lon", point.x().to_f64().unwrap().to_string()),
+            ("lat", point.y().to_f64().unwrap().to_string()),
+        ];
+        let res: Option<CartoCiudadResult<T>> = self.get("reverseGeocode", query)?;
+        Ok(res.and_then(CartoCiudadResult::into_found))
+    }
+
+    fn get<R>(&self, path: &str, query: Vec<(&'static str, String)>) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl Default for CartoCiudad {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Forward<T> for CartoCiudad
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of the single best match of an address.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(place)?;
+        Ok(res.iter().filter_map(CartoCiudadResult::point).collect())
+    }
+}
+
+impl<T> Reverse<T> for CartoCiudad
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the street and number of the nearest portal,
+    /// followed by its municipality.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point)?;
+        Ok(res.map(|result| result.label()))
+    }
+}
+
+/// A candidate, or the location it resolves to
+///
+/// Candidates don't include coordinates; `find` and reverse results do.
+///
+///```json
+///{
+///  "id": "280790001034",
+///  "province": "Madrid",
+///  "comunidadAutonoma": "Comunidad de Madrid",
+///  "muni": "Madrid",
+///  "type": "portal",
+///  "address": "GENERAL IBAÑEZ DE IBERO",
+///  "postalCode": "28003",
+///  "poblacion": "Madrid",
+///  "geom": "POINT(-3.7099 40.4449)",
+///  "tip_via": "CALLE",
+///  "lat": 40.4449,
+///  "lng": -3.7099,
+///  "portalNumber": 3,
+///  "noNumber": false,
+///  "stateMsg": "Resultado exacto de la búsqueda",
+///  "extension": null,
+///  "state": 1,
+///  "countryCode": "011",
+///  "refCatastral": null
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CartoCiudadResult<T>
+where
+    T: Float + Debug,
+{
+    pub id: Option<String>,
+    pub province: Option<String>,
+    pub comunidad_autonoma: Option<String>,
+    /// The municipality
+    pub muni: Option<String>,
+    /// The kind of entity, e.g. `callejero` (street), `portal`, `Municipio` or `toponimo`
+    pub r#type: Option<String>,
+    /// The street or entity name
+    pub address: Option<String>,
+    pub postal_code: Option<String>,
+    /// The locality
+    pub poblacion: Option<String>,
+    /// The geometry, as WKT
+    pub geom: Option<String>,
+    /// The type of street, e.g. `CALLE` or `AVENIDA`
+    #[serde(rename = "tip_via")]
+    pub tip_via: Option<String>,
+    pub lat: Option<T>,
+    pub lng: Option<T>,
+    pub portal_number: Option<u32>,
+    /// Whether the street has no numbers
+    pub no_number: Option<bool>,
+    /// A description of the match
+    pub state_msg: Option<String>,
+    /// The extension of the portal number, e.g. `BIS`
+    pub extension: Option<String>,
+    /// The kind of match: 1 (exact), 2 (nearest number), 3 (approximate), 4 (street only),
+    /// 5 (cadastral reference), 6 (place name), or 10 (not found)
+    pub state: Option<u8>,
+    pub country_code: Option<String>,
+    pub ref_catastral: Option<String>,
+}
+
+// The `state` of requests which match nothing
+const NOT_FOUND: u8 = 10;
+
+impl<T> CartoCiudadResult<T>
+where
+    T: Float + Debug,
+{
+    /// The location of the result, if resolved
+    pub fn point(&self) -> Option<Point<T>> {
+        Some(Point::new(self.lng?, self.lat?))
+    }
+
+    fn into_found(self) -> Option<Self> {
+        match self.state {
+            Some(NOT_FOUND) => None,
+            _ if self.id.is_none() && self.address.is_none() => None,
+            _ => Some(self),
+        }
+    }
+
+    fn label(&self) -> String {
+        let street = [&self.tip_via, &self.address]
+            .iter()
+            .filter_map(|part| part.as_deref())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let number = self.portal_number.map(|number| number.to_string());
+        [Some(street), number, self.muni.clone()]
+            .iter()
+            .filter_map(|part| part.as_deref())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn find_test() {
+        let res: CartoCiudadResult<f64> = serde_json::from_str(
+            r#"{
+                "id": "280790001034", "province": "Madrid",
+                "comunidadAutonoma": "Comunidad de Madrid", "muni": "Madrid",
+                "type": "portal", "address": "GENERAL IBAÑEZ DE IBERO",
+                "postalCode": "28003", "poblacion": "Madrid",
+                "geom": "POINT(-3.7099 40.4449)", "tip_via": "CALLE",
+                "lat": 40.4449, "lng": -3.7099, "portalNumber": 3, "noNumber": false,
+                "stateMsg": "Resultado exacto de la búsqueda", "extension": null,
+                "state": 1, "countryCode": "011", "refCatastral": null
+            }"#,
+        )
+        .unwrap();
+        let res = res.into_found().unwrap();
+        assert_eq!(res.point(), Some(Point::new(-3.7099, 40.4449)));
+        assert_eq!(res.label(), "CALLE GENERAL IBAÑEZ DE IBERO, 3, Madrid");
+    }
+
+    #[test]
+    fn not_found_test() {
+        let res: CartoCiudadResult<f64> = serde_json::from_str(
+            r#"{ "id": null, "address": null, "state": 10, "stateMsg": "No se ha encontrado" }"#,
+        )
+        .unwrap();
+        assert!(res.into_found().is_none());
+    }
+}
@@ -0,0 +1,370 @@
+//! The Danish [DAWA](https://dawadocs.dataforsyningen.dk/) (Danmarks Adressers Web API) provider.
+//!
+//! Geocoding methods are implemented on the [`Dawa`](struct.Dawa.html) struct. DAWA serves the
+//! official Danish address register, and is free and requires no key. Please see the
+//! [API documentation](https://dawadocs.dataforsyningen.dk/dok/api) for details.
+//!
+//! Denmark distinguishes *adresser* (addresses, down to the floor and door of a unit) from
+//! *adgangsadresser* (access addresses: the entrance of a building, identified by its street
+//! and house number). Forward searches may target either; reverse lookups return the nearest
+//! access address. Results are requested in the flat `mini` structure, with WGS84 coordinates.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Dawa, Forward, Point};
+//!
+//! let dawa = Dawa::new();
+//! let address = "Rentemestervej 8, 2400 København NV";
+//! let res: Result<Vec<Point<f64>>, _> = dawa.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+
+/// An instance of the DAWA geocoding service
+pub struct Dawa {
+    client: Client,
+    endpoint: String,
+}
+
+/// An instance of a parameter builder for DAWA searches
+pub struct DawaParams<'a> {
+    query: &'a str,
+    fuzzy: bool,
+    postnr: Option<&'a str>,
+    kommunekode: Option<&'a str>,
+    per_side: Option<u16>,
+    side: Option<u16>,
+}
+
+impl<'a> DawaParams<'a> {
+    /// Create a new DAWA parameter builder
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::dawa::DawaParams;
+    ///
+    /// let params = DawaParams::new(&"Rentemestervej 8")
+    ///     .with_postnr(&"2400")
+    ///     .with_fuzzy(true)
+    ///     .with_per_side(10)
+    ///     .build();
+    /// ```
+    pub fn new(query: &'a str) -> DawaParams<'a> {
+        DawaParams {
+            query,
+            fuzzy: false,
+            postnr: None,
+            kommunekode: None,
+            per_side: None,
+            side: None,
+        }
+    }
+
+    /// Set the `fuzzy` property, also returning approximate matches
+    pub fn with_fuzzy(&mut self, fuzzy: bool) -> &mut Self {
+        self.fuzzy = fuzzy;
+        self
+    }
+
+    /// Set the `postnr` property, restricting results to a postcode
+    pub fn with_postnr(&mut self, postnr: &'a str) -> &mut Self {
+        self.postnr = Some(postnr);
+        self
+    }
+
+    /// Set the `kommunekode` property, restricting results to a municipality code
+    pub fn with_kommunekode(&mut self, kommunekode: &'a str) -> &mut Self {
+        self.kommunekode = Some(kommunekode);
+        self
+    }
+
+    /// Set the `per_side` property, the number of results per page
+    pub fn with_per_side(&mut self, per_side: u16) -> &mut Self {
+        self.per_side = Some(per_side);
+        self
+    }
+
+    /// Set the `side` property, the page to return, starting from 1
+    pub fn with_side(&mut self, side: u16) -> &mut Self {
+        self.side = Some(side);
+        self
+    }
+
+    /// Build and return an instance of DawaParams
+    pub fn build(&self) -> DawaParams<'a> {
+        DawaParams {
+            query: self.query,
+            fuzzy: self.fuzzy,
+            postnr: self.postnr,
+            kommunekode: self.kommunekode,
+            per_side: self.per_side,
+            side: self.side,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("q", self.query.to_string())];
+        if self.fuzzy {
+            query.push(("fuzzy", String::from("true")));
+        }
+        if let Some(postnr) = self.postnr {
+            query.push(("postnr", postnr.to_string()));
+        }
+        if let Some(kommunekode) = self.kommunekode {
+            query.push(("kommunekode", kommunekode.to_string()));
+        }
+        if let Some(per_side) = self.per_side {
+            query.push(("per_side", per_side.to_string()));
+        }
+        if let Some(side) = self.side {
+            query.push(("side", side.to_string()));
+        }
+        query
+    }
+}
+
+impl Dawa {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "DAWA",
+        docs_url: "https://dawadocs.dataforsyningen.dk/dok/api",
+        terms_url: Some("https://dataforsyningen.dk/asset/PDF/rettigheder_vilkaar/Vilk%C3%A5r%20for%20brug%20af%20frie%20geografiske%20data.pdf"),
+        attribution: Some("Danmarks Adresseregister (DAR)"),
+        rate_limit: None,
+    };
+
+    /// Create a new DAWA geocoding instance using the default endpoint
+    pub fn new() -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Dawa {
+            client,
+            endpoint: "https://api.dataforsyningen.dk/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of a DAWA geocoding instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://api.dataforsyningen.dk/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// Search for addresses, including units within buildings
+    ///
+    /// Please see [the documentation](https://dawadocs.dataforsyningen.dk/dok/api/adresse#s%C3%B8gning) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Dawa;
+    /// use geocoding::dawa::{DawaAddress, DawaParams};
+    ///
+    /// let dawa = Dawa::new();
+    /// let params = DawaParams::new(&"Rentemestervej 8, 2400").build();
+    /// let res: Result<Vec<DawaAddress<f64>>, _> = dawa.addresses(&params);
+    /// ```
+    pub fn addresses<T>(&self, params: &DawaParams) -> Result<Vec<DawaAddress<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get("adresser", params.as_query())
+    }
+
+    /// Search for access addresses
+    ///
+    /// Please see [the documentation](https://dawadocs.dataforsyningen.dk/dok/api/adgangsadresse#s%C3%B8gning) for details.
+    pub fn access_addresses<T>(
+        &self,
+        params: &DawaParams,
+    ) -> Result<Vec<DawaAddress<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get("adgangsadresser", params.as_query())
+    }
+
+    /// Find the access address closest to a point
+    ///
+    /// Please see [the documentation](https://dawadocs.dataforsyningen.dk/dok/api/adgangsadresse#reverse) for details.
+    pub fn reverse_full<T>(&self, point: &Point<T>) -> Result<DawaAddress<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let query = vec![
+            ("x", point.x().to_f64().unwrap().to_string()),
+            ("y", point.y().to_f64().unwrap().to_string()),
+        ];
+        self.get("adgangsadresser/reverse", query)
+    }
+
+    fn get<R>(
+        &self,
+        path: &str,
+        mut query: Vec<(&'static str, String)>,
+    ) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        query.push(("struktur", String::from("mini")));
+        query.push(("srid", String::from("4326")));
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl Default for Dawa {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Forward<T> for Dawa
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding search of addresses.
+    /// Please see [the documentation](https://dawadocs.dataforsyningen.dk/dok/api/adresse#s%C3%B8gning) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.addresses(&DawaParams::new(place))?;
+        Ok(res
+            .iter()
+            .map(|address| Point::new(address.x, address.y))
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for Dawa
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the `betegnelse` of the nearest access address.
+    /// Please see [the documentation](https://dawadocs.dataforsyningen.dk/dok/api/adgangsadresse#reverse) for details.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point)?;
+        Ok(Some(res.betegnelse))
+    }
+}
+
+/// An address or access address, in the `mini` structure
+///
+/// See [the documentation](https://dawadocs.dataforsyningen.dk/dok/api/adresse) for more details
+///
+///```json
+///{
+///  "id": "0a3f50a0-73bf-32b8-e044-0003ba298018",
+///  "status": 1,
+///  "darstatus": 3,
+///  "vejkode": "5804",
+///  "vejnavn": "Rentemestervej",
+///  "adresseringsvejnavn": "Rentemestervej",
+///  "husnr": "8",
+///  "etage": null,
+///  "dør": null,
+///  "supplerendebynavn": null,
+///  "postnr": "2400",
+///  "postnrnavn": "København NV",
+///  "stormodtagerpostnr": null,
+///  "stormodtagerpostnrnavn": null,
+///  "kommunekode": "0101",
+///  "adgangsadresseid": "0a3f507a-b2e6-32b8-e044-0003ba298018",
+///  "x": 12.5308654,
+///  "y": 55.7075395,
+///  "betegnelse": "Rentemestervej 8, 2400 København NV"
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DawaAddress<T>
+where
+    T: Float + Debug,
+{
+    pub id: String,
+    /// The legacy status: 1 (valid) or 3 (preliminary)
+    pub status: u8,
+    /// The status in the address register: 2 (preliminary) or 3 (valid)
+    pub darstatus: Option<u8>,
+    pub vejkode: String,
+    /// The street name
+    pub vejnavn: String,
+    /// The abbreviated street name, used when addressing mail
+    pub adresseringsvejnavn: Option<String>,
+    /// The house number
+    pub husnr: String,
+    /// The floor, for unit addresses
+    pub etage: Option<String>,
+    /// The door, for unit addresses
+    #[serde(rename = "dør")]
+    pub dor: Option<String>,
+    /// An additional locality name
+    pub supplerendebynavn: Option<String>,
+    pub postnr: String,
+    /// The postal district name
+    pub postnrnavn: String,
+    /// The postcode of a large organisation with its own postcode
+    pub stormodtagerpostnr: Option<String>,
+    pub stormodtagerpostnrnavn: Option<String>,
+    /// The municipality code
+    pub kommunekode: String,
+    /// The access address of a unit address
+    pub adgangsadresseid: Option<String>,
+    /// The longitude of the entrance
+    pub x: T,
+    /// The latitude of the entrance
+    pub y: T,
+    /// The full address, formatted on one line
+    pub betegnelse: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn address_test() {
+        let res: Vec<DawaAddress<f64>> = serde_json::from_str(
+            r#"[
+                {
+                    "id": "0a3f50a0-73bf-32b8-e044-0003ba298018",
+                    "status": 1, "darstatus": 3,
+                    "vejkode": "5804", "vejnavn": "Rentemestervej",
+                    "adresseringsvejnavn": "Rentemestervej",
+                    "husnr": "8", "etage": "st", "dør": "tv",
+                    "supplerendebynavn": null,
+                    "postnr": "2400", "postnrnavn": "København NV",
+                    "stormodtagerpostnr": null, "stormodtagerpostnrnavn": null,
+                    "kommunekode": "0101",
+                    "adgangsadresseid": "0a3f507a-b2e6-32b8-e044-0003ba298018",
+                    "x": 12.5308654, "y": 55.7075395,
+                    "betegnelse": "Rentemestervej 8, st. tv, 2400 København NV"
+                }
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(res[0].dor.as_deref(), Some("tv"));
+        assert_eq!(res[0].x, 12.5308654);
+    }
+}
@@ -0,0 +1,337 @@
+//! The [geocode.earth](https://geocode.earth/) provider.
+//!
+//! Geocoding methods are implemented on the [`GeocodeEarth`](struct.GeocodeEarth.html) struct.
+//! geocode.earth is a hosted [Pelias](../pelias/index.html) service, run by the Pelias core
+//! team. It shares Pelias's parameters and responses, and adds structured search and
+//! autocompletion on a commercial API requiring a key. Please see the
+//! [API documentation](https://geocode.earth/docs/) for details.
+//!
+//! Use [`Pelias`](../struct.Pelias.html) for self-hosted instances instead.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, GeocodeEarth, Point};
+//!
+//! let geocode_earth = GeocodeEarth::new("ge-dcba4321".to_string());
+//! let address = "Schwabing, München";
+//! let res: Result<Vec<Point<f64>>, _> = geocode_earth.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::pelias::{PeliasParams, PeliasResponse, PeliasReverseParams};
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+
+/// An instance of the geocode.earth geocoding service
+pub struct GeocodeEarth {
+    client: Client,
+    endpoint: String,
+    api_key: String,
+}
+
+/// An instance of a parameter builder for geocode.earth structured searches
+///
+/// At least one component must be set.
+#[derive(Default)]
+pub struct StructuredParams<'a> {
+    address: Option<&'a str>,
+    neighbourhood: Option<&'a str>,
+    borough: Option<&'a str>,
+    locality: Option<&'a str>,
+    county: Option<&'a str>,
+    region: Option<&'a str>,
+    postalcode: Option<&'a str>,
+    country: Option<&'a str>,
+    size: Option<u8>,
+}
+
+impl<'a> StructuredParams<'a> {
+    /// Create a new structured search parameter builder
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::geocodeearth::StructuredParams;
+    ///
+    /// let params = StructuredParams::new()
+    ///     .with_address("30 West 26th Street")
+    ///     .with_locality("New York")
+    ///     .with_region("NY")
+    ///     .with_country("USA")
+    ///     .build();
+    /// ```
+    pub fn new() -> StructuredParams<'a> {
+        StructuredParams::default()
+    }
+
+    /// Set the `address` property, a house number and street
+    pub fn with_address(&mut self, address: &'a str) -> &mut Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// Set the `neighbourhood` property
+    pub fn with_neighbourhood(&mut self, neighbourhood: &'a str) -> &mut Self {
+        self.neighbourhood = Some(neighbourhood);
+        self
+    }
+
+    /// Set the `borough` property, e.g. `Manhattan`
+    pub fn with_borough(&mut self, borough: &'a str) -> &mut Self {
+        self.borough = Some(borough);
+        self
+    }
+
+    /// Set the `locality` property, a city or town
+    pub fn with_locality(&mut self, locality: &'a str) -> &mut Self {
+        self.locality = Some(locality);
+        self
+    }
+
+    /// Set the `county` property
+    pub fn with_county(&mut self, county: &'a str) -> &mut Self {
+        self.county = Some(county);
+        self
+    }
+
+    /// Set the `region` property, a state or province, by name or abbreviation
+    pub fn with_region(&mut self, region: &'a str) -> &mut Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// Set the `postalcode` property
+    pub fn with_postalcode(&mut self, postalcode: &'a str) -> &mut Self {
+        self.postalcode = Some(postalcode);
+        self
+    }
+
+    /// Set the `country` property, by name or ISO 3166-1 alpha-2 or alpha-3 code
+    pub fn with_country(&mut self, country: &'a str) -> &mut Self {
+        self.country = Some(country);
+        self
+    }
+
+    /// Set the `size` property, the maximum number of results to return
+    pub fn with_size(&mut self, size: u8) -> &mut Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Build and return an instance of StructuredParams
+    pub fn build(&self) -> StructuredParams<'a> {
+        StructuredParams {
+            address: self.address,
+            neighbourhood: self.neighbourhood,
+            borough: self.borough,
+            locality: self.locality,
+            county: self.county,
+            region: self.region,
+            postalcode: self.postalcode,
+            country: self.country,
+            size: self.size,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let components = [
+            ("address", self.address),
+            ("neighbourhood", self.neighbourhood),
+            ("borough", self.borough),
+            ("locality", self.locality),
+            ("county", self.county),
+            ("region", self.region),
+            ("postalcode", self.postalcode),
+            ("country", self.country),
+        ];
+        let mut query: Vec<(&'static str, String)> = components
+            .iter()
+            .filter_map(|(name, value)| value.map(|value| (*name, value.to_string())))
+            .collect();
+        if let Some(size) = self.size {
+            query.push(("size", size.to_string()));
+        }
+        query
+    }
+}
+
+impl GeocodeEarth {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "geocode.earth",
+        docs_url: "https://geocode.earth/docs/",
+        terms_url: Some("https://geocode.earth/terms/"),
+        attribution: Some("https://geocode.earth/guidelines"),
+        rate_limit: None,
+    };
+
+    /// Create a new geocode.earth instance using an API key
+    pub fn new(api_key: String) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        GeocodeEarth {
+            client,
+            endpoint: "https://api.geocode.earth/v1/".to_string(),
+            api_key,
+        }
+    }
+
+    /// Set a custom endpoint of a geocode.earth instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://api.geocode.earth/v1/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A forward-geocoding search of a location, returning a full detailed response
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::GeocodeEarth;
+    /// use geocoding::pelias::{PeliasParams, PeliasResponse};
+    ///
+    /// let geocode_earth = GeocodeEarth::new("ge-dcba4321".to_string());
+    /// let params = PeliasParams::new("Tottenham Court Road")
+    ///     .with_boundary_country("GBR")
+    ///     .build();
+    /// let res: Result<PeliasResponse<f64>, _> = geocode_earth.forward_full(&params);
+    /// ```
+    pub fn forward_full<T>(
+        &self,
+        params: &PeliasParams<T>,
+    ) -> Result<PeliasResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get("search", params.as_query())
+    }
+
+    /// A structured search of an address given by its components, returning a full detailed
+    /// response
+    pub fn structured<T>(
+        &self,
+        params: &StructuredParams,
+    ) -> Result<PeliasResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get("search/structured", params.as_query())
+    }
+
+    /// An autocomplete search for partially typed text, returning a full detailed response
+    pub fn autocomplete<T>(
+        &self,
+        params: &PeliasParams<T>,
+    ) -> Result<PeliasResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get("autocomplete", params.as_query())
+    }
+
+    /// A reverse lookup of a point, returning a full detailed response
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+        params: &PeliasReverseParams,
+    ) -> Result<PeliasResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get("reverse", params.as_query(point))
+    }
+
+    fn get<T>(
+        &self,
+        path: &str,
+        mut query: Vec<(&'static str, String)>,
+    ) -> Result<PeliasResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        query.push(("api_key", self.api_key.clone()));
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: PeliasResponse<T> = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl<T> Forward<T> for GeocodeEarth
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of an address. Please see [the documentation](https://geocode.earth/docs/forward/search/) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(&PeliasParams::new(place))?;
+        Ok(res
+            .features
+            .iter()
+            .map(|feature| {
+                Point::new(
+                    feature.geometry.coordinates.0,
+                    feature.geometry.coordinates.1,
+                )
+            })
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for GeocodeEarth
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the `label` of the nearest result.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point, PeliasReverseParams::new().with_size(1))?;
+        Ok(res
+            .features
+            .first()
+            .and_then(|feature| feature.properties.label.clone()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn structured_params_test() {
+        let params = StructuredParams::new()
+            .with_address("30 West 26th Street")
+            .with_locality("New York")
+            .with_country("USA")
+            .with_size(1)
+            .build();
+        assert_eq!(
+            params.as_query(),
+            vec![
+                ("address", "30 West 26th Street".to_string()),
+                ("locality", "New York".to_string()),
+                ("country", "USA".to_string()),
+                ("size", "1".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,427 @@
+//! The Canadian [GeoGratis](https://geogratis.gc.ca/) provider.
+//!
+//! Geocoding methods are implemented on the [`Geogratis`](struct.Geogratis.html) struct.
+//! Natural Resources Canada publishes two free services which require no key: the Geolocation
+//! Service, which locates civic addresses, street intersections, postal code areas (FSAs),
+//! National Topographic System (NTS) map sheets and place names, and the Geographical Names
+//! Search Service, which searches the Canadian Geographical Names Database. Please see the
+//! [API documentation](https://geogratis.gc.ca/site/eng/api) for details.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, Geogratis, Point};
+//!
+//! let geogratis = Geogratis::new();
+//! let address = "580 Booth Street, Ottawa";
+//! let res: Result<Vec<Point<f64>>, _> = geogratis.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+
+/// An instance of the GeoGratis geolocation and geographical names services
+pub struct Geogratis {
+    client: Client,
+    endpoint: String,
+    lang: &'static str,
+}
+
+/// The kind of a located entity
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LocationKind {
+    /// A civic address
+    Address,
+    /// A street
+    Street,
+    /// A street intersection
+    Intersection,
+    /// A forward sortation area, the first three characters of a postal code
+    Fsa,
+    /// An NTS map sheet
+    Nts,
+    /// A place name
+    Geoname,
+    /// Any other kind, holding the type returned by the service
+    Other(String),
+}
+
+/// An instance of a parameter builder for geographical names searches
+pub struct GeonameParams<'a> {
+    query: &'a str,
+    concise: Option<&'a str>,
+    province: Option<&'a str>,
+    num: Option<u16>,
+}
+
+impl<'a> GeonameParams<'a> {
+    /// Create a new geographical names parameter builder
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::geogratis::GeonameParams;
+    ///
+    /// let params = GeonameParams::new("Moose")
+    ///     .with_concise("LAKE")
+    ///     .with_province("35")
+    ///     .build();
+    /// ```
+    pub fn new(query: &'a str) -> GeonameParams<'a> {
+        GeonameParams {
+            query,
+            concise: None,
+            province: None,
+            num: None,
+        }
+    }
+
+    /// Set the `concise` property, restricting results to a generic type, e.g. `CITY` or `LAKE`
+    pub fn with_concise(&mut self, concise: &'a str) -> &mut Self {
+        self.concise = Some(concise);
+        self
+    }
+
+    /// Set the `province` property, restricting results to a province or territory by its
+    /// two-digit SGC code, e.g. `35` for Ontario
+    pub fn with_province(&mut self, province: &'a str) -> &mut Self {
+        self.province = Some(province);
+        self
+    }
+
+    /// Set the `num` property, the maximum number of results to return
+    pub fn with_num(&mut self, num: u16) -> &mut Self {
+        self.num = Some(num);
+        self
+    }
+
+    /// Build and return an instance of GeonameParams
+    pub fn build(&self) -> GeonameParams<'a> {
+        GeonameParams {
+            query: self.query,
+            concise: self.concise,
+            province: self.province,
+            num: self.num,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("q", self.query.to_string())];
+        if let Some(concise) = self.concise {
+            query.push(("concise", concise.to_string()));
+        }
+        if let Some(province) = self.province {
+            query.push(("province", province.to_string()));
+        }
+        if let Some(num) = self.num {
+            query.push(("num", num.to_string()));
+        }
+        query
+    }
+}
+
+impl Geogratis {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "GeoGratis",
+        docs_url: "https://geogratis.gc.ca/site/eng/api",
+        terms_url: Some("https://open.canada.ca/en/open-government-licence-canada"),
+        attribution: Some(
+            "Contains information licensed under the Open Government Licence – Canada",
+        ),
+        rate_limit: None,
+    };
+
+    /// Create a new GeoGratis instance using the default endpoint, with English results
+    pub fn new() -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Geogratis {
+            client,
+            endpoint: "https://geogratis.gc.ca/services/".to_string(),
+            lang: "en",
+        }
+    }
+
+    /// Set a custom endpoint of a GeoGratis instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://geogratis.gc.ca/services/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// Return results in French rather than English
+    pub fn with_french(mut self) -> Self {
+        self.lang = "fr";
+        self
+    }
+
+    /// Locate an address, intersection, postal code area, NTS map sheet or place name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Geogratis;
+    /// use geocoding::geogratis::Location;
+    ///
+    /// let geogratis = Geogratis::new();
+    /// // An NTS map sheet
+    /// let res: Result<Vec<Location<f64>>, _> = geogratis.locate("31G05");
+    /// ```
+    pub fn locate<T>(&self, query: &str) -> Result<Vec<Location<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let path = format!("geolocation/{}/locate", self.lang);
+        self.get(&path, vec![("q", query.to_string())])
+    }
+
+    /// Search the Canadian Geographical Names Database
+    pub fn geonames<T>(&self, params: &GeonameParams) -> Result<Vec<Geoname<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let path = format!("geoname/{}/geonames", self.lang);
+        let res: GeonamesResponse<T> = self.get(&path, params.as_query())?;
+        Ok(res.items)
+    }
+
+    /// Search for geographical names within `radius` kilometres of a point
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+        radius: u32,
+    ) -> Result<Vec<Geoname<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let path = format!("geoname/{}/geonames", self.lang);
+        let query = vec![
+            ("lat", point.y().to_f64().unwrap().to_string()),
+            ("lon", point.x().to_f64().unwrap().to_string()),
+            ("radius", radius.to_string()),
+        ];
+        let res: GeonamesResponse<T> = self.get(&path, query)?;
+        Ok(res.items)
+    }
+
+    fn get<R>(&self, path: &str, query: Vec<(&'static str, String)>) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl Default for Geogratis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Forward<T> for Geogratis
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup using the Geolocation Service. Please see [the documentation](https://geogratis.gc.ca/site/eng/api) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.locate(place)?;
+        Ok(res.iter().map(Location::point).collect())
+    }
+}
+
+impl<T> Reverse<T> for Geogratis
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the name of the first geographical name within
+    /// 5 kilometres, followed by its province.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point, 5)?;
+        Ok(res.first().map(Geoname::label))
+    }
+}
+
+/// A located entity
+///
+///```json
+///{
+///  "title": "580 Booth Street, Ottawa, Ontario",
+///  "qualifier": "INTERPOLATED_POSITION",
+///  "type": "ca.gc.nrcan.geoloc.data.model.Street",
+///  "bbox": [-75.7069, 45.3961, -75.7069, 45.3961],
+///  "geometry": { "type": "Point", "coordinates": [-75.7069, 45.3961] }
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Location<T>
+where
+    T: Float + Debug,
+{
+    pub title: String,
+    /// How the position was determined, e.g. `INTERPOLATED_POSITION` or `LOCATION`
+    pub qualifier: Option<String>,
+    /// The Java class name of the entity type
+    pub r#type: String,
+    pub bbox: Option<(T, T, T, T)>,
+    pub geometry: LocationGeometry<T>,
+}
+
+impl<T> Location<T>
+where
+    T: Float + Debug,
+{
+    /// The location of the entity
+    pub fn point(&self) -> Point<T> {
+        let (x, y) = self.geometry.coordinates;
+        Point::new(x, y)
+    }
+
+    /// The kind of the entity, parsed from its `type`
+    ///
+    /// Civic addresses are reported as streets with an `INTERPOLATED_POSITION` qualifier.
+    pub fn kind(&self) -> LocationKind {
+        let name = self.r#type.rsplit('.').next().unwrap_or_default();
+        match name {
+            "Street" if self.qualifier.as_deref() == Some("INTERPOLATED_POSITION") => {
+                LocationKind::Address
+            }
+            "Street" => LocationKind::Street,
+            "Intersection" => LocationKind::Intersection,
+            "FSA" => LocationKind::Fsa,
+            "NTS" => LocationKind::Nts,
+            "Geoname" => LocationKind::Geoname,
+            _ => LocationKind::Other(self.r#type.clone()),
+        }
+    }
+}
+
+/// The point geometry of a located entity
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LocationGeometry<T>
+where
+    T: Float + Debug,
+{
+    pub coordinates: (T, T),
+}
+
+#[derive(Deserialize)]
+struct GeonamesResponse<T>
+where
+    T: Float + Debug,
+{
+    #[serde(default = "Vec::new")]
+    items: Vec<Geoname<T>>,
+}
+
+/// A geographical name
+///
+///```json
+///{
+///  "id": "FEUOJ",
+///  "name": "Ottawa",
+///  "location": "Ottawa",
+///  "province": { "code": "35", "term": "Ontario" },
+///  "concise": { "code": "CITY", "term": "City" },
+///  "generic": { "code": "3", "term": "City" },
+///  "latitude": 45.4166,
+///  "longitude": -75.7
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Geoname<T>
+where
+    T: Float + Debug,
+{
+    /// The unique key of the name in the Canadian Geographical Names Database
+    pub id: String,
+    pub name: String,
+    /// A description of where the feature lies
+    pub location: Option<String>,
+    pub province: Option<Term>,
+    /// The generic type of the feature, e.g. `CITY` or `LAKE`
+    pub concise: Option<Term>,
+    pub generic: Option<Term>,
+    pub latitude: T,
+    pub longitude: T,
+}
+
+impl<T> Geoname<T>
+where
+    T: Float + Debug,
+{
+    /// The location of the feature
+    pub fn point(&self) -> Point<T> {
+        Point::new(self.longitude, self.latitude)
+    }
+
+    fn label(&self) -> String {
+        match &self.province {
+            Some(province) => format!("{}, {}", self.name, province.term),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// A coded term
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Term {
+    pub code: String,
+    pub term: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn locate_test() {
+        let res: Vec<Location<f64>> = serde_json::from_str(
+            r#"[{
+                "title": "31G05", "qualifier": "LOCATION",
+                "type": "ca.gc.nrcan.geoloc.data.model.NTS",
+                "bbox": [-76.0, 45.25, -75.5, 45.5],
+                "geometry": { "type": "Point", "coordinates": [-75.75, 45.375] }
+            }]"#,
+        )
+        .unwrap();
+        assert_eq!(res[0].kind(), LocationKind::Nts);
+        assert_eq!(res[0].point(), Point::new(-75.75, 45.375));
+    }
+
+    #[test]
+    fn geonames_test() {
+        let res: GeonamesResponse<f64> = serde_json::from_str(
+            r#"{"items": [{
+                "id": "FEUOJ", "name": "Ottawa", "location": "Ottawa",
+                "province": { "code": "35", "term": "Ontario" },
+                "concise": { "code": "CITY", "term": "City" },
+                "latitude": 45.4166, "longitude": -75.7
+            }]}"#,
+        )
+        .unwrap();
+        assert_eq!(res.items[0].label(), "Ottawa, Ontario");
+    }
+}
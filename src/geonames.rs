@@ -0,0 +1,506 @@
+//! The [GeoNames](https://www.geonames.org/) provider.
+//!
+//! Geocoding methods are implemented on the [`Geonames`](struct.Geonames.html) struct.
+//! GeoNames is a gazetteer of place names rather than addresses: forward geocoding uses the
+//! `searchJSON` web service, reverse geocoding uses `findNearbyPlaceName`, and postal codes can
+//! be looked up using `postalCodeSearchJSON`. Please see the
+//! [API documentation](https://www.geonames.org/export/web-services.html) for details.
+//!
+//! Requests are authenticated with a GeoNames username, which must have the free web services
+//! enabled on its account page. The `demo` account is heavily rate-limited.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, Geonames, Point};
+//!
+//! let geonames = Geonames::new("your-username".to_string());
+//! let address = "Zürich";
+//! let res: Result<Vec<Point<f64>>, _> = geonames.forward(address);
+//! ```
+use crate::deserialize_float;
+use crate::info::{ProviderInfo, RateLimit};
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use serde::de::IgnoredAny;
+use std::fmt::Debug;
+use std::time::Duration;
+
+/// An instance of the GeoNames geocoding service
+pub struct Geonames {
+    username: String,
+    client: Client,
+    endpoint: String,
+}
+
+/// An instance of a parameter builder for GeoNames searches
+///
+/// Results are paginated: `total_results_count` in the response is the number of matches,
+/// and further pages may be requested by increasing the start row by the maximum number of rows.
+pub struct GeonamesParams<'a> {
+    query: &'a str,
+    max_rows: Option<u16>,
+    start_row: Option<u16>,
+    countries: &'a [&'a str],
+    feature_classes: &'a [char],
+    lang: Option<&'a str>,
+}
+
+impl<'a> GeonamesParams<'a> {
+    /// Create a new GeoNames parameter builder
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::geonames::GeonamesParams;
+    ///
+    /// let params = GeonamesParams::new(&"Zürich")
+    ///     .with_countries(&["CH"])
+    ///     .with_feature_classes(&['P'])
+    ///     .with_max_rows(10)
+    ///     .with_start_row(10)
+    ///     .build();
+    /// ```
+    pub fn new(query: &'a str) -> GeonamesParams<'a> {
+        GeonamesParams {
+            query,
+            max_rows: None,
+            start_row: None,
+            countries: &[],
+            feature_classes: &[],
+            lang: None,
+        }
+    }
+
+    /// Set the `maxRows` property, the maximum number of results to return (100 by default, at most 1000)
+    pub fn with_max_rows(&mut self, max_rows: u16) -> &mut Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
+
+    /// Set the `startRow` property, the index of the first result to return (at most 5000)
+    pub fn with_start_row(&mut self, start_row: u16) -> &mut Self {
+        self.start_row = Some(start_row);
+        self
+    }
+
+    /// Set the `country` property, restricting results to ISO 3166-1 alpha-2 country codes
+    pub fn with_countries(&mut self, countries: &'a [&'a str]) -> &mut Self {
+        self.countries = countries;
+        self
+    }
+
+    /// Set the `featureClass` property, restricting results to feature classes, e.g. `P` for populated places
+    pub fn with_feature_classes(&mut self, feature_classes: &'a [char]) -> &mut Self {
+        self.feature_classes = feature_classes;
+        self
+    }
+
+    /// Set the `lang` property, the language of place names
+    pub fn with_lang(&mut self, lang: &'a str) -> &mut Self {
+        self.lang = Some(lang);
+        self
+    }
+
+    /// Build and return an instance of GeonamesParams
+    pub fn build(&self) -> GeonamesParams<'a> {
+        GeonamesParams {
+            query: self.query,
+            max_rows: self.max_rows,
+            start_row: self.start_row,
+            countries: self.countries,
+            feature_classes: self.feature_classes,
+            lang: self.lang,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("q", self.query.to_string())];
+        if let Some(max_rows) = self.max_rows {
+            query.push(("maxRows", max_rows.to_string()));
+        }
+        if let Some(start_row) = self.start_row {
+            query.push(("startRow", start_row.to_string()));
+        }
+        // Both are repeated parameters
+        for country in self.countries {
+            query.push(("country", country.to_string()));
+        }
+        for feature_class in self.feature_classes {
+            query.push(("featureClass", feature_class.to_string()));
+        }
+        if let Some(lang) = self.lang {
+            query.push(("lang", lang.to_string()));
+        }
+        query
+    }
+}
+
+impl Geonames {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "GeoNames",
+        docs_url: "https://www.geonames.org/export/web-services.html",
+        terms_url: Some("https://www.geonames.org/export/"),
+        attribution: Some("GeoNames, CC BY 4.0"),
+        rate_limit: Some(RateLimit {
+            requests: 1000,
+            per: Duration::from_secs(3600),
+        }),
+    };
+
+    /// Create a new GeoNames geocoding instance using the default endpoint
+    pub fn new(username: String) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Geonames {
+            username,
+            client,
+            endpoint: "https://secure.geonames.org/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of a GeoNames geocoding instance, e.g. for premium accounts
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://secure.geonames.org/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A full-text search for place names, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://www.geonames.org/export/geonames-search.html) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Geonames;
+    /// use geocoding::geonames::{GeonamesParams, GeonamesSearchResponse};
+    ///
+    /// let geonames = Geonames::new("your-username".to_string());
+    /// let params = GeonamesParams::new(&"Zürich").with_max_rows(5).build();
+    /// let res: Result<GeonamesSearchResponse<f64>, _> = geonames.forward_full(&params);
+    /// ```
+    pub fn forward_full<T>(
+        &self,
+        params: &GeonamesParams,
+    ) -> Result<GeonamesSearchResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get("searchJSON", params.as_query())?
+            .into_result(GeocodingError::Forward)
+    }
+
+    /// Find the populated places closest to a point, returning a full detailed response
+    ///
+    /// `radius` is in kilometres; at most `max_rows` places are returned, nearest first.
+    ///
+    /// Please see [the documentation](https://www.geonames.org/export/web-services.html#findNearbyPlaceName) for details.
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+        radius: Option<f64>,
+        max_rows: Option<u16>,
+    ) -> Result<GeonamesNearbyResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = vec![
+            ("lat", point.y().to_f64().unwrap().to_string()),
+            ("lng", point.x().to_f64().unwrap().to_string()),
+        ];
+        if let Some(radius) = radius {
+            query.push(("radius", radius.to_string()));
+        }
+        if let Some(max_rows) = max_rows {
+            query.push(("maxRows", max_rows.to_string()));
+        }
+        self.get("findNearbyPlaceNameJSON", query)?
+            .into_result(GeocodingError::Reverse)
+    }
+
+    /// Look up a postal code, optionally restricted to an ISO 3166-1 alpha-2 country code
+    ///
+    /// Please see [the documentation](https://www.geonames.org/export/web-services.html#postalCodeSearch) for details.
+    pub fn postal_code_search<T>(
+        &self,
+        postal_code: &str,
+        country: Option<&str>,
+    ) -> Result<GeonamesPostalCodeResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = vec![("postalcode", postal_code.to_string())];
+        if let Some(country) = country {
+            query.push(("country", country.to_string()));
+        }
+        self.get("postalCodeSearchJSON", query)?
+            .into_result(GeocodingError::Forward)
+    }
+
+    fn get<R>(
+        &self,
+        path: &str,
+        mut query: Vec<(&'static str, String)>,
+    ) -> Result<GeonamesReply<R>, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        query.push(("username", self.username.clone()));
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: GeonamesReply<R> = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl<T> Forward<T> for Geonames
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding search of place names.
+    /// Please see [the documentation](https://www.geonames.org/export/geonames-search.html) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(&GeonamesParams::new(place))?;
+        Ok(res
+            .geonames
+            .iter()
+            .map(|toponym| Point::new(toponym.lng, toponym.lat))
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for Geonames
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the name of the nearest populated place,
+    /// followed by its first-level administrative division and country.
+    /// Please see [the documentation](https://www.geonames.org/export/web-services.html#findNearbyPlaceName) for details.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point, None, Some(1))?;
+        Ok(res.geonames.first().map(Toponym::label))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum GeonamesReply<R> {
+    // Errors are reported with a 200 status and a `status` object, see
+    // https://www.geonames.org/export/webservice-exception.html for the codes
+    Error {
+        #[serde(rename = "status")]
+        _status: IgnoredAny,
+    },
+    Ok(R),
+}
+
+impl<R> GeonamesReply<R> {
+    fn into_result(self, error: GeocodingError) -> Result<R, GeocodingError> {
+        match self {
+            GeonamesReply::Ok(res) => Ok(res),
+            GeonamesReply::Error { .. } => Err(error),
+        }
+    }
+}
+
+/// The top-level full JSON response returned by a `searchJSON` request
+///
+/// See [the documentation](https://www.geonames.org/export/geonames-search.html) for more details
+///
+///```json
+///{
+///  "totalResultsCount": 242,
+///  "geonames": [
+///    {
+///      "adminCode1": "25",
+///      "lng": "8.55",
+///      "geonameId": 2657896,
+///      "toponymName": "Zürich",
+///      "countryId": "2658434",
+///      "fcl": "P",
+///      "population": 341730,
+///      "countryCode": "CH",
+///      "name": "Zurich",
+///      "fclName": "city, village,...",
+///      "adminCodes1": { "ISO3166_2": "ZH" },
+///      "countryName": "Switzerland",
+///      "fcodeName": "seat of a first-order administrative division",
+///      "adminName1": "Zurich",
+///      "lat": "47.36667",
+///      "fcode": "PPLA"
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeonamesSearchResponse<T>
+where
+    T: Float + Debug,
+{
+    /// The total number of matches, across all pages
+    pub total_results_count: u32,
+    pub geonames: Vec<Toponym<T>>,
+}
+
+/// The top-level full JSON response returned by a `findNearbyPlaceName` request
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GeonamesNearbyResponse<T>
+where
+    T: Float + Debug,
+{
+    pub geonames: Vec<Toponym<T>>,
+}
+
+/// A GeoNames place
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Toponym<T>
+where
+    T: Float + Debug,
+{
+    pub geoname_id: u64,
+    /// The name, in the requested language if available
+    pub name: String,
+    /// The main name of the place
+    pub toponym_name: String,
+    #[serde(deserialize_with = "deserialize_float")]
+    pub lat: T,
+    #[serde(deserialize_with = "deserialize_float")]
+    pub lng: T,
+    pub country_code: Option<String>,
+    pub country_name: Option<String>,
+    pub admin_code1: Option<String>,
+    pub admin_name1: Option<String>,
+    /// The feature class, e.g. `P` for populated places
+    pub fcl: Option<String>,
+    /// The feature code, e.g. `PPLC` for capitals
+    pub fcode: Option<String>,
+    pub population: Option<u64>,
+    /// The distance from the queried point in kilometres, for nearby searches
+    pub distance: Option<String>,
+}
+
+impl<T> Toponym<T>
+where
+    T: Float + Debug,
+{
+    fn label(&self) -> String {
+        let mut parts = vec![self.name.as_str()];
+        parts.extend(
+            [&self.admin_name1, &self.country_name]
+                .iter()
+                .filter_map(|part| part.as_deref())
+                .filter(|part| !part.is_empty()),
+        );
+        parts.join(", ")
+    }
+}
+
+/// The top-level full JSON response returned by a `postalCodeSearch` request
+///
+/// See [the documentation](https://www.geonames.org/export/web-services.html#postalCodeSearch) for more details
+///
+///```json
+///{
+///  "postalCodes": [
+///    {
+///      "adminCode2": "112",
+///      "adminCode1": "ZH",
+///      "adminName2": "Bezirk Zürich",
+///      "lng": 8.5385,
+///      "countryCode": "CH",
+///      "postalCode": "8001",
+///      "adminName1": "Kanton Zürich",
+///      "placeName": "Zürich",
+///      "lat": 47.3703
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeonamesPostalCodeResponse<T>
+where
+    T: Float + Debug,
+{
+    pub postal_codes: Vec<PostalCode<T>>,
+}
+
+/// A postal code and the place it serves
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostalCode<T>
+where
+    T: Float + Debug,
+{
+    pub postal_code: String,
+    pub place_name: String,
+    pub country_code: String,
+    #[serde(deserialize_with = "deserialize_float")]
+    pub lat: T,
+    #[serde(deserialize_with = "deserialize_float")]
+    pub lng: T,
+    pub admin_code1: Option<String>,
+    pub admin_name1: Option<String>,
+    pub admin_code2: Option<String>,
+    pub admin_name2: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn search_response_test() {
+        let res: GeonamesReply<GeonamesSearchResponse<f64>> = serde_json::from_str(
+            r#"{
+                "totalResultsCount": 242,
+                "geonames": [
+                    {
+                        "adminCode1": "25", "lng": "8.55", "geonameId": 2657896,
+                        "toponymName": "Zürich", "countryId": "2658434", "fcl": "P",
+                        "population": 341730, "countryCode": "CH", "name": "Zurich",
+                        "adminCodes1": { "ISO3166_2": "ZH" }, "countryName": "Switzerland",
+                        "adminName1": "Zurich", "lat": "47.36667", "fcode": "PPLA"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let res = res.into_result(GeocodingError::Forward).unwrap();
+        assert_eq!(res.total_results_count, 242);
+        assert_eq!(res.geonames[0].lat, 47.36667);
+        assert_eq!(res.geonames[0].label(), "Zurich, Zurich, Switzerland");
+    }
+
+    #[test]
+    fn error_test() {
+        let res: GeonamesReply<GeonamesSearchResponse<f64>> = serde_json::from_str(
+            r#"{ "status": { "message": "user does not exist.", "value": 10 } }"#,
+        )
+        .unwrap();
+        assert!(matches!(res, GeonamesReply::Error { .. }));
+        assert!(res.into_result(GeocodingError::Forward).is_err());
+    }
+}
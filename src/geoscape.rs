@@ -0,0 +1,403 @@
+//! The Australian [Geoscape](https://geoscape.com.au/) provider.
+//!
+//! Geocoding methods are implemented on the [`Geoscape`](struct.Geoscape.html) struct. Geoscape
+//! Australia maintains G-NAF, the Geocoded National Address File, and serves it through its
+//! Predictive API, for address autocompletion, and its Geocoder API, which also geocodes
+//! addresses in batches. Addresses are identified by their G-NAF persistent identifier (PID).
+//! A consumer key from the [Geoscape Hub](https://hub.geoscape.com.au/) is required. Please see
+//! the [API documentation](https://docs.geoscape.com.au/) for details.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, Geoscape, Point};
+//!
+//! let geoscape = Geoscape::new("dcba4321".to_string());
+//! let address = "2 Bond St, Sydney NSW 2000";
+//! let res: Result<Vec<Point<f64>>, _> = geoscape.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use reqwest::header::AUTHORIZATION;
+use std::fmt::Debug;
+
+/// An instance of the Geoscape address services
+pub struct Geoscape {
+    api_key: String,
+    client: Client,
+    endpoint: String,
+}
+
+impl Geoscape {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "Geoscape",
+        docs_url: "https://docs.geoscape.com.au/",
+        terms_url: Some("https://geoscape.com.au/legal/"),
+        attribution: Some("Incorporates or developed using G-NAF © Geoscape Australia"),
+        rate_limit: None,
+    };
+
+    /// Create a new Geoscape instance using a consumer key
+    pub fn new(api_key: String) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Geoscape {
+            api_key,
+            client,
+            endpoint: "https://api.psma.com.au/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of a Geoscape instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://api.psma.com.au/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// Suggest addresses completing partially typed text, using the Predictive API
+    ///
+    /// Suggestions are resolved to a full address by [`address`](#method.address).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Geoscape;
+    /// use geocoding::geoscape::GeoscapeSuggestion;
+    ///
+    /// let geoscape = Geoscape::new("dcba4321".to_string());
+    /// let res: Result<Vec<GeoscapeSuggestion>, _> = geoscape.suggest("2 Bond St Syd", Some(5));
+    /// ```
+    pub fn suggest(
+        &self,
+        query: &str,
+        max_results: Option<u8>,
+    ) -> Result<Vec<GeoscapeSuggestion>, GeocodingError> {
+        let mut params = vec![("query", query.to_string())];
+        if let Some(max_results) = max_results {
+            params.push(("maxNumberOfResults", max_results.to_string()));
+        }
+        let res: SuggestResponse = self.get("v1/predictive/address", params)?;
+        Ok(res.suggest)
+    }
+
+    /// Retrieve an address by its G-NAF PID, using the Predictive API
+    pub fn address<T>(&self, pid: &str) -> Result<GeoscapeFeature<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let res: AddressResponse<T> =
+            self.get(&format!("v1/predictive/address/{}", pid), vec![])?;
+        Ok(res.address)
+    }
+
+    /// A forward-geocoding lookup of an address, returning at most `max_results` matches
+    pub fn forward_full<T>(
+        &self,
+        address: &str,
+        max_results: Option<u8>,
+    ) -> Result<GeoscapeResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut params = vec![("address", address.to_string())];
+        if let Some(max_results) = max_results {
+            params.push(("maxNumberOfResults", max_results.to_string()));
+        }
+        self.get("v2/addresses/geocoder", params)
+    }
+
+    /// Geocode a batch of addresses in a single request
+    ///
+    /// The results are in the order of `addresses`, each holding the matches of one address.
+    /// Please see [the documentation](https://docs.geoscape.com.au/) for the maximum size of a batch.
+    pub fn batch_forward<T>(
+        &self,
+        addresses: &[&str],
+    ) -> Result<Vec<GeoscapeResponse<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let body = BatchRequest {
+            addresses: addresses
+                .iter()
+                .map(|address| BatchAddress { address })
+                .collect(),
+        };
+        let resp = self
+            .client
+            .post(format!("{}v2/addresses/geocoder", self.endpoint))
+            .header(AUTHORIZATION, &self.api_key)
+            .json(&body)
+            .send()?
+            .error_for_status()?;
+        let res: BatchResponse<T> = resp.json()?;
+        Ok(res.results)
+    }
+
+    /// A reverse lookup of a point, returning at most `max_results` nearby addresses
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+        max_results: Option<u8>,
+    ) -> Result<GeoscapeResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut params = vec![
+            ("latitude", point.y().to_f64().unwrap().to_string()),
+            ("longitude", point.x().to_f64().unwrap().to_string()),
+        ];
+        if let Some(max_results) = max_results {
+            params.push(("maxNumberOfResults", max_results.to_string()));
+        }
+        self.get("v2/addresses/reverseGeocoder", params)
+    }
+
+    fn get<R>(&self, path: &str, query: Vec<(&'static str, String)>) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .header(AUTHORIZATION, &self.api_key)
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl<T> Forward<T> for Geoscape
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of an address. Please see [the documentation](https://docs.geoscape.com.au/) for details.
+    ///
+    /// This method passes the `address` parameter to the API.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(place, None)?;
+        Ok(res.features.iter().map(GeoscapeFeature::point).collect())
+    }
+}
+
+impl<T> Reverse<T> for Geoscape
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the formatted address of the nearest address.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point, Some(1))?;
+        Ok(res
+            .features
+            .into_iter()
+            .next()
+            .map(|feature| feature.properties.formatted_address))
+    }
+}
+
+#[derive(Serialize)]
+struct BatchRequest<'a> {
+    addresses: Vec<BatchAddress<'a>>,
+}
+
+#[derive(Serialize)]
+struct BatchAddress<'a> {
+    address: &'a str,
+}
+
+#[derive(Deserialize)]
+struct BatchResponse<T>
+where
+    T: Float + Debug,
+{
+    #[serde(default = "Vec::new")]
+    results: Vec<GeoscapeResponse<T>>,
+}
+
+#[derive(Deserialize)]
+struct SuggestResponse {
+    #[serde(default = "Vec::new")]
+    suggest: Vec<GeoscapeSuggestion>,
+}
+
+#[derive(Deserialize)]
+struct AddressResponse<T>
+where
+    T: Float + Debug,
+{
+    address: GeoscapeFeature<T>,
+}
+
+/// An address suggested by the Predictive API
+///
+///```json
+///{
+///  "address": "2 BOND ST, SYDNEY NSW 2000",
+///  "id": "GANSW704071394",
+///  "rank": 0
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GeoscapeSuggestion {
+    pub address: String,
+    /// The G-NAF PID of the address
+    pub id: String,
+    pub rank: u32,
+}
+
+/// The matches of an address, as a GeoJSON feature collection
+///
+///```json
+///{
+///  "type": "FeatureCollection",
+///  "features": [
+///    {
+///      "type": "Feature",
+///      "geometry": { "type": "Point", "coordinates": [151.20787, -33.86463] },
+///      "properties": {
+///        "addressId": "GANSW704071394",
+///        "formattedAddress": "2 BOND ST, SYDNEY NSW 2000",
+///        "buildingName": null,
+///        "lotIdentifier": null,
+///        "flatType": null,
+///        "flatNumber": null,
+///        "levelType": null,
+///        "levelNumber": null,
+///        "streetNumber1": "2",
+///        "streetNumber2": null,
+///        "streetName": "BOND",
+///        "streetType": "ST",
+///        "streetSuffix": null,
+///        "localityName": "SYDNEY",
+///        "stateTerritory": "NSW",
+///        "postcode": "2000",
+///        "geocodeType": "FRONTAGE CENTRE SETBACK",
+///        "matchQuality": 1.0
+///      }
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GeoscapeResponse<T>
+where
+    T: Float + Debug,
+{
+    #[serde(default = "Vec::new")]
+    pub features: Vec<GeoscapeFeature<T>>,
+}
+
+/// An address
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GeoscapeFeature<T>
+where
+    T: Float + Debug,
+{
+    pub geometry: GeoscapeGeometry<T>,
+    pub properties: GeoscapeAddress,
+}
+
+impl<T> GeoscapeFeature<T>
+where
+    T: Float + Debug,
+{
+    /// The location of the address
+    pub fn point(&self) -> Point<T> {
+        let (x, y) = self.geometry.coordinates;
+        Point::new(x, y)
+    }
+}
+
+/// The point geometry of an address
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GeoscapeGeometry<T>
+where
+    T: Float + Debug,
+{
+    pub coordinates: (T, T),
+}
+
+/// The components of an address
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeoscapeAddress {
+    /// The G-NAF PID of the address
+    pub address_id: String,
+    pub formatted_address: String,
+    pub building_name: Option<String>,
+    pub lot_identifier: Option<String>,
+    /// The kind of flat, e.g. `UNIT` or `APARTMENT`
+    pub flat_type: Option<String>,
+    pub flat_number: Option<String>,
+    /// The kind of level, e.g. `LEVEL` or `GROUND`
+    pub level_type: Option<String>,
+    pub level_number: Option<String>,
+    /// The first number of the address
+    pub street_number1: Option<String>,
+    /// The last number of the address, for ranges
+    pub street_number2: Option<String>,
+    pub street_name: Option<String>,
+    /// The abbreviated street type, e.g. `ST` or `RD`
+    pub street_type: Option<String>,
+    pub street_suffix: Option<String>,
+    pub locality_name: Option<String>,
+    /// The abbreviated state or territory, e.g. `NSW`
+    pub state_territory: Option<String>,
+    pub postcode: Option<String>,
+    /// How the address was located, e.g. `FRONTAGE CENTRE SETBACK` or `PROPERTY CENTROID`
+    pub geocode_type: Option<String>,
+    /// The quality of a match, between 0 and 1; absent from Predictive API results
+    pub match_quality: Option<f64>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn address_test() {
+        let res: AddressResponse<f64> = serde_json::from_str(
+            r#"{
+                "address": {
+                    "type": "Feature",
+                    "geometry": { "type": "Point", "coordinates": [151.20787, -33.86463] },
+                    "properties": {
+                        "addressId": "GANSW704071394",
+                        "formattedAddress": "2 BOND ST, SYDNEY NSW 2000",
+                        "streetNumber1": "2", "streetName": "BOND", "streetType": "ST",
+                        "localityName": "SYDNEY", "stateTerritory": "NSW", "postcode": "2000",
+                        "geocodeType": "FRONTAGE CENTRE SETBACK"
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(res.address.properties.address_id, "GANSW704071394");
+        assert_eq!(
+            res.address.properties.state_territory.as_deref(),
+            Some("NSW")
+        );
+        assert_eq!(res.address.point(), Point::new(151.20787, -33.86463));
+    }
+}
@@ -0,0 +1,462 @@
+//! The IGN [Géoplateforme](https://geoservices.ign.fr/documentation/services/services-geoplateforme/geocodage) geocoding provider.
+//!
+//! Geocoding methods are implemented on the [`IgnFrance`](struct.IgnFrance.html) struct. The
+//! Géoplateforme geocoder, run by the French national mapping agency, searches three indexes:
+//! addresses (from the [Base Adresse Nationale](../banfrance/index.html)), points of interest
+//! (from the BD TOPO), and cadastral parcels. It is free and requires no key. Please see the
+//! [API documentation](https://data.geopf.fr/geocodage/openapi) for details.
+//!
+//! Address results share their properties with the [`BanFrance`](../banfrance/struct.BanFrance.html)
+//! provider, which remains the better choice for batch geocoding of addresses.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, IgnFrance, Point};
+//!
+//! let ign = IgnFrance::new();
+//! let address = "73 avenue de Paris, Saint-Mandé";
+//! let res: Result<Vec<Point<f64>>, _> = ign.forward(address);
+//! ```
+use crate::banfrance::{BanGeometry, BanProperties};
+use crate::info::{ProviderInfo, RateLimit};
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+
+/// An instance of the Géoplateforme geocoding service
+pub struct IgnFrance {
+    client: Client,
+    endpoint: String,
+}
+
+/// The indexes which may be searched
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IgnIndex {
+    /// Addresses, the default
+    Address,
+    /// Points of interest, including administrative units and place names
+    Poi,
+    /// Cadastral parcels
+    Parcel,
+}
+
+impl IgnIndex {
+    fn as_str(self) -> &'static str {
+        match self {
+            IgnIndex::Address => "address",
+            IgnIndex::Poi => "poi",
+            IgnIndex::Parcel => "parcel",
+        }
+    }
+}
+
+/// An instance of a parameter builder for Géoplateforme searches
+///
+/// Parcels are usually searched by their identifiers rather than a free-text query, using
+/// filters such as `departmentcode`, `municipalitycode`, `section` and `number`.
+pub struct IgnParams<'a, T>
+where
+    T: Float + Debug,
+{
+    query: Option<&'a str>,
+    indexes: &'a [IgnIndex],
+    limit: Option<u8>,
+    filters: &'a [(&'a str, &'a str)],
+    bias: Option<&'a Point<T>>,
+}
+
+impl<'a, T> IgnParams<'a, T>
+where
+    T: Float + Debug,
+{
+    /// Create a new Géoplateforme parameter builder
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::ignfrance::{IgnIndex, IgnParams};
+    ///
+    /// let params = IgnParams::<f64>::new()
+    ///     .with_indexes(&[IgnIndex::Parcel])
+    ///     .with_filters(&[
+    ///         ("departmentcode", "94"),
+    ///         ("municipalitycode", "067"),
+    ///         ("section", "AB"),
+    ///         ("number", "0042"),
+    ///     ])
+    ///     .build();
+    /// ```
+    pub fn new() -> IgnParams<'a, T> {
+        IgnParams {
+            query: None,
+            indexes: &[],
+            limit: None,
+            filters: &[],
+            bias: None,
+        }
+    }
+
+    /// Set the `q` property, the free-text query
+    pub fn with_query(&mut self, query: &'a str) -> &mut Self {
+        self.query = Some(query);
+        self
+    }
+
+    /// Set the `index` property, the indexes to search
+    pub fn with_indexes(&mut self, indexes: &'a [IgnIndex]) -> &mut Self {
+        self.indexes = indexes;
+        self
+    }
+
+    /// Set the `limit` property, the maximum number of results to return
+    pub fn with_limit(&mut self, limit: u8) -> &mut Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Add filters on result properties, e.g. `("postcode", "94160")` or `("category", "mairie")`
+    pub fn with_filters(&mut self, filters: &'a [(&'a str, &'a str)]) -> &mut Self {
+        self.filters = filters;
+        self
+    }
+
+    /// Set the `lat` and `lon` properties, favouring results close to a point
+    pub fn with_bias(&mut self, bias: &'a Point<T>) -> &mut Self {
+        self.bias = Some(bias);
+        self
+    }
+
+    /// Build and return an instance of IgnParams
+    pub fn build(&self) -> IgnParams<'a, T> {
+        IgnParams {
+            query: self.query,
+            indexes: self.indexes,
+            limit: self.limit,
+            filters: self.filters,
+            bias: self.bias,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![];
+        if let Some(q) = self.query {
+            query.push(("q", q.to_string()));
+        }
+        if !self.indexes.is_empty() {
+            let indexes: Vec<_> = self.indexes.iter().map(|index| index.as_str()).collect();
+            query.push(("index", indexes.join(",")));
+        }
+        if let Some(limit) = self.limit {
+            query.push(("limit", limit.to_string()));
+        }
+        if let Some(bias) = self.bias {
+            query.push(("lat", bias.y().to_f64().unwrap().to_string()));
+            query.push(("lon", bias.x().to_f64().unwrap().to_string()));
+        }
+        query
+    }
+}
+
+impl<'a, T> Default for IgnParams<'a, T>
+where
+    T: Float + Debug,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IgnFrance {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "IGN Géoplateforme",
+        docs_url: "https://data.geopf.fr/geocodage/openapi",
+        terms_url: Some("https://geoservices.ign.fr/cgu-licences"),
+        attribution: Some("IGN, Licence Ouverte 2.0"),
+        rate_limit: Some(RateLimit::per_second(50)),
+    };
+
+    /// Create a new Géoplateforme geocoding instance using the default endpoint
+    pub fn new() -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        IgnFrance {
+            client,
+            endpoint: "https://data.geopf.fr/geocodage/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of a Géoplateforme geocoding instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://data.geopf.fr/geocodage/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A forward-geocoding search, returning a full detailed response
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::IgnFrance;
+    /// use geocoding::ignfrance::{IgnIndex, IgnParams, IgnResponse};
+    ///
+    /// let ign = IgnFrance::new();
+    /// let params = IgnParams::<f64>::new()
+    ///     .with_query(&"mairie Saint-Mandé")
+    ///     .with_indexes(&[IgnIndex::Poi])
+    ///     .build();
+    /// let res: Result<IgnResponse<f64>, _> = ign.forward_full(&params);
+    /// ```
+    pub fn forward_full<T>(&self, params: &IgnParams<T>) -> Result<IgnResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = params.as_query();
+        query.extend(params.filters.iter().map(|&(k, v)| (k, v.to_string())));
+        self.get("search", &query)
+    }
+
+    /// A reverse lookup of a point in the given indexes, returning a full detailed response
+    ///
+    /// Parcel lookups return the parcel containing the point.
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+        indexes: &[IgnIndex],
+    ) -> Result<IgnResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = vec![
+            ("lon", point.x().to_f64().unwrap().to_string()),
+            ("lat", point.y().to_f64().unwrap().to_string()),
+        ];
+        if !indexes.is_empty() {
+            let indexes: Vec<_> = indexes.iter().map(|index| index.as_str()).collect();
+            query.push(("index", indexes.join(",")));
+        }
+        self.get("reverse", &query)
+    }
+
+    fn get<R>(&self, path: &str, query: &[(&str, String)]) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl Default for IgnFrance {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Forward<T> for IgnFrance
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding search of addresses.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(&IgnParams::new().with_query(place).build())?;
+        Ok(res
+            .features
+            .iter()
+            .map(|feature| {
+                Point::new(
+                    feature.geometry.coordinates.0,
+                    feature.geometry.coordinates.1,
+                )
+            })
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for IgnFrance
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the `label` of the nearest address.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point, &[IgnIndex::Address])?;
+        Ok(res
+            .features
+            .into_iter()
+            .find_map(|feature| match feature.properties {
+                IgnProperties::Address(address) => Some(address.label),
+                _ => None,
+            }))
+    }
+}
+
+/// The top-level full GeoJSON response returned by a search or reverse request
+///
+///```json
+///{
+///  "type": "FeatureCollection",
+///  "features": [
+///    {
+///      "type": "Feature",
+///      "geometry": { "type": "Point", "coordinates": [2.4249, 48.8456] },
+///      "properties": {
+///        "_type": "parcel",
+///        "id": "94067000AB0042",
+///        "departmentcode": "94",
+///        "municipalitycode": "067",
+///        "oldmunicipalitycode": "000",
+///        "districtcode": "",
+///        "section": "AB",
+///        "sheet": "1",
+///        "number": "0042",
+///        "city": "Saint-Mandé",
+///        "score": 1
+///      }
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IgnResponse<T>
+where
+    T: Float + Debug,
+{
+    pub r#type: String,
+    pub features: Vec<IgnFeature<T>>,
+}
+
+/// A search or reverse result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IgnFeature<T>
+where
+    T: Float + Debug,
+{
+    pub r#type: String,
+    /// The location of the result; the centroid of parcels
+    pub geometry: BanGeometry<T>,
+    pub properties: IgnProperties,
+}
+
+/// The properties of a result, depending on its index
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "_type", rename_all = "lowercase")]
+pub enum IgnProperties {
+    Address(BanProperties),
+    Poi(PoiProperties),
+    Parcel(ParcelProperties),
+}
+
+/// The properties of a point of interest
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PoiProperties {
+    /// The names of the point of interest, the first being the main one
+    pub names: Vec<String>,
+    pub toponym: Option<String>,
+    /// Its categories, e.g. `mairie` or `commune`
+    pub category: Vec<String>,
+    pub postcode: Vec<String>,
+    /// The INSEE codes of the municipalities it lies in
+    pub citycode: Vec<String>,
+    pub city: Vec<String>,
+    pub score: Option<f64>,
+    /// Additional properties, depending on the category
+    pub extrafields: Option<serde_json::Value>,
+}
+
+/// The properties of a cadastral parcel
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ParcelProperties {
+    /// The 14-character parcel identifier
+    pub id: String,
+    pub departmentcode: String,
+    pub municipalitycode: String,
+    /// The code of the former municipality, for merged municipalities
+    pub oldmunicipalitycode: Option<String>,
+    /// The arrondissement, in Paris, Lyon and Marseille
+    pub districtcode: Option<String>,
+    pub section: String,
+    pub sheet: Option<String>,
+    pub number: String,
+    pub city: Option<String>,
+    pub score: Option<f64>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn indexes_test() {
+        let res: IgnResponse<f64> = serde_json::from_str(
+            r#"{
+                "type": "FeatureCollection",
+                "features": [
+                    {
+                        "type": "Feature",
+                        "geometry": { "type": "Point", "coordinates": [2.4249, 48.8456] },
+                        "properties": {
+                            "_type": "parcel", "id": "94067000AB0042",
+                            "departmentcode": "94", "municipalitycode": "067",
+                            "oldmunicipalitycode": "000", "districtcode": "",
+                            "section": "AB", "sheet": "1", "number": "0042",
+                            "city": "Saint-Mandé", "score": 1
+                        }
+                    },
+                    {
+                        "type": "Feature",
+                        "geometry": { "type": "Point", "coordinates": [2.4189, 48.8421] },
+                        "properties": {
+                            "_type": "poi", "names": ["Mairie de Saint-Mandé"],
+                            "toponym": "Mairie", "category": ["mairie"],
+                            "postcode": ["94160"], "citycode": ["94067"],
+                            "city": ["Saint-Mandé"], "score": 0.9
+                        }
+                    },
+                    {
+                        "type": "Feature",
+                        "geometry": { "type": "Point", "coordinates": [2.4248, 48.8455] },
+                        "properties": {
+                            "_type": "address",
+                            "label": "73 Avenue de Paris 94160 Saint-Mandé",
+                            "score": 0.97, "housenumber": "73", "id": "94067_0180_00073",
+                            "type": "housenumber", "name": "73 Avenue de Paris",
+                            "postcode": "94160", "citycode": "94067",
+                            "x": 656498.72, "y": 6860790.13, "city": "Saint-Mandé",
+                            "context": "94, Val-de-Marne, Île-de-France",
+                            "importance": 0.6, "street": "Avenue de Paris"
+                        }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(
+            matches!(&res.features[0].properties, IgnProperties::Parcel(parcel) if parcel.section == "AB")
+        );
+        assert!(matches!(&res.features[1].properties, IgnProperties::Poi(_)));
+        assert!(
+            matches!(&res.features[2].properties, IgnProperties::Address(address) if address.citycode == "94067")
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! Machine-readable provider metadata.
+//!
+//! Each provider exposes an `INFO` constant describing the service: its name, where its
+//! documentation and terms of use live, the attribution its terms require when displaying
+//! results, and the default rate limit of its free (or public) tier. Applications using several
+//! providers can use it to render the correct attribution for each result.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::Openstreetmap;
+//!
+//! let info = Openstreetmap::INFO;
+//! assert_eq!(info.name, "Nominatim");
+//! assert_eq!(info.attribution, Some("© OpenStreetMap contributors"));
+//! ```
+use std::time::Duration;
+
+/// Metadata describing a geocoding provider
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ProviderInfo {
+    /// The name of the service
+    pub name: &'static str,
+    /// The API documentation
+    pub docs_url: &'static str,
+    /// The terms of use, for hosted services
+    pub terms_url: Option<&'static str>,
+    /// The attribution required when displaying results, if any
+    pub attribution: Option<&'static str>,
+    /// The default rate limit, if the service enforces one
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// A maximum number of requests per period of time
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub per: Duration,
+}
+
+impl RateLimit {
+    /// The rate limit of `requests` requests per second
+    pub const fn per_second(requests: u32) -> Self {
+        RateLimit {
+            requests,
+            per: Duration::from_secs(1),
+        }
+    }
+
+    /// The minimum delay between two consecutive requests respecting the limit
+    pub fn min_interval(&self) -> Duration {
+        self.per / self.requests.max(1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn min_interval_test() {
+        assert_eq!(
+            RateLimit::per_second(2).min_interval(),
+            Duration::from_millis(500)
+        );
+        let per_minute = RateLimit {
+            requests: 600,
+            per: Duration::from_secs(60),
+        };
+        assert_eq!(per_minute.min_interval(), Duration::from_millis(100));
+    }
+}
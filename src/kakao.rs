@@ -0,0 +1,455 @@
+//! The [Kakao Local](https://developers.kakao.com/docs/latest/en/local/common) provider.
+//!
+//! Geocoding methods are implemented on the [`Kakao`](struct.Kakao.html) struct, using the
+//! address search and `coord2address` endpoints of the Kakao Local REST API.
+//! A REST API key is required.
+//!
+//! Korean addresses come in two forms: a road name address (도로명 주소), and a land-lot
+//! address (지번 주소) based on cadastral parcels. Kakao returns both where available, as
+//! [`RoadAddress`](struct.RoadAddress.html) and [`LandLotAddress`](struct.LandLotAddress.html).
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, Kakao, Point};
+//!
+//! let kakao = Kakao::new("your-rest-api-key".to_string());
+//! let address = "전북 삼성동 100";
+//! let res: Result<Vec<Point<f64>>, _> = kakao.forward(address);
+//! ```
+use crate::deserialize_float;
+use crate::info::ProviderInfo;
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use reqwest::header::AUTHORIZATION;
+use std::fmt::Debug;
+
+/// An instance of the Kakao Local geocoding service
+pub struct Kakao {
+    api_key: String,
+    client: Client,
+    endpoint: String,
+}
+
+/// How Kakao matches the query against addresses
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AnalyzeType {
+    /// Return addresses similar to the query (the default)
+    Similar,
+    /// Only return addresses exactly matching the query
+    Exact,
+}
+
+/// An instance of a parameter builder for Kakao address searches
+pub struct KakaoParams<'a> {
+    query: &'a str,
+    analyze_type: Option<AnalyzeType>,
+    page: Option<u8>,
+    size: Option<u8>,
+}
+
+impl<'a> KakaoParams<'a> {
+    /// Create a new Kakao parameter builder
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::kakao::{AnalyzeType, KakaoParams};
+    ///
+    /// let params = KakaoParams::new(&"전북 삼성동 100")
+    ///     .with_analyze_type(AnalyzeType::Exact)
+    ///     .with_size(5)
+    ///     .build();
+    /// ```
+    pub fn new(query: &'a str) -> KakaoParams<'a> {
+        KakaoParams {
+            query,
+            analyze_type: None,
+            page: None,
+            size: None,
+        }
+    }
+
+    /// Set the `analyze_type` property
+    pub fn with_analyze_type(&mut self, analyze_type: AnalyzeType) -> &mut Self {
+        self.analyze_type = Some(analyze_type);
+        self
+    }
+
+    /// Set the `page` property, from 1 to 45
+    pub fn with_page(&mut self, page: u8) -> &mut Self {
+        self.page = Some(page);
+        self
+    }
+
+    /// Set the `size` property, the number of results per page, from 1 to 30
+    pub fn with_size(&mut self, size: u8) -> &mut Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Build and return an instance of KakaoParams
+    pub fn build(&self) -> KakaoParams<'a> {
+        KakaoParams {
+            query: self.query,
+            analyze_type: self.analyze_type,
+            page: self.page,
+            size: self.size,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("query", self.query.to_string())];
+        if let Some(analyze_type) = self.analyze_type {
+            let analyze_type = match analyze_type {
+                AnalyzeType::Similar => "similar",
+                AnalyzeType::Exact => "exact",
+            };
+            query.push(("analyze_type", analyze_type.to_string()));
+        }
+        if let Some(page) = self.page {
+            query.push(("page", page.to_string()));
+        }
+        if let Some(size) = self.size {
+            query.push(("size", size.to_string()));
+        }
+        query
+    }
+}
+
+impl Kakao {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "Kakao Local",
+        docs_url: "https://developers.kakao.com/docs/latest/en/local/dev-guide",
+        terms_url: Some("https://developers.kakao.com/terms/latest/en/site-terms"),
+        attribution: Some("© Kakao"),
+        rate_limit: None,
+    };
+
+    /// Create a new Kakao geocoding instance using the default endpoint
+    pub fn new(api_key: String) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Kakao {
+            api_key,
+            client,
+            endpoint: "https://dapi.kakao.com/v2/local/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of a Kakao geocoding instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://dapi.kakao.com/v2/local/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// An address search, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://developers.kakao.com/docs/latest/en/local/dev-guide#address-coord) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Kakao;
+    /// use geocoding::kakao::{KakaoParams, KakaoResponse};
+    ///
+    /// let kakao = Kakao::new("your-rest-api-key".to_string());
+    /// let params = KakaoParams::new(&"전북 삼성동 100").build();
+    /// let res: Result<KakaoResponse<f64>, _> = kakao.forward_full(&params);
+    /// ```
+    pub fn forward_full<T>(&self, params: &KakaoParams) -> Result<KakaoResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get("search/address.json", params.as_query())
+    }
+
+    /// A reverse lookup of a WGS84 point, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://developers.kakao.com/docs/latest/en/local/dev-guide#coord-to-address) for details.
+    pub fn reverse_full<T>(&self, point: &Point<T>) -> Result<KakaoReverseResponse, GeocodingError>
+    where
+        T: Float + Debug,
+    {
+        let query = vec![
+            ("x", point.x().to_f64().unwrap().to_string()),
+            ("y", point.y().to_f64().unwrap().to_string()),
+            ("input_coord", String::from("WGS84")),
+        ];
+        self.get("geo/coord2address.json", query)
+    }
+
+    fn get<R>(&self, path: &str, query: Vec<(&'static str, String)>) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .header(AUTHORIZATION, format!("KakaoAK {}", self.api_key))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl<T> Forward<T> for Kakao
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// An address search. Please see [the documentation](https://developers.kakao.com/docs/latest/en/local/dev-guide#address-coord) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(&KakaoParams::new(place))?;
+        Ok(res
+            .documents
+            .iter()
+            .map(|document| Point::new(document.x, document.y))
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for Kakao
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the road name address if there is one,
+    /// and the land-lot address otherwise.
+    /// Please see [the documentation](https://developers.kakao.com/docs/latest/en/local/dev-guide#coord-to-address) for details.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point)?;
+        Ok(res.documents.into_iter().next().and_then(|document| {
+            let ReverseDocument {
+                address,
+                road_address,
+            } = document;
+            road_address
+                .map(|address| address.address_name)
+                .or_else(|| address.map(|address| address.address_name))
+        }))
+    }
+}
+
+/// The top-level full JSON response returned by an address search
+///
+/// See [the documentation](https://developers.kakao.com/docs/latest/en/local/dev-guide#address-coord-response) for more details
+///
+///```json
+///{
+///  "meta": { "total_count": 1, "pageable_count": 1, "is_end": true },
+///  "documents": [
+///    {
+///      "address_name": "전북 익산시 부송동 100",
+///      "address_type": "REGION_ADDR",
+///      "x": "126.99597295767953",
+///      "y": "35.97664845766847",
+///      "address": {
+///        "address_name": "전북 익산시 부송동 100",
+///        "region_1depth_name": "전북",
+///        "region_2depth_name": "익산시",
+///        "region_3depth_name": "부송동",
+///        "region_3depth_h_name": "삼성동",
+///        "h_code": "4514069000",
+///        "b_code": "4514013400",
+///        "mountain_yn": "N",
+///        "main_address_no": "100",
+///        "sub_address_no": ""
+///      },
+///      "road_address": {
+///        "address_name": "전북 익산시 망산길 11-17",
+///        "region_1depth_name": "전북",
+///        "region_2depth_name": "익산시",
+///        "region_3depth_name": "부송동",
+///        "road_name": "망산길",
+///        "underground_yn": "N",
+///        "main_building_no": "11",
+///        "sub_building_no": "17",
+///        "building_name": "",
+///        "zone_no": "54547"
+///      }
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KakaoResponse<T>
+where
+    T: Float + Debug,
+{
+    pub meta: Meta,
+    pub documents: Vec<Document<T>>,
+}
+
+/// Pagination metadata
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Meta {
+    pub total_count: u32,
+    pub pageable_count: Option<u32>,
+    pub is_end: Option<bool>,
+}
+
+/// An address search result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Document<T>
+where
+    T: Float + Debug,
+{
+    pub address_name: String,
+    pub address_type: AddressType,
+    #[serde(deserialize_with = "deserialize_float")]
+    pub x: T,
+    #[serde(deserialize_with = "deserialize_float")]
+    pub y: T,
+    /// The land-lot address, if any
+    pub address: Option<LandLotAddress>,
+    /// The road name address, if any
+    pub road_address: Option<RoadAddress>,
+}
+
+/// What an address search result matched
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AddressType {
+    /// A region, such as a neighbourhood
+    Region,
+    /// A road
+    Road,
+    /// A land-lot address
+    RegionAddr,
+    /// A road name address
+    RoadAddr,
+}
+
+/// A land-lot (지번) address
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LandLotAddress {
+    pub address_name: String,
+    /// The province or metropolitan city
+    pub region_1depth_name: String,
+    /// The city, county or district
+    pub region_2depth_name: String,
+    /// The legal neighbourhood (법정동)
+    pub region_3depth_name: String,
+    /// The administrative neighbourhood (행정동), for address searches only
+    pub region_3depth_h_name: Option<String>,
+    pub h_code: Option<String>,
+    pub b_code: Option<String>,
+    /// Whether the lot is on a mountain (`Y` or `N`)
+    pub mountain_yn: String,
+    pub main_address_no: String,
+    pub sub_address_no: String,
+}
+
+/// A road name (도로명) address
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoadAddress {
+    pub address_name: String,
+    pub region_1depth_name: String,
+    pub region_2depth_name: String,
+    pub region_3depth_name: String,
+    pub road_name: String,
+    /// Whether the building is underground (`Y` or `N`)
+    pub underground_yn: String,
+    pub main_building_no: String,
+    pub sub_building_no: String,
+    pub building_name: String,
+    /// The postcode
+    pub zone_no: String,
+}
+
+/// The top-level full JSON response returned by a `coord2address` request
+///
+/// See [the documentation](https://developers.kakao.com/docs/latest/en/local/dev-guide#coord-to-address-response) for more details
+///
+///```json
+///{
+///  "meta": { "total_count": 1 },
+///  "documents": [
+///    {
+///      "road_address": null,
+///      "address": {
+///        "address_name": "서울 강남구 삼성동 159",
+///        "region_1depth_name": "서울",
+///        "region_2depth_name": "강남구",
+///        "region_3depth_name": "삼성동",
+///        "mountain_yn": "N",
+///        "main_address_no": "159",
+///        "sub_address_no": ""
+///      }
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KakaoReverseResponse {
+    pub meta: Meta,
+    pub documents: Vec<ReverseDocument>,
+}
+
+/// A `coord2address` result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReverseDocument {
+    pub address: Option<LandLotAddress>,
+    pub road_address: Option<RoadAddress>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn forward_response_test() {
+        let res: KakaoResponse<f64> = serde_json::from_str(
+            r#"{
+                "meta": { "total_count": 1, "pageable_count": 1, "is_end": true },
+                "documents": [
+                    {
+                        "address_name": "전북 익산시 부송동 100",
+                        "address_type": "REGION_ADDR",
+                        "x": "126.99597295767953",
+                        "y": "35.97664845766847",
+                        "address": {
+                            "address_name": "전북 익산시 부송동 100",
+                            "region_1depth_name": "전북",
+                            "region_2depth_name": "익산시",
+                            "region_3depth_name": "부송동",
+                            "region_3depth_h_name": "삼성동",
+                            "h_code": "4514069000",
+                            "b_code": "4514013400",
+                            "mountain_yn": "N",
+                            "main_address_no": "100",
+                            "sub_address_no": "",
+                            "x": "126.99597295767953",
+                            "y": "35.97664845766847"
+                        },
+                        "road_address": null
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let document = &res.documents[0];
+        assert_eq!(document.address_type, AddressType::RegionAddr);
+        assert_eq!(
+            Point::new(document.x, document.y),
+            Point::new(126.99597295767953, 35.97664845766847)
+        );
+        assert!(document.road_address.is_none());
+        assert_eq!(document.address.as_ref().unwrap().main_address_no, "100");
+    }
+}
@@ -0,0 +1,447 @@
+//! The Norwegian [Kartverket](https://www.kartverket.no/) provider.
+//!
+//! Geocoding methods are implemented on the [`Kartverket`](struct.Kartverket.html) struct.
+//! Kartverket, the Norwegian Mapping Authority, publishes the official address register through
+//! its free adresser API, which requires no key. Please see the
+//! [API documentation](https://ws.geonorge.no/adresser/v1/) for details.
+//!
+//! Results are located in ETRS89, which may be treated as WGS84; their coordinates in the
+//! national UTM zone 33N grid are available through `utm33_point`.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, Kartverket, Point};
+//!
+//! let kartverket = Kartverket::new();
+//! let address = "Karl Johans gate 1, Oslo";
+//! let res: Result<Vec<Point<f64>>, _> = kartverket.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::transform::ETRS89_UTM33;
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+
+// Results are requested in ETRS89 geographic coordinates
+const OUTPUT_CRS: &str = "4258";
+
+/// An instance of the Kartverket address service
+pub struct Kartverket {
+    client: Client,
+    endpoint: String,
+}
+
+/// An instance of a parameter builder for Kartverket address searches
+pub struct KartverketParams<'a> {
+    query: &'a str,
+    municipality: Option<&'a str>,
+    postcode: Option<&'a str>,
+    fuzzy: bool,
+    per_page: Option<u16>,
+    page: Option<u16>,
+}
+
+impl<'a> KartverketParams<'a> {
+    /// Create a new Kartverket parameter builder
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::kartverket::KartverketParams;
+    ///
+    /// let params = KartverketParams::new("Karl Johans gate 1")
+    ///     .with_municipality("0301")
+    ///     .with_per_page(5)
+    ///     .build();
+    /// ```
+    pub fn new(query: &'a str) -> KartverketParams<'a> {
+        KartverketParams {
+            query,
+            municipality: None,
+            postcode: None,
+            fuzzy: false,
+            per_page: None,
+            page: None,
+        }
+    }
+
+    /// Set the `kommunenummer` property, restricting results to a municipality by its
+    /// four-digit number
+    pub fn with_municipality(&mut self, municipality: &'a str) -> &mut Self {
+        self.municipality = Some(municipality);
+        self
+    }
+
+    /// Set the `postnummer` property, restricting results to a postcode
+    pub fn with_postcode(&mut self, postcode: &'a str) -> &mut Self {
+        self.postcode = Some(postcode);
+        self
+    }
+
+    /// Set the `fuzzy` property, tolerating misspellings
+    pub fn with_fuzzy(&mut self, fuzzy: bool) -> &mut Self {
+        self.fuzzy = fuzzy;
+        self
+    }
+
+    /// Set the `treffPerSide` property, the number of results per page
+    pub fn with_per_page(&mut self, per_page: u16) -> &mut Self {
+        self.per_page = Some(per_page);
+        self
+    }
+
+    /// Set the `side` property, the zero-based page of results
+    pub fn with_page(&mut self, page: u16) -> &mut Self {
+        self.page = Some(page);
+        self
+    }
+
+    /// Build and return an instance of KartverketParams
+    pub fn build(&self) -> KartverketParams<'a> {
+        KartverketParams {
+            query: self.query,
+            municipality: self.municipality,
+            postcode: self.postcode,
+            fuzzy: self.fuzzy,
+            per_page: self.per_page,
+            page: self.page,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("sok", self.query.to_string())];
+        if let Some(municipality) = self.municipality {
+            query.push(("kommunenummer", municipality.to_string()));
+        }
+        if let Some(postcode) = self.postcode {
+            query.push(("postnummer", postcode.to_string()));
+        }
+        if self.fuzzy {
+            query.push(("fuzzy", "true".to_string()));
+        }
+        if let Some(per_page) = self.per_page {
+            query.push(("treffPerSide", per_page.to_string()));
+        }
+        if let Some(page) = self.page {
+            query.push(("side", page.to_string()));
+        }
+        query
+    }
+}
+
+impl Kartverket {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "Kartverket",
+        docs_url: "https://ws.geonorge.no/adresser/v1/",
+        terms_url: Some("https://www.kartverket.no/api-og-data/vilkar-for-bruk"),
+        attribution: Some("© Kartverket"),
+        rate_limit: None,
+    };
+
+    /// Create a new Kartverket address instance using the default endpoint
+    pub fn new() -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Kartverket {
+            client,
+            endpoint: "https://ws.geonorge.no/adresser/v1/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of a Kartverket address instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://ws.geonorge.no/adresser/v1/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A forward-geocoding search of addresses, returning a full detailed response
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Kartverket;
+    /// use geocoding::kartverket::{KartverketParams, KartverketResponse};
+    ///
+    /// let kartverket = Kartverket::new();
+    /// let params = KartverketParams::new("Karl Johans gate 1")
+    ///     .with_municipality("0301")
+    ///     .build();
+    /// let res: Result<KartverketResponse<f64>, _> = kartverket.forward_full(&params);
+    /// ```
+    pub fn forward_full<T>(
+        &self,
+        params: &KartverketParams,
+    ) -> Result<KartverketResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get("sok", params.as_query())
+    }
+
+    /// Search for addresses within `radius` metres of a point, nearest first
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+        radius: u32,
+        per_page: Option<u16>,
+    ) -> Result<KartverketResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = vec![
+            ("lat", point.y().to_f64().unwrap().to_string()),
+            ("lon", point.x().to_f64().unwrap().to_string()),
+            ("radius", radius.to_string()),
+        ];
+        if let Some(per_page) = per_page {
+            query.push(("treffPerSide", per_page.to_string()));
+        }
+        self.get("punktsok", query)
+    }
+
+    fn get<R>(
+        &self,
+        path: &str,
+        mut query: Vec<(&'static str, String)>,
+    ) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        query.push(("utkoordsys", OUTPUT_CRS.to_string()));
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl Default for Kartverket {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Forward<T> for Kartverket
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of an address. Please see [the documentation](https://ws.geonorge.no/adresser/v1/) for details.
+    ///
+    /// This method passes the `sok` parameter to the API.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(&KartverketParams::new(place))?;
+        Ok(res.adresser.iter().map(KartverketAddress::point).collect())
+    }
+}
+
+impl<T> Reverse<T> for Kartverket
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the nearest address within 100 metres, followed
+    /// by its postcode and postal town.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point, 100, Some(1))?;
+        Ok(res.adresser.first().map(KartverketAddress::label))
+    }
+}
+
+/// The top-level full JSON response returned by a search
+///
+///```json
+///{
+///  "metadata": {
+///    "totaltAntallTreff": 1,
+///    "treffPerSide": 10,
+///    "side": 0,
+///    "viserFra": 0,
+///    "viserTil": 1,
+///    "sokeStreng": "sok=Karl Johans gate 1&kommunenummer=0301"
+///  },
+///  "adresser": [
+///    {
+///      "adressenavn": "Karl Johans gate",
+///      "adressetekst": "Karl Johans gate 1",
+///      "adressetilleggsnavn": null,
+///      "adressekode": 13000,
+///      "nummer": 1,
+///      "bokstav": "",
+///      "kommunenummer": "0301",
+///      "kommunenavn": "OSLO",
+///      "gardsnummer": 207,
+///      "bruksnummer": 88,
+///      "festenummer": 0,
+///      "undernummer": null,
+///      "bruksenhetsnummer": ["H0101"],
+///      "objtype": "Vegadresse",
+///      "poststed": "OSLO",
+///      "postnummer": "0154",
+///      "stedfestingverifisert": true,
+///      "representasjonspunkt": {
+///        "epsg": "EPSG:4258",
+///        "lat": 59.91085,
+///        "lon": 10.74992
+///      },
+///      "oppdateringsdato": "2024-01-12T10:34:19"
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KartverketResponse<T>
+where
+    T: Float + Debug,
+{
+    pub metadata: KartverketMetadata,
+    #[serde(default = "Vec::new")]
+    pub adresser: Vec<KartverketAddress<T>>,
+}
+
+/// Paging metadata of a search
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KartverketMetadata {
+    pub totalt_antall_treff: u32,
+    pub treff_per_side: u32,
+    pub side: u32,
+    pub viser_fra: u32,
+    pub viser_til: u32,
+    pub soke_streng: Option<String>,
+}
+
+/// An address
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KartverketAddress<T>
+where
+    T: Float + Debug,
+{
+    /// The street or farm name
+    pub adressenavn: Option<String>,
+    /// The full address, e.g. `Karl Johans gate 1`
+    pub adressetekst: String,
+    /// An additional name, such as a farm name
+    pub adressetilleggsnavn: Option<String>,
+    pub adressekode: Option<u32>,
+    pub nummer: Option<u32>,
+    /// A letter suffix of the number, or an empty string
+    pub bokstav: Option<String>,
+    /// The four-digit municipality number
+    pub kommunenummer: String,
+    pub kommunenavn: String,
+    /// The cadastral unit (gnr)
+    pub gardsnummer: Option<u32>,
+    /// The cadastral sub-unit (bnr)
+    pub bruksnummer: Option<u32>,
+    pub festenummer: Option<u32>,
+    pub undernummer: Option<u32>,
+    /// The dwelling numbers at the address
+    #[serde(default = "Vec::new")]
+    pub bruksenhetsnummer: Vec<String>,
+    /// The kind of address: `Vegadresse` (street address) or `Matrikkeladresse` (cadastral
+    /// address)
+    pub objtype: String,
+    pub poststed: Option<String>,
+    pub postnummer: Option<String>,
+    /// Whether the location has been verified
+    pub stedfestingverifisert: Option<bool>,
+    pub representasjonspunkt: KartverketPoint<T>,
+    /// The distance to the searched point, in metres, for reverse searches
+    #[serde(rename = "meterDistanseTilPunkt")]
+    pub meter_distanse_til_punkt: Option<T>,
+    pub oppdateringsdato: Option<String>,
+}
+
+/// A location in ETRS89
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KartverketPoint<T>
+where
+    T: Float + Debug,
+{
+    pub epsg: String,
+    pub lat: T,
+    pub lon: T,
+}
+
+impl<T> KartverketAddress<T>
+where
+    T: Float + Debug,
+{
+    /// The location of the address
+    pub fn point(&self) -> Point<T> {
+        Point::new(self.representasjonspunkt.lon, self.representasjonspunkt.lat)
+    }
+
+    /// The location of the address in ETRS89 / UTM zone 33N, as an easting and northing
+    pub fn utm33_point(&self) -> Point<T> {
+        ETRS89_UTM33.project(&self.point())
+    }
+
+    fn label(&self) -> String {
+        let town = [self.postnummer.as_deref(), self.poststed.as_deref()]
+            .iter()
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if town.is_empty() {
+            self.adressetekst.clone()
+        } else {
+            format!("{}, {}", self.adressetekst, town)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reverse_test() {
+        let res: KartverketResponse<f64> = serde_json::from_str(
+            r#"{
+                "metadata": {
+                    "totaltAntallTreff": 1, "treffPerSide": 1, "side": 0,
+                    "viserFra": 0, "viserTil": 1, "sokeStreng": "lat=59.9109&lon=10.7499&radius=100"
+                },
+                "adresser": [{
+                    "adressenavn": "Karl Johans gate", "adressetekst": "Karl Johans gate 1",
+                    "adressetilleggsnavn": null, "adressekode": 13000, "nummer": 1, "bokstav": "",
+                    "kommunenummer": "0301", "kommunenavn": "OSLO", "gardsnummer": 207,
+                    "bruksnummer": 88, "festenummer": 0, "undernummer": null,
+                    "bruksenhetsnummer": ["H0101"], "objtype": "Vegadresse", "poststed": "OSLO",
+                    "postnummer": "0154", "stedfestingverifisert": true,
+                    "representasjonspunkt": { "epsg": "EPSG:4258", "lat": 59.91085, "lon": 10.74992 },
+                    "meterDistanseTilPunkt": 4.2, "oppdateringsdato": "2024-01-12T10:34:19"
+                }]
+            }"#,
+        )
+        .unwrap();
+        let address = &res.adresser[0];
+        assert_eq!(address.kommunenummer, "0301");
+        assert_eq!(address.point(), Point::new(10.74992, 59.91085));
+        assert_eq!(address.label(), "Karl Johans gate 1, 0154 OSLO");
+        let utm = address.utm33_point();
+        assert!((utm.x() - 262_411.34).abs() < 0.01);
+        assert!((utm.y() - 6_649_112.57).abs() < 0.01);
+    }
+}
@@ -0,0 +1,435 @@
+//! The Swedish [Lantmäteriet](https://www.lantmateriet.se/) provider.
+//!
+//! Geocoding methods are implemented on the [`Lantmateriet`](struct.Lantmateriet.html) struct.
+//! Lantmäteriet, the Swedish mapping, cadastral and land registration authority, publishes the
+//! official address register through its Belägenhetsadress Direkt API. Access requires an
+//! agreement; requests are authenticated with OAuth2 access tokens obtained from the consumer
+//! key and secret of an application registered in the
+//! [API portal](https://apimanager.lantmateriet.se/). Tokens are requested when first needed
+//! and renewed shortly before they expire. Please see the
+//! [API documentation](https://www.lantmateriet.se/sv/geodata/vara-produkter/produktlista/belagenhetsadress-direkt/) for details.
+//!
+//! Addresses are located in the SWEREF 99 TM national grid, and converted to and from WGS84
+//! using [`SWEREF99_TM`](../transform/constant.SWEREF99_TM.html).
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, Lantmateriet, Point};
+//!
+//! let lantmateriet = Lantmateriet::new(
+//!     "dcba4321".to_string(),
+//!     "abcd1234".to_string(),
+//! );
+//! let address = "Drottninggatan 1, Stockholm";
+//! let res: Result<Vec<Point<f64>>, _> = lantmateriet.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::transform::SWEREF99_TM;
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Tokens are renewed this long before they expire
+const TOKEN_MARGIN: Duration = Duration::from_secs(60);
+
+// The SWEREF 99 TM reference system
+const SRID: &str = "3006";
+
+/// An instance of the Lantmäteriet address service
+pub struct Lantmateriet {
+    client: Client,
+    endpoint: String,
+    token_endpoint: String,
+    consumer_key: String,
+    consumer_secret: String,
+    token: Mutex<Option<(String, Instant)>>,
+}
+
+impl Lantmateriet {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "Lantmäteriet",
+        docs_url: "https://www.lantmateriet.se/sv/geodata/vara-produkter/produktlista/belagenhetsadress-direkt/",
+        terms_url: Some("https://www.lantmateriet.se/sv/geodata/Licenser-avtal-och-priser/"),
+        attribution: Some("© Lantmäteriet"),
+        rate_limit: None,
+    };
+
+    /// Create a new Lantmäteriet instance using the consumer key and secret of an application
+    pub fn new(consumer_key: String, consumer_secret: String) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Lantmateriet {
+            client,
+            endpoint: "https://api.lantmateriet.se/distribution/produkter/belagenhetsadress/v4.2/"
+                .to_string(),
+            token_endpoint: "https://apimanager.lantmateriet.se/oauth2/token".to_string(),
+            consumer_key,
+            consumer_secret,
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Set a custom endpoint of a Lantmäteriet instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://api.lantmateriet.se/distribution/produkter/belagenhetsadress/v4.2/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// Set a custom OAuth2 token endpoint of a Lantmäteriet instance
+    pub fn with_token_endpoint(mut self, token_endpoint: &str) -> Self {
+        self.token_endpoint = token_endpoint.to_owned();
+        self
+    }
+
+    /// Search for addresses matching a free-text query, returning at most `max_hits` references
+    ///
+    /// References identify addresses without locating them; they are resolved by
+    /// [`lookup`](#method.lookup).
+    pub fn search(
+        &self,
+        address: &str,
+        max_hits: Option<u16>,
+    ) -> Result<Vec<AddressReference>, GeocodingError> {
+        let mut query = vec![("adress", address.to_string())];
+        if let Some(max_hits) = max_hits {
+            query.push(("maxHits", max_hits.to_string()));
+        }
+        self.get("referens/fritext", &query)
+    }
+
+    /// Search for addresses within `radius` metres of a point, nearest first
+    pub fn nearby<T>(
+        &self,
+        point: &Point<T>,
+        radius: u32,
+        max_hits: Option<u16>,
+    ) -> Result<Vec<AddressReference>, GeocodingError>
+    where
+        T: Float + Debug,
+    {
+        let grid = SWEREF99_TM.project(point);
+        let path = format!(
+            "referens/punkt/{}/{:.2},{:.2}",
+            SRID,
+            grid.y().to_f64().unwrap(),
+            grid.x().to_f64().unwrap()
+        );
+        let mut query = vec![("radie", radius.to_string())];
+        if let Some(max_hits) = max_hits {
+            query.push(("maxHits", max_hits.to_string()));
+        }
+        self.get(&path, &query)
+    }
+
+    /// Retrieve the full records of addresses by their identifiers
+    pub fn lookup<T>(&self, ids: &[&str]) -> Result<Vec<AddressFeature<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let resp = self
+            .client
+            .post(&self.endpoint)
+            .bearer_auth(self.token()?)
+            .query(&[("includeData", "total"), ("srid", SRID)])
+            .json(ids)
+            .send()?
+            .error_for_status()?;
+        let res: AddressCollection<T> = resp.json()?;
+        Ok(res.features)
+    }
+
+    /// A forward-geocoding search of an address, returning full detailed records
+    pub fn forward_full<T>(
+        &self,
+        address: &str,
+        max_hits: Option<u16>,
+    ) -> Result<Vec<AddressFeature<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let references = self.search(address, max_hits)?;
+        let ids: Vec<&str> = references
+            .iter()
+            .map(|reference| reference.objektidentitet.as_str())
+            .collect();
+        self.lookup(&ids)
+    }
+
+    fn get<R>(&self, path: &str, query: &[(&'static str, String)]) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .bearer_auth(self.token()?)
+            .query(query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+
+    // A valid access token, requested anew if missing or about to expire
+    fn token(&self) -> Result<String, GeocodingError> {
+        let mut token = self.token.lock().unwrap();
+        if let Some((access_token, expires)) = &*token {
+            if Instant::now() + TOKEN_MARGIN < *expires {
+                return Ok(access_token.clone());
+            }
+        }
+        let resp = self
+            .client
+            .post(&self.token_endpoint)
+            .basic_auth(&self.consumer_key, Some(&self.consumer_secret))
+            .form(&[("grant_type", "client_credentials")])
+            .send()?
+            .error_for_status()?;
+        let res: TokenResponse = resp.json()?;
+        let expires = Instant::now() + Duration::from_secs(res.expires_in);
+        *token = Some((res.access_token.clone(), expires));
+        Ok(res.access_token)
+    }
+}
+
+impl<T> Forward<T> for Lantmateriet
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of an address. Please see [the documentation](https://www.lantmateriet.se/sv/geodata/vara-produkter/produktlista/belagenhetsadress-direkt/) for details.
+    ///
+    /// This method passes the `adress` parameter to the API.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(place, None)?;
+        Ok(res.iter().filter_map(AddressFeature::point).collect())
+    }
+}
+
+impl<T> Reverse<T> for Lantmateriet
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the designation of the nearest address within
+    /// 100 metres.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.nearby(point, 100, Some(1))?;
+        Ok(res
+            .into_iter()
+            .next()
+            .and_then(|reference| reference.beteckning))
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+/// A reference to an address
+///
+///```json
+///{
+///  "objektidentitet": "2a3d4f6e-8b0c-4e1d-9f2a-3b4c5d6e7f80",
+///  "beteckning": "Drottninggatan 1, 111 51 Stockholm"
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddressReference {
+    /// The identifier of the address
+    pub objektidentitet: String,
+    /// The full designation of the address
+    pub beteckning: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AddressCollection<T>
+where
+    T: Float + Debug,
+{
+    #[serde(default = "Vec::new")]
+    features: Vec<AddressFeature<T>>,
+}
+
+/// An address record
+///
+///```json
+///{
+///  "type": "Feature",
+///  "id": "2a3d4f6e-8b0c-4e1d-9f2a-3b4c5d6e7f80",
+///  "geometry": null,
+///  "properties": {
+///    "objektidentitet": "2a3d4f6e-8b0c-4e1d-9f2a-3b4c5d6e7f80",
+///    "adressplatsattribut": {
+///      "adressplatstyp": "Gatuadressplats",
+///      "status": "Gällande",
+///      "postnummer": 11151,
+///      "postort": "STOCKHOLM",
+///      "adressplatspunkt": { "type": "Point", "coordinates": [674571.87, 6580743.01] }
+///    },
+///    "adressplatsbeteckning": {
+///      "adressplatsnummer": "1",
+///      "bokstavstillagg": null
+///    },
+///    "adressomrade": {
+///      "faststalltNamn": "Drottninggatan",
+///      "kommundel": {
+///        "faststalltNamn": "Stockholm",
+///        "kommunkod": "0180",
+///        "kommunnamn": "Stockholm"
+///      }
+///    }
+///  }
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddressFeature<T>
+where
+    T: Float + Debug,
+{
+    pub id: Option<String>,
+    pub properties: AddressProperties<T>,
+}
+
+impl<T> AddressFeature<T>
+where
+    T: Float + Debug,
+{
+    /// The location of the address in WGS84, if any
+    pub fn point(&self) -> Option<Point<T>> {
+        let location = self.grid_point()?;
+        Some(SWEREF99_TM.unproject(&location))
+    }
+
+    /// The location of the address in SWEREF 99 TM, as an easting and northing, if any
+    pub fn grid_point(&self) -> Option<Point<T>> {
+        let point = self
+            .properties
+            .adressplatsattribut
+            .as_ref()?
+            .adressplatspunkt
+            .as_ref()?;
+        let (easting, northing) = point.coordinates;
+        Some(Point::new(easting, northing))
+    }
+}
+
+/// The properties of an address record
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddressProperties<T>
+where
+    T: Float + Debug,
+{
+    pub objektidentitet: String,
+    pub adressplatsattribut: Option<AddressAttributes<T>>,
+    pub adressplatsbeteckning: Option<AddressNumber>,
+    pub adressomrade: Option<AddressArea>,
+}
+
+/// The attributes of an address
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddressAttributes<T>
+where
+    T: Float + Debug,
+{
+    /// The kind of address, e.g. `Gatuadressplats` (street address) or `Byadressplats`
+    /// (village address)
+    pub adressplatstyp: Option<String>,
+    pub status: Option<String>,
+    pub postnummer: Option<u32>,
+    pub postort: Option<String>,
+    pub adressplatspunkt: Option<GridPoint<T>>,
+}
+
+/// A point in SWEREF 99 TM
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GridPoint<T>
+where
+    T: Float + Debug,
+{
+    /// The easting and northing, in metres
+    pub coordinates: (T, T),
+}
+
+/// The number of an address within its street or village
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddressNumber {
+    pub adressplatsnummer: Option<String>,
+    /// A letter suffix, e.g. `A`
+    pub bokstavstillagg: Option<String>,
+}
+
+/// The street or village of an address
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressArea {
+    /// The street or village name
+    pub faststallt_namn: Option<String>,
+    pub kommundel: Option<MunicipalityPart>,
+}
+
+/// The part of a municipality an address lies in
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MunicipalityPart {
+    pub faststallt_namn: Option<String>,
+    /// The four-digit municipality code
+    pub kommunkod: Option<String>,
+    pub kommunnamn: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lookup_test() {
+        let res: AddressCollection<f64> = serde_json::from_str(
+            r#"{"type": "FeatureCollection", "features": [{
+                "type": "Feature", "id": "2a3d4f6e-8b0c-4e1d-9f2a-3b4c5d6e7f80", "geometry": null,
+                "properties": {
+                    "objektidentitet": "2a3d4f6e-8b0c-4e1d-9f2a-3b4c5d6e7f80",
+                    "adressplatsattribut": {
+                        "adressplatstyp": "Gatuadressplats", "status": "Gällande",
+                        "postnummer": 11151, "postort": "STOCKHOLM",
+                        "adressplatspunkt": { "type": "Point", "coordinates": [674571.87, 6580743.01] }
+                    },
+                    "adressplatsbeteckning": { "adressplatsnummer": "1", "bokstavstillagg": null },
+                    "adressomrade": {
+                        "faststalltNamn": "Drottninggatan",
+                        "kommundel": { "faststalltNamn": "Stockholm", "kommunkod": "0180", "kommunnamn": "Stockholm" }
+                    }
+                }
+            }]}"#,
+        )
+        .unwrap();
+        let feature = &res.features[0];
+        let area = feature.properties.adressomrade.as_ref().unwrap();
+        assert_eq!(area.faststallt_namn.as_deref(), Some("Drottninggatan"));
+        let point = feature.point().unwrap();
+        assert!((point.x() - 18.0686).abs() < 1e-6);
+        assert!((point.y() - 59.3293).abs() < 1e-6);
+    }
+}
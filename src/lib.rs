@@ -24,6 +24,26 @@
 //![dependencies]
 //!geocoding = { version = "*", default-features = false, features = ["rustls-tls"] }
 //!```
+//!
+//! ### Offline geocoding
+//!
+//! The `offline` feature enables the [`OfflineReverse`](offline/struct.OfflineReverse.html)
+//! provider, which answers reverse lookups from a GeoNames cities dataset without network access,
+//! and the [`OfflinePostcodes`](offline/struct.OfflinePostcodes.html) provider, which resolves
+//! postcodes from a GeoNames postal code dataset. The `tiger` feature enables the
+//! [`Tiger`](tiger/struct.Tiger.html) provider, which geocodes US street addresses by
+//! interpolating the address ranges of Census TIGER/Line files.
+//!
+//! ### Time zones
+//!
+//! The `chrono-tz` feature parses the time zones of OpenCage results, with
+//! [`Timezone::localize`](opencage/struct.Timezone.html) giving the local time at a result.
+//!
+//! ### Address normalization
+//!
+//! The `libpostal` feature enables the [`libpostal`](libpostal/index.html) module, which parses
+//! and normalizes addresses using [libpostal](https://github.com/openvenues/libpostal). It
+//! requires libpostal and its models to be installed.
 
 static UA_STRING: &str = "Rust-Geocoding";
 
@@ -33,9 +53,10 @@ use reqwest::blocking::Client;
 use reqwest::header::ToStrError;
 use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
 use serde::de::DeserializeOwned;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::fmt::Debug;
 use std::num::ParseIntError;
+use std::time::Duration;
 use thiserror::Error;
 
 // The OpenCage geocoding provider
@@ -50,6 +71,161 @@ pub use crate::openstreetmap::Openstreetmap;
 pub mod geoadmin;
 pub use crate::geoadmin::GeoAdmin;
 
+// The Pelias geocoding provider
+pub mod pelias;
+pub use crate::pelias::Pelias;
+
+// The LocationIQ geocoding provider
+pub mod locationiq;
+pub use crate::locationiq::LocationIq;
+
+// The MapQuest geocoding provider
+pub mod mapquest;
+pub use crate::mapquest::MapQuest;
+
+// The TomTom geocoding provider
+pub mod tomtom;
+pub use crate::tomtom::TomTom;
+
+// The ArcGIS geocoding provider
+pub mod arcgis;
+pub use crate::arcgis::ArcGis;
+
+// The Baidu Maps geocoding provider
+pub mod baidu;
+pub use crate::baidu::Baidu;
+
+// The AMap geocoding provider
+pub mod amap;
+pub use crate::amap::Amap;
+
+// The Kakao Local geocoding provider
+pub mod kakao;
+pub use crate::kakao::Kakao;
+
+// The Naver Maps geocoding provider
+pub mod naver;
+pub use crate::naver::Naver;
+
+// The Tencent Maps geocoding provider
+pub mod tencent;
+pub use crate::tencent::Tencent;
+
+// The Base Adresse Nationale geocoding provider
+pub mod banfrance;
+pub use crate::banfrance::BanFrance;
+
+// The US Census Bureau geocoding provider
+pub mod uscensus;
+pub use crate::uscensus::UsCensus;
+
+// The GeoNames geocoding provider
+pub mod geonames;
+pub use crate::geonames::Geonames;
+
+// The positionstack geocoding provider
+pub mod positionstack;
+pub use crate::positionstack::PositionStack;
+
+// The Ordnance Survey Places API geocoding provider
+pub mod osplaces;
+pub use crate::osplaces::OsPlaces;
+
+// The Smarty US address verification provider
+pub mod smarty;
+pub use crate::smarty::Smarty;
+
+// The Danish DAWA geocoding provider
+pub mod dawa;
+pub use crate::dawa::Dawa;
+
+// The Dutch PDOK Locatieserver geocoding provider
+pub mod pdok;
+pub use crate::pdok::Pdok;
+
+// The German BKG geocoding provider
+pub mod bkg;
+pub use crate::bkg::Bkg;
+
+// The Spanish CartoCiudad geocoding provider
+pub mod cartociudad;
+pub use crate::cartociudad::CartoCiudad;
+
+// The IGN Géoplateforme geocoding provider
+pub mod ignfrance;
+pub use crate::ignfrance::IgnFrance;
+
+// The Lantmäteriet geocoding provider
+pub mod lantmateriet;
+pub use crate::lantmateriet::Lantmateriet;
+
+// The Kartverket geocoding provider
+pub mod kartverket;
+pub use crate::kartverket::Kartverket;
+
+// The National Land Survey of Finland geocoding provider
+pub mod nlsfinland;
+pub use crate::nlsfinland::NlsFinland;
+
+// The Geoscape geocoding provider
+pub mod geoscape;
+pub use crate::geoscape::Geoscape;
+
+// The LINZ geocoding provider
+pub mod linz;
+pub use crate::linz::Linz;
+
+// The GeoGratis geocoding provider
+pub mod geogratis;
+pub use crate::geogratis::Geogratis;
+
+// The geocode.earth geocoding provider
+pub mod geocodeearth;
+pub use crate::geocodeearth::GeocodeEarth;
+
+// The offline Plus Codes provider
+pub mod pluscodes;
+pub use crate::pluscodes::PlusCodes;
+
+// The Wikidata provider
+pub mod wikidata;
+pub use crate::wikidata::Wikidata;
+
+// The Overpass API provider
+pub mod overpass;
+pub use crate::overpass::Overpass;
+
+// The offline GeoNames geocoders
+#[cfg(feature = "offline")]
+pub mod offline;
+#[cfg(feature = "offline")]
+pub use crate::offline::{OfflinePostcodes, OfflineReverse};
+
+// The offline TIGER/Line geocoder
+#[cfg(feature = "tiger")]
+pub mod tiger;
+#[cfg(feature = "tiger")]
+pub use crate::tiger::Tiger;
+
+// Address parsing and normalization
+#[cfg(feature = "libpostal")]
+pub mod libpostal;
+
+// Normalized addresses
+pub mod address;
+pub use crate::address::Address;
+
+// Batch reverse-geocoding reports
+pub mod batch;
+pub use crate::batch::ReverseMatrix;
+
+// Coordinate system conversions
+pub mod transform;
+
+// Provider metadata
+pub mod info;
+pub use crate::info::ProviderInfo;
+
 /// Errors that can occur during geocoding operations
 #[derive(Error, Debug)]
 pub enum GeocodingError {
@@ -63,6 +239,26 @@ pub enum GeocodingError {
     HeaderConversion(#[from] ToStrError),
     #[error("Error converting int to String")]
     ParseInt(#[from] ParseIntError),
+    #[error("Top result falls outside the requested bounds")]
+    OutOfBounds,
+    #[error("CSV error")]
+    Csv(#[from] csv::Error),
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(String),
+    #[error("Access forbidden: {0}")]
+    Forbidden(String),
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        message: String,
+        /// How long the provider asked to wait before retrying, if it said so
+        retry_after: Option<Duration>,
+    },
+    #[error("Couldn't set up libpostal")]
+    Libpostal,
+    #[error("Provider error {status}: {message}")]
+    Provider { status: i32, message: String },
 }
 
 /// Reverse-geocode a coordinate.
@@ -152,6 +348,62 @@ where
     }
 }
 
+impl<T> InputBounds<T>
+where
+    T: Float + Debug,
+{
+    /// Whether a point lies within the bounds, edges included
+    pub fn contains(&self, point: &Point<T>) -> bool {
+        point.x() >= self.minimum_lonlat.x()
+            && point.x() <= self.maximum_lonlat.x()
+            && point.y() >= self.minimum_lonlat.y()
+            && point.y() <= self.maximum_lonlat.y()
+    }
+
+    /// The center of the bounds
+    pub fn center(&self) -> Point<T> {
+        let two = T::one() + T::one();
+        Point::new(
+            (self.minimum_lonlat.x() + self.maximum_lonlat.x()) / two,
+            (self.minimum_lonlat.y() + self.maximum_lonlat.y()) / two,
+        )
+    }
+
+    /// Apply a `StrictBounds` mode to a list of results, given a way to locate each result
+    pub(crate) fn enforce<R, F>(
+        &self,
+        results: Vec<R>,
+        mode: StrictBounds,
+        point: F,
+    ) -> Result<Vec<R>, GeocodingError>
+    where
+        F: Fn(&R) -> Point<T>,
+    {
+        match mode {
+            StrictBounds::Filter => Ok(results
+                .into_iter()
+                .filter(|result| self.contains(&point(result)))
+                .collect()),
+            StrictBounds::Error => match results.first() {
+                Some(top) if !self.contains(&point(top)) => Err(GeocodingError::OutOfBounds),
+                _ => Ok(results),
+            },
+        }
+    }
+}
+
+/// How to treat results falling outside the bounds passed to a forward-geocoding request
+///
+/// Most providers only use bounds to bias their results, so a result outside of them may
+/// still be returned. Strict bounds are checked once the response has been received.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StrictBounds {
+    /// Drop the results falling outside the bounds
+    Filter,
+    /// Fail with `GeocodingError::OutOfBounds` if the top result falls outside the bounds
+    Error,
+}
+
 /// Convert borrowed input bounds into the correct String representation
 impl<T> From<InputBounds<T>> for String
 where
@@ -168,3 +420,57 @@ where
         )
     }
 }
+
+/// Deserialize a float which a provider may encode either as a JSON number or as a string
+pub(crate) fn deserialize_float<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Float,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrFloat {
+        String(String),
+        Float(f64),
+    }
+
+    let value = match StringOrFloat::deserialize(deserializer)? {
+        StringOrFloat::String(s) => s.parse().map_err(serde::de::Error::custom)?,
+        StringOrFloat::Float(f) => f,
+    };
+    T::from(value).ok_or_else(|| serde::de::Error::custom("float out of range"))
+}
+
+/// Deserialize a list of floats which a provider may encode either as JSON numbers or as strings
+pub(crate) fn deserialize_float_vec<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Float,
+{
+    #[derive(Deserialize)]
+    struct Wrapper(#[serde(deserialize_with = "deserialize_float")] f64);
+
+    Vec::<Wrapper>::deserialize(deserializer)?
+        .into_iter()
+        .map(|Wrapper(f)| T::from(f).ok_or_else(|| serde::de::Error::custom("float out of range")))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn strict_bounds_test() {
+        let bounds = InputBounds::new((-0.5, 51.25), (0.25, 51.75));
+        let points = vec![Point::new(2.1287, 41.4014), Point::new(-0.1347, 51.5207)];
+        let filtered = bounds
+            .enforce(points.clone(), StrictBounds::Filter, |p| *p)
+            .unwrap();
+        assert_eq!(filtered, vec![Point::new(-0.1347, 51.5207)]);
+        assert!(matches!(
+            bounds.enforce(points, StrictBounds::Error, |p| *p),
+            Err(GeocodingError::OutOfBounds)
+        ));
+    }
+}
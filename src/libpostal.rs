@@ -0,0 +1,269 @@
+//! Address parsing and normalization using [libpostal](https://github.com/openvenues/libpostal).
+//!
+//! libpostal is a C library parsing and normalizing street addresses worldwide, using
+//! statistical models trained on OpenStreetMap and OpenAddresses data. Unnormalized input is a
+//! common cause of poor geocoding results: [`expand_address`](fn.expand_address.html) expands
+//! abbreviations and normalizes case, accents and numbers, and the
+//! [`Normalized`](struct.Normalized.html) wrapper applies it to the queries of any `Forward`
+//! provider. [`parse_address`](fn.parse_address.html) labels the components of an address.
+//!
+//! This module is only available with the `libpostal` feature, which links to a libpostal
+//! installation. Its models take up about 2GB, and are loaded on first use, which takes a few
+//! seconds.
+//!
+//! ### Example
+//!
+//! ```no_run
+//! use geocoding::libpostal::{expand_address, parse_to_address, Normalized};
+//! use geocoding::{Forward, Openstreetmap, Point};
+//!
+//! let expansions = expand_address("30 W 26th St Fl 7").unwrap();
+//! assert!(expansions.contains(&"30 west 26th street floor 7".to_string()));
+//!
+//! let address = parse_to_address("781 Franklin Ave Crown Heights Brooklyn NY 11216").unwrap();
+//! assert_eq!(address.postcode.as_deref(), Some("11216"));
+//!
+//! let osm = Normalized::new(Openstreetmap::new());
+//! let res: Result<Vec<Point<f64>>, _> = osm.forward("Schwabing, Muenchen");
+//! ```
+use crate::address::{Address, LIBPOSTAL};
+use crate::GeocodingError;
+use crate::Point;
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::ffi::{CStr, CString};
+use std::fmt::Debug;
+use std::os::raw::c_char;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+
+#[repr(C)]
+struct ParserOptions {
+    language: *mut c_char,
+    country: *mut c_char,
+}
+
+#[repr(C)]
+struct ParserResponse {
+    num_components: usize,
+    components: *mut *mut c_char,
+    labels: *mut *mut c_char,
+}
+
+#[repr(C)]
+struct NormalizeOptions {
+    languages: *mut *mut c_char,
+    num_languages: usize,
+    address_components: u16,
+    latin_ascii: bool,
+    transliterate: bool,
+    strip_accents: bool,
+    decompose: bool,
+    lowercase: bool,
+    trim_string: bool,
+    drop_parentheticals: bool,
+    replace_numeric_hyphens: bool,
+    delete_numeric_hyphens: bool,
+    split_alpha_from_numeric: bool,
+    replace_word_hyphens: bool,
+    delete_word_hyphens: bool,
+    delete_final_periods: bool,
+    delete_acronym_periods: bool,
+    drop_english_possessives: bool,
+    delete_apostrophes: bool,
+    expand_numex: bool,
+    roman_numerals: bool,
+}
+
+#[link(name = "postal")]
+extern "C" {
+    fn libpostal_setup() -> bool;
+    fn libpostal_setup_parser() -> bool;
+    fn libpostal_setup_language_classifier() -> bool;
+    fn libpostal_get_address_parser_default_options() -> ParserOptions;
+    fn libpostal_parse_address(address: *mut c_char, options: ParserOptions)
+        -> *mut ParserResponse;
+    fn libpostal_address_parser_response_destroy(response: *mut ParserResponse);
+    fn libpostal_get_default_options() -> NormalizeOptions;
+    fn libpostal_expand_address(
+        input: *mut c_char,
+        options: NormalizeOptions,
+        n: *mut usize,
+    ) -> *mut *mut c_char;
+    fn libpostal_expansion_array_destroy(expansions: *mut *mut c_char, n: usize);
+}
+
+static SETUP: Once = Once::new();
+static READY: AtomicBool = AtomicBool::new(false);
+
+// Load the libpostal models, once per process
+fn setup() -> Result<(), GeocodingError> {
+    SETUP.call_once(|| {
+        let ready = unsafe {
+            libpostal_setup() && libpostal_setup_parser() && libpostal_setup_language_classifier()
+        };
+        READY.store(ready, Ordering::SeqCst);
+    });
+    if READY.load(Ordering::SeqCst) {
+        Ok(())
+    } else {
+        Err(GeocodingError::Libpostal)
+    }
+}
+
+fn to_c_string(input: &str) -> CString {
+    CString::new(input.replace('\0', "")).unwrap()
+}
+
+unsafe fn from_c_string(input: *const c_char) -> String {
+    CStr::from_ptr(input).to_string_lossy().into_owned()
+}
+
+/// Parse an address into `(label, component)` pairs, such as `("road", "franklin ave")`
+///
+/// Components are lowercased. Please see the
+/// [libpostal documentation](https://github.com/openvenues/libpostal#parser-labels) for the labels.
+///
+/// Returns [`GeocodingError::Libpostal`](../enum.GeocodingError.html#variant.Libpostal) if the
+/// libpostal models can't be loaded.
+pub fn parse_address(address: &str) -> Result<Vec<(String, String)>, GeocodingError> {
+    setup()?;
+    let input = to_c_string(address);
+    unsafe {
+        let options = libpostal_get_address_parser_default_options();
+        let response = libpostal_parse_address(input.as_ptr() as *mut c_char, options);
+        if response.is_null() {
+            return Ok(vec![]);
+        }
+        let parsed = &*response;
+        let components = (0..parsed.num_components)
+            .map(|i| {
+                (
+                    from_c_string(*parsed.labels.add(i)),
+                    from_c_string(*parsed.components.add(i)),
+                )
+            })
+            .collect();
+        libpostal_address_parser_response_destroy(response);
+        Ok(components)
+    }
+}
+
+/// Parse an address into a provider-independent [`Address`](../struct.Address.html)
+///
+/// Returns [`GeocodingError::Libpostal`](../enum.GeocodingError.html#variant.Libpostal) if the
+/// libpostal models can't be loaded.
+pub fn parse_to_address(address: &str) -> Result<Address, GeocodingError> {
+    let components = parse_address(address)?;
+    Ok(Address::from_components(
+        LIBPOSTAL,
+        components
+            .iter()
+            .map(|(label, component)| (label.as_str(), component.as_str())),
+    ))
+}
+
+/// Expand an address into its normalized forms, such as `30 west 26th street` for
+/// `30 W 26th St`
+///
+/// Abbreviations are ambiguous, so several expansions may be returned, the most likely first.
+///
+/// Returns [`GeocodingError::Libpostal`](../enum.GeocodingError.html#variant.Libpostal) if the
+/// libpostal models can't be loaded.
+pub fn expand_address(address: &str) -> Result<Vec<String>, GeocodingError> {
+    setup()?;
+    let input = to_c_string(address);
+    unsafe {
+        let options = libpostal_get_default_options();
+        let mut n = 0;
+        let expansions = libpostal_expand_address(input.as_ptr() as *mut c_char, options, &mut n);
+        if expansions.is_null() {
+            return Ok(vec![]);
+        }
+        let res = (0..n).map(|i| from_c_string(*expansions.add(i))).collect();
+        libpostal_expansion_array_destroy(expansions, n);
+        Ok(res)
+    }
+}
+
+/// Normalize a forward-geocoding query, returning its first expansion, or the query itself if
+/// it has none
+///
+/// Returns [`GeocodingError::Libpostal`](../enum.GeocodingError.html#variant.Libpostal) if the
+/// libpostal models can't be loaded.
+pub fn normalize_query(query: &str) -> Result<String, GeocodingError> {
+    Ok(expand_address(query)?
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| query.to_string()))
+}
+
+/// A provider whose forward-geocoding queries are normalized by
+/// [`normalize_query`](fn.normalize_query.html) before being sent
+///
+/// Reverse lookups are passed through unchanged.
+pub struct Normalized<P> {
+    inner: P,
+}
+
+impl<P> Normalized<P> {
+    /// Wrap a provider
+    pub fn new(inner: P) -> Self {
+        Normalized { inner }
+    }
+
+    /// The wrapped provider
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Unwrap the provider
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<T, P> Forward<T> for Normalized<P>
+where
+    T: Float + Debug,
+    P: Forward<T>,
+{
+    /// A forward-geocoding lookup of the normalized address.
+    fn forward(&self, address: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        self.inner.forward(&normalize_query(address)?)
+    }
+}
+
+impl<T, P> Reverse<T> for Normalized<P>
+where
+    T: Float + Debug,
+    P: Reverse<T>,
+{
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        self.inner.reverse(point)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // These tests need libpostal and its models to be installed
+    #[test]
+    #[ignore]
+    fn parse_address_test() {
+        let components = parse_address("781 Franklin Ave Crown Heights Brooklyn NY 11216").unwrap();
+        assert!(components.contains(&("house_number".to_string(), "781".to_string())));
+        assert!(components.contains(&("road".to_string(), "franklin ave".to_string())));
+        assert!(components.contains(&("postcode".to_string(), "11216".to_string())));
+        let address = parse_to_address("781 Franklin Ave Crown Heights Brooklyn NY 11216").unwrap();
+        assert_eq!(address.house_number.as_deref(), Some("781"));
+    }
+
+    #[test]
+    #[ignore]
+    fn expand_address_test() {
+        let expansions = expand_address("30 W 26th St Fl 7").unwrap();
+        assert!(expansions.contains(&"30 west 26th street floor 7".to_string()));
+    }
+}
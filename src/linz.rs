@@ -0,0 +1,362 @@
+//! The New Zealand [LINZ](https://www.linz.govt.nz/) provider.
+//!
+//! Geocoding methods are implemented on the [`Linz`](struct.Linz.html) struct. Land Information
+//! New Zealand publishes the authoritative NZ Addresses dataset on the LINZ Data Service, which
+//! is queried through its WFS interface. A free API key from the
+//! [LINZ Data Service](https://data.linz.govt.nz/) is required. Please see the
+//! [API documentation](https://www.linz.govt.nz/guidance/data-service/linz-data-service-guide/web-services/wfs-filtering-by-attribute)
+//! for details.
+//!
+//! Forward lookups match the full address case-insensitively, so queries should follow
+//! the dataset's format, e.g. "1 Willis Street, Wellington Central, Wellington". Reverse lookups
+//! search a box around the point and return the nearest address.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, Linz, Point};
+//!
+//! let linz = Linz::new("dcba4321".to_string());
+//! let address = "1 Willis Street, Wellington Central, Wellington";
+//! let res: Result<Vec<Point<f64>>, _> = linz.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::cmp::Ordering;
+use std::fmt::Debug;
+
+// The NZ Addresses layer
+const ADDRESS_LAYER: &str = "layer-105689";
+
+// Metres per degree of latitude
+const METRES_PER_DEGREE: f64 = 111_320.0;
+
+/// An instance of the LINZ Data Service address layer
+pub struct Linz {
+    client: Client,
+    endpoint: String,
+    api_key: String,
+    layer: String,
+}
+
+impl Linz {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "LINZ Data Service",
+        docs_url:
+            "https://www.linz.govt.nz/guidance/data-service/linz-data-service-guide/web-services",
+        terms_url: Some("https://data.linz.govt.nz/terms-of-use/"),
+        attribution: Some(
+            "Sourced from the LINZ Data Service and licensed for reuse under CC BY 4.0",
+        ),
+        rate_limit: None,
+    };
+
+    /// Create a new LINZ instance using an API key
+    pub fn new(api_key: String) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Linz {
+            client,
+            endpoint: "https://data.linz.govt.nz/".to_string(),
+            api_key,
+            layer: ADDRESS_LAYER.to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of a LINZ instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://data.linz.govt.nz/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// Query a different address layer, such as a newer release of NZ Addresses
+    pub fn with_layer(mut self, layer: &str) -> Self {
+        self.layer = layer.to_owned();
+        self
+    }
+
+    /// A forward-geocoding lookup of an address, returning at most `count` matches
+    ///
+    /// Addresses whose full address contains `address`, ignoring case, are matched.
+    pub fn forward_full<T>(
+        &self,
+        address: &str,
+        count: Option<u16>,
+    ) -> Result<LinzResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let filter = format!("full_address ILIKE '%{}%'", address.replace('\'', "''"));
+        let mut query = vec![("cql_filter", filter)];
+        if let Some(count) = count {
+            query.push(("count", count.to_string()));
+        }
+        self.get(query)
+    }
+
+    /// A reverse lookup of a point, returning the addresses within a box extending `radius`
+    /// metres from it, nearest first
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+        radius: u32,
+    ) -> Result<LinzResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let lon = point.x().to_f64().unwrap();
+        let lat = point.y().to_f64().unwrap();
+        let dlat = f64::from(radius) / METRES_PER_DEGREE;
+        let dlon = dlat / lat.to_radians().cos();
+        // WFS 2.0 orders EPSG:4326 coordinates as latitude, longitude
+        let bbox = format!(
+            "{},{},{},{},urn:ogc:def:crs:EPSG::4326",
+            lat - dlat,
+            lon - dlon,
+            lat + dlat,
+            lon + dlon
+        );
+        let mut res = self.get(vec![("bbox", bbox)])?;
+        res.features.sort_by(|a, b| {
+            let a = a.distance_to(lon, lat);
+            let b = b.distance_to(lon, lat);
+            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+        });
+        Ok(res)
+    }
+
+    fn get<T>(
+        &self,
+        mut query: Vec<(&'static str, String)>,
+    ) -> Result<LinzResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        query.extend(vec![
+            ("service", "WFS".to_string()),
+            ("version", "2.0.0".to_string()),
+            ("request", "GetFeature".to_string()),
+            ("typeNames", self.layer.clone()),
+            ("outputFormat", "json".to_string()),
+            ("srsName", "EPSG:4326".to_string()),
+        ]);
+        let resp = self
+            .client
+            .get(format!(
+                "{}services;key={}/wfs",
+                self.endpoint, self.api_key
+            ))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: LinzResponse<T> = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl<T> Forward<T> for Linz
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of an address, matching the full address.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(place, None)?;
+        Ok(res.features.iter().map(LinzFeature::point).collect())
+    }
+}
+
+impl<T> Reverse<T> for Linz
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the full address of the nearest address within
+    /// 100 metres.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point, 100)?;
+        Ok(res
+            .features
+            .into_iter()
+            .next()
+            .map(|feature| feature.properties.full_address))
+    }
+}
+
+/// The top-level full GeoJSON response returned by a WFS query
+///
+///```json
+///{
+///  "type": "FeatureCollection",
+///  "features": [
+///    {
+///      "type": "Feature",
+///      "id": "layer-105689.2010637",
+///      "geometry": { "type": "Point", "coordinates": [174.7767, -41.2841] },
+///      "properties": {
+///        "address_id": 2010637,
+///        "source_dataset": "AIMS",
+///        "change_id": 1234567,
+///        "full_address_number": "1",
+///        "full_road_name": "Willis Street",
+///        "full_address": "1 Willis Street, Wellington Central, Wellington",
+///        "territorial_authority": "Wellington City",
+///        "unit_type": null,
+///        "unit_value": null,
+///        "level_type": null,
+///        "level_value": null,
+///        "address_number_prefix": null,
+///        "address_number": 1,
+///        "address_number_suffix": null,
+///        "address_number_high": null,
+///        "road_name_prefix": null,
+///        "road_name": "Willis",
+///        "road_type_name": "Street",
+///        "road_suffix": null,
+///        "water_name": null,
+///        "water_body_name": null,
+///        "suburb_locality": "Wellington Central",
+///        "town_city": "Wellington",
+///        "address_class": "Thoroughfare",
+///        "address_lifecycle": "Current",
+///        "gd2000_xcoord": 174.7767,
+///        "gd2000_ycoord": -41.2841
+///      }
+///    }
+///  ],
+///  "totalFeatures": 1,
+///  "numberMatched": 1,
+///  "numberReturned": 1
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinzResponse<T>
+where
+    T: Float + Debug,
+{
+    #[serde(default = "Vec::new")]
+    pub features: Vec<LinzFeature<T>>,
+    pub number_matched: Option<u32>,
+    pub number_returned: Option<u32>,
+}
+
+/// An address
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LinzFeature<T>
+where
+    T: Float + Debug,
+{
+    pub id: Option<String>,
+    pub geometry: LinzGeometry<T>,
+    pub properties: LinzAddress,
+}
+
+impl<T> LinzFeature<T>
+where
+    T: Float + Debug,
+{
+    /// The location of the address
+    pub fn point(&self) -> Point<T> {
+        let (x, y) = self.geometry.coordinates;
+        Point::new(x, y)
+    }
+
+    // An equirectangular approximation of the distance to a point, in degrees of latitude
+    fn distance_to(&self, lon: f64, lat: f64) -> f64 {
+        let (x, y) = self.geometry.coordinates;
+        let dlon = (x.to_f64().unwrap() - lon) * lat.to_radians().cos();
+        let dlat = y.to_f64().unwrap() - lat;
+        dlon.hypot(dlat)
+    }
+}
+
+/// The point geometry of an address
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LinzGeometry<T>
+where
+    T: Float + Debug,
+{
+    pub coordinates: (T, T),
+}
+
+/// The attributes of an address in NZ Addresses
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LinzAddress {
+    /// The unique identifier of the address
+    pub address_id: u64,
+    pub full_address_number: Option<String>,
+    pub full_road_name: Option<String>,
+    /// The full address, e.g. `1 Willis Street, Wellington Central, Wellington`
+    pub full_address: String,
+    pub territorial_authority: Option<String>,
+    /// The kind of unit, e.g. `Flat` or `Unit`
+    pub unit_type: Option<String>,
+    pub unit_value: Option<String>,
+    pub level_type: Option<String>,
+    pub level_value: Option<String>,
+    pub address_number_prefix: Option<String>,
+    pub address_number: Option<u32>,
+    pub address_number_suffix: Option<String>,
+    /// The last number of the address, for ranges
+    pub address_number_high: Option<u32>,
+    pub road_name: Option<String>,
+    pub road_type_name: Option<String>,
+    pub road_suffix: Option<String>,
+    /// The name of a water route, for addresses reached by water
+    pub water_name: Option<String>,
+    pub water_body_name: Option<String>,
+    pub suburb_locality: Option<String>,
+    pub town_city: Option<String>,
+    /// `Thoroughfare` or `Water`
+    pub address_class: Option<String>,
+    pub address_lifecycle: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nearest_test() {
+        let res: LinzResponse<f64> = serde_json::from_str(
+            r#"{
+                "type": "FeatureCollection",
+                "features": [{
+                    "type": "Feature", "id": "layer-105689.2010637",
+                    "geometry": { "type": "Point", "coordinates": [174.7767, -41.2841] },
+                    "properties": {
+                        "address_id": 2010637, "full_address_number": "1",
+                        "full_road_name": "Willis Street",
+                        "full_address": "1 Willis Street, Wellington Central, Wellington",
+                        "address_number": 1, "road_name": "Willis", "road_type_name": "Street",
+                        "suburb_locality": "Wellington Central", "town_city": "Wellington",
+                        "address_class": "Thoroughfare"
+                    }
+                }],
+                "totalFeatures": 1, "numberMatched": 1, "numberReturned": 1
+            }"#,
+        )
+        .unwrap();
+        let feature = &res.features[0];
+        assert_eq!(feature.properties.address_id, 2010637);
+        assert_eq!(feature.point(), Point::new(174.7767, -41.2841));
+        assert!(feature.distance_to(174.7767, -41.2841) < 1e-9);
+    }
+}
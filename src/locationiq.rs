@@ -0,0 +1,298 @@
+//! The [LocationIQ](https://locationiq.com/) provider.
+//!
+//! LocationIQ offers a hosted, Nominatim-compatible geocoding API. Geocoding methods are
+//! implemented on the [`LocationIq`](struct.LocationIq.html) struct, and forward searches accept
+//! the same [`OpenstreetmapParams`](../openstreetmap/struct.OpenstreetmapParams.html) as the
+//! Nominatim provider. Please see the [API documentation](https://docs.locationiq.com/) for details.
+//!
+//! An API key is required. Rate limits apply, depending on your plan: the free tier allows
+//! 2 requests per second and 5,000 requests per day.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{LocationIq, Forward, Point};
+//!
+//! let liq = LocationIq::new("your-api-key".to_string());
+//! let address = "Schwabing, München";
+//! let res: Result<Vec<Point<f64>>, _> = liq.forward(address);
+//! ```
+use crate::info::{ProviderInfo, RateLimit};
+use crate::openstreetmap::{AddressDetails, OpenstreetmapParams, Sortable};
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{deserialize_float, deserialize_float_vec};
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use reqwest::StatusCode;
+use std::fmt::Debug;
+
+/// An instance of the LocationIQ geocoding service
+pub struct LocationIq {
+    api_key: String,
+    client: Client,
+    endpoint: String,
+}
+
+impl LocationIq {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "LocationIQ",
+        docs_url: "https://docs.locationiq.com/",
+        terms_url: Some("https://locationiq.com/tos"),
+        attribution: Some("© LocationIQ.com, © OpenStreetMap contributors"),
+        rate_limit: Some(RateLimit::per_second(2)),
+    };
+
+    /// Create a new LocationIQ geocoding instance using the default (US) endpoint
+    pub fn new(api_key: String) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        LocationIq {
+            api_key,
+            client,
+            endpoint: "https://us1.locationiq.com/v1/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of a LocationIQ geocoding instance, e.g. the EU region
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://eu1.locationiq.com/v1/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A forward-geocoding lookup of an address, returning a full detailed response
+    ///
+    /// Accepts an [`OpenstreetmapParams`](../openstreetmap/struct.OpenstreetmapParams.html) struct
+    /// for specifying options, including whether to include address details in the response
+    /// and whether to filter by a bounding box.
+    ///
+    /// Please see [the documentation](https://docs.locationiq.com/reference/search) for details.
+    ///
+    /// This method passes the `format` parameter to the API.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::{LocationIq, InputBounds, Point};
+    /// use geocoding::locationiq::LocationIqPlace;
+    /// use geocoding::openstreetmap::OpenstreetmapParams;
+    ///
+    /// let liq = LocationIq::new("your-api-key".to_string());
+    /// let viewbox = InputBounds::new(
+    ///     (-0.13806939125061035, 51.51989264641164),
+    ///     (-0.13427138328552246, 51.52319711775629),
+    /// );
+    /// let params = OpenstreetmapParams::new(&"UCL Centre for Advanced Spatial Analysis")
+    ///     .with_addressdetails(true)
+    ///     .with_viewbox(&viewbox)
+    ///     .build();
+    /// let res: Result<Vec<LocationIqPlace<f64>>, _> = liq.forward_full(&params);
+    /// ```
+    pub fn forward_full<T>(
+        &self,
+        params: &OpenstreetmapParams<T>,
+    ) -> Result<Vec<LocationIqPlace<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = params.as_query();
+        query.push(("format", String::from("json")));
+        let res: Vec<LocationIqPlace<T>> = self.get("search", query)?.unwrap_or_default();
+        params.order_results(res, |place| Point::new(place.lon, place.lat))
+    }
+
+    /// A reverse lookup of a point, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://docs.locationiq.com/reference/reverse) for details.
+    ///
+    /// This method passes the `format` and `addressdetails` parameters to the API.
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+    ) -> Result<Option<LocationIqPlace<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let query = vec![
+            ("lon", point.x().to_f64().unwrap().to_string()),
+            ("lat", point.y().to_f64().unwrap().to_string()),
+            ("format", String::from("json")),
+            ("addressdetails", String::from("1")),
+        ];
+        self.get("reverse", query)
+    }
+
+    // LocationIQ answers searches without any result with a 404 status
+    fn get<R>(
+        &self,
+        path: &str,
+        mut query: Vec<(&'static str, String)>,
+    ) -> Result<Option<R>, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        query.push(("key", self.api_key.clone()));
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?;
+        if resp.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let res: R = resp.error_for_status()?.json()?;
+        Ok(Some(res))
+    }
+}
+
+impl<T> Forward<T> for LocationIq
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of an address. Please see [the documentation](https://docs.locationiq.com/reference/search) for details.
+    ///
+    /// This method passes the `format` parameter to the API.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(&OpenstreetmapParams::new(place))?;
+        Ok(res
+            .iter()
+            .map(|place| Point::new(place.lon, place.lat))
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for LocationIq
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point. More detail on the format of the
+    /// returned `String` can be found [here](https://docs.locationiq.com/reference/reverse)
+    ///
+    /// This method passes the `format` and `addressdetails` parameters to the API.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        Ok(self.reverse_full(point)?.map(|place| place.display_name))
+    }
+}
+
+/// A geocoding result, in Nominatim's `json` format
+///
+/// Searches return a list of places, reverse lookups a single place.
+/// See [the documentation](https://docs.locationiq.com/reference/search) for more details
+///
+///```json
+///{
+///  "place_id": "263681481",
+///  "licence": "https://locationiq.com/attribution",
+///  "osm_type": "way",
+///  "osm_id": "355421084",
+///  "boundingbox": ["41.401227", "41.4015815", "2.1284918", "2.128952"],
+///  "lat": "41.40140675",
+///  "lon": "2.12872241167437",
+///  "display_name": "68, Carrer de Calatrava, les Tres Torres, Sarrià - Sant Gervasi, Barcelona, Catalonia, 08017, Spain",
+///  "class": "building",
+///  "type": "apartments",
+///  "importance": 0.741,
+///  "address": {
+///    "house_number": "68",
+///    "road": "Carrer de Calatrava",
+///    "city": "Barcelona",
+///    "postcode": "08017",
+///    "country": "Spain",
+///    "country_code": "es"
+///  }
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LocationIqPlace<T>
+where
+    T: Float + Debug,
+{
+    pub place_id: String,
+    pub licence: String,
+    pub osm_type: Option<String>,
+    pub osm_id: Option<String>,
+    /// The bounding box, as `[min latitude, max latitude, min longitude, max longitude]`
+    #[serde(deserialize_with = "deserialize_float_vec")]
+    pub boundingbox: Vec<T>,
+    #[serde(deserialize_with = "deserialize_float")]
+    pub lat: T,
+    #[serde(deserialize_with = "deserialize_float")]
+    pub lon: T,
+    pub display_name: String,
+    pub class: Option<String>,
+    pub r#type: Option<String>,
+    pub importance: Option<f64>,
+    pub address: Option<AddressDetails>,
+}
+
+impl<T> Sortable<T> for LocationIqPlace<T>
+where
+    T: Float + Debug,
+{
+    fn importance(&self) -> Option<f64> {
+        self.importance
+    }
+
+    // Only returned in the `jsonv2` format
+    fn place_rank(&self) -> Option<u64> {
+        None
+    }
+
+    fn position(&self) -> Point<T> {
+        Point::new(self.lon, self.lat)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn place_test() {
+        let place: LocationIqPlace<f64> = serde_json::from_str(
+            r#"{
+                "place_id": "263681481",
+                "licence": "https://locationiq.com/attribution",
+                "osm_type": "way",
+                "osm_id": "355421084",
+                "boundingbox": ["41.401227", "41.4015815", "2.1284918", "2.128952"],
+                "lat": "41.40140675",
+                "lon": "2.12872241167437",
+                "display_name": "68, Carrer de Calatrava, Barcelona, Catalonia, 08017, Spain",
+                "class": "building",
+                "type": "apartments",
+                "importance": 0.741,
+                "address": {
+                    "house_number": "68",
+                    "road": "Carrer de Calatrava",
+                    "city": "Barcelona",
+                    "country_code": "es"
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            Point::new(place.lon, place.lat),
+            Point::new(2.12872241167437, 41.40140675)
+        );
+        assert_eq!(
+            place.boundingbox,
+            vec![41.401227, 41.4015815, 2.1284918, 2.128952]
+        );
+        assert_eq!(place.address.unwrap().city.unwrap(), "Barcelona");
+    }
+}
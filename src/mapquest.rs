@@ -0,0 +1,645 @@
+//! The [MapQuest Geocoding API](https://developer.mapquest.com/documentation/geocoding-api/) provider.
+//!
+//! Geocoding methods are implemented on the [`MapQuest`](struct.MapQuest.html) struct, which
+//! can use either the licensed endpoint or the open (OpenStreetMap-based) endpoint. Both require
+//! an API key. Results carry a typed [`QualityCode`](struct.QualityCode.html) describing the
+//! granularity and confidence of each match.
+//!
+//! ### A Note on Coordinate Order
+//! MapQuest returns coordinates as `latLng` objects.
+//! `Geocoding` returns `Point` coordinates in `[Longitude, Latitude]` `(x, y)` order.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, MapQuest, Point};
+//!
+//! let mq = MapQuest::new("your-api-key".to_string());
+//! let address = "1555 Blake St, Denver, CO";
+//! let res: Result<Vec<Point<f64>>, _> = mq.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::GeocodingError;
+use crate::InputBounds;
+use crate::Point;
+use crate::StrictBounds;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use serde::Deserializer;
+use std::fmt::Debug;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// An instance of the MapQuest geocoding service
+pub struct MapQuest {
+    api_key: String,
+    client: Client,
+    endpoint: String,
+}
+
+/// An instance of a parameter builder for MapQuest geocoding
+pub struct MapQuestParams<'a, T>
+where
+    T: Float + Debug,
+{
+    location: &'a str,
+    max_results: Option<u16>,
+    bounding_box: Option<&'a InputBounds<T>>,
+    strict_bounds: Option<StrictBounds>,
+}
+
+impl<'a, T> MapQuestParams<'a, T>
+where
+    T: Float + Debug,
+{
+    /// Create a new MapQuest parameter builder
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::InputBounds;
+    /// use geocoding::mapquest::MapQuestParams;
+    ///
+    /// let bbox = InputBounds::new((-105.1, 39.6), (-104.8, 39.9));
+    /// let params = MapQuestParams::new(&"1555 Blake St")
+    ///     .with_max_results(5)
+    ///     .with_bounding_box(&bbox)
+    ///     .build();
+    /// ```
+    pub fn new(location: &'a str) -> MapQuestParams<'a, T> {
+        MapQuestParams {
+            location,
+            max_results: None,
+            bounding_box: None,
+            strict_bounds: None,
+        }
+    }
+
+    /// Set the `maxResults` property
+    pub fn with_max_results(&mut self, max_results: u16) -> &mut Self {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    /// Set the `boundingBox` property, which favours results within the box
+    pub fn with_bounding_box(&mut self, bounding_box: &'a InputBounds<T>) -> &mut Self {
+        self.bounding_box = Some(bounding_box);
+        self
+    }
+
+    /// Check the results against the `boundingBox`, which MapQuest only uses as a hint
+    pub fn with_strict_bounds(&mut self, strict_bounds: StrictBounds) -> &mut Self {
+        self.strict_bounds = Some(strict_bounds);
+        self
+    }
+
+    /// Build and return an instance of MapQuestParams
+    pub fn build(&self) -> MapQuestParams<'a, T> {
+        MapQuestParams {
+            location: self.location,
+            max_results: self.max_results,
+            bounding_box: self.bounding_box,
+            strict_bounds: self.strict_bounds,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("location", self.location.to_string())];
+        if let Some(max_results) = self.max_results {
+            query.push(("maxResults", max_results.to_string()));
+        }
+        if let Some(bbox) = self.bounding_box {
+            // MapQuest expects the upper-left and lower-right corners, in lat, lon order
+            query.push((
+                "boundingBox",
+                format!(
+                    "{},{},{},{}",
+                    bbox.maximum_lonlat.y().to_f64().unwrap(),
+                    bbox.minimum_lonlat.x().to_f64().unwrap(),
+                    bbox.minimum_lonlat.y().to_f64().unwrap(),
+                    bbox.maximum_lonlat.x().to_f64().unwrap()
+                ),
+            ));
+        }
+        query
+    }
+}
+
+impl MapQuest {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "MapQuest",
+        docs_url: "https://developer.mapquest.com/documentation/geocoding-api/",
+        terms_url: Some("https://hello.mapquest.com/terms-of-use"),
+        attribution: Some("© MapQuest"),
+        rate_limit: None,
+    };
+
+    /// Create a new MapQuest geocoding instance using the licensed endpoint
+    pub fn new(api_key: String) -> Self {
+        MapQuest::new_with_endpoint(
+            api_key,
+            "https://www.mapquestapi.com/geocoding/v1/".to_string(),
+        )
+    }
+
+    /// Create a new MapQuest geocoding instance using the open, OpenStreetMap-based endpoint
+    pub fn new_open(api_key: String) -> Self {
+        MapQuest::new_with_endpoint(
+            api_key,
+            "https://open.mapquestapi.com/geocoding/v1/".to_string(),
+        )
+    }
+
+    /// Create a new MapQuest geocoding instance with a custom endpoint.
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://www.mapquestapi.com/geocoding/v1/")
+    pub fn new_with_endpoint(api_key: String, endpoint: String) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        MapQuest {
+            api_key,
+            client,
+            endpoint,
+        }
+    }
+
+    /// A forward-geocoding lookup of an address, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://developer.mapquest.com/documentation/geocoding-api/address/get/) for details.
+    ///
+    /// This method passes the `thumbMaps` parameter to the API.
+    pub fn forward_full<T>(
+        &self,
+        params: &MapQuestParams<T>,
+    ) -> Result<MapQuestResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = params.as_query();
+        query.push(("thumbMaps", String::from("false")));
+        let mut res = self.get("address", query)?;
+        if res.info.statuscode != 0 {
+            return Err(GeocodingError::Forward);
+        }
+        if let (Some(bbox), Some(mode)) = (params.bounding_box, params.strict_bounds) {
+            for result in res.results.iter_mut() {
+                let locations = std::mem::take(&mut result.locations);
+                result.locations = bbox.enforce(locations, mode, |location| {
+                    Point::new(location.lat_lng.lng, location.lat_lng.lat)
+                })?;
+            }
+        }
+        Ok(res)
+    }
+
+    /// A reverse lookup of a point, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://developer.mapquest.com/documentation/geocoding-api/reverse/get/) for details.
+    ///
+    /// This method passes the `thumbMaps` parameter to the API.
+    pub fn reverse_full<T>(&self, point: &Point<T>) -> Result<MapQuestResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let query = vec![
+            (
+                "location",
+                // MapQuest expects lat, lon order
+                format!(
+                    "{},{}",
+                    point.y().to_f64().unwrap(),
+                    point.x().to_f64().unwrap()
+                ),
+            ),
+            ("thumbMaps", String::from("false")),
+        ];
+        let res = self.get("reverse", query)?;
+        if res.info.statuscode != 0 {
+            return Err(GeocodingError::Reverse);
+        }
+        Ok(res)
+    }
+
+    fn get<T>(
+        &self,
+        path: &str,
+        mut query: Vec<(&'static str, String)>,
+    ) -> Result<MapQuestResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        query.push(("key", self.api_key.clone()));
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: MapQuestResponse<T> = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl<T> Forward<T> for MapQuest
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of an address. Please see [the documentation](https://developer.mapquest.com/documentation/geocoding-api/address/get/) for details.
+    ///
+    /// This method passes the `thumbMaps` parameter to the API.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(&MapQuestParams::new(place))?;
+        Ok(res
+            .results
+            .iter()
+            .flat_map(|result| result.locations.iter())
+            .map(|location| Point::new(location.lat_lng.lng, location.lat_lng.lat))
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for MapQuest
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the address of the nearest location,
+    /// formatted as `street, city, state postcode, country`.
+    ///
+    /// This method passes the `thumbMaps` parameter to the API.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point)?;
+        Ok(res
+            .results
+            .first()
+            .and_then(|result| result.locations.first())
+            .map(|location| location.formatted()))
+    }
+}
+
+/// The top-level full JSON response returned by a geocoding request
+///
+/// See [the documentation](https://developer.mapquest.com/documentation/geocoding-api/address/get/) for more details
+///
+///```json
+///{
+///  "info": {
+///    "statuscode": 0,
+///    "copyright": {
+///      "text": "© 2022 MapQuest, Inc.",
+///      "imageUrl": "http://api.mqcdn.com/res/mqlogo.gif",
+///      "imageAltText": "© 2022 MapQuest, Inc."
+///    },
+///    "messages": []
+///  },
+///  "options": { "maxResults": -1, "ignoreLatLngInput": false },
+///  "results": [
+///    {
+///      "providedLocation": { "location": "1555 Blake St, Denver, CO" },
+///      "locations": [
+///        {
+///          "street": "1555 Blake St",
+///          "adminArea6": "",
+///          "adminArea6Type": "Neighborhood",
+///          "adminArea5": "Denver",
+///          "adminArea5Type": "City",
+///          "adminArea4": "Denver County",
+///          "adminArea4Type": "County",
+///          "adminArea3": "CO",
+///          "adminArea3Type": "State",
+///          "adminArea1": "US",
+///          "adminArea1Type": "Country",
+///          "postalCode": "80202-1614",
+///          "geocodeQualityCode": "P1AAA",
+///          "geocodeQuality": "POINT",
+///          "dragPoint": false,
+///          "sideOfStreet": "N",
+///          "linkId": "0",
+///          "unknownInput": "",
+///          "type": "s",
+///          "latLng": { "lat": 39.750307, "lng": -104.999472 },
+///          "displayLatLng": { "lat": 39.750307, "lng": -104.999472 }
+///        }
+///      ]
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MapQuestResponse<T>
+where
+    T: Float + Debug,
+{
+    pub info: Info,
+    pub results: Vec<MapQuestResult<T>>,
+}
+
+/// Status and copyright metadata
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Info {
+    pub statuscode: i32,
+    pub copyright: Option<Copyright>,
+    pub messages: Vec<String>,
+}
+
+/// Copyright notice to be displayed alongside results
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Copyright {
+    pub text: String,
+    pub image_url: String,
+    pub image_alt_text: String,
+}
+
+/// The locations found for one provided location
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapQuestResult<T>
+where
+    T: Float + Debug,
+{
+    pub provided_location: Option<serde_json::Value>,
+    pub locations: Vec<MapQuestLocation<T>>,
+}
+
+/// A geocoded location
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapQuestLocation<T>
+where
+    T: Float + Debug,
+{
+    pub street: String,
+    /// Neighborhood
+    #[serde(rename = "adminArea6")]
+    pub admin_area6: Option<String>,
+    /// City
+    #[serde(rename = "adminArea5")]
+    pub admin_area5: Option<String>,
+    /// County
+    #[serde(rename = "adminArea4")]
+    pub admin_area4: Option<String>,
+    /// State
+    #[serde(rename = "adminArea3")]
+    pub admin_area3: Option<String>,
+    /// Country
+    #[serde(rename = "adminArea1")]
+    pub admin_area1: Option<String>,
+    pub postal_code: Option<String>,
+    pub geocode_quality_code: QualityCode,
+    pub geocode_quality: String,
+    pub side_of_street: Option<String>,
+    pub lat_lng: LatLng<T>,
+    pub display_lat_lng: Option<LatLng<T>>,
+    pub map_url: Option<String>,
+}
+
+impl<T> MapQuestLocation<T>
+where
+    T: Float + Debug,
+{
+    /// The address of the location, formatted as `street, city, state postcode, country`
+    pub fn formatted(&self) -> String {
+        let state_postcode = [&self.admin_area3, &self.postal_code]
+            .iter()
+            .filter_map(|part| part.as_deref())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        [
+            Some(self.street.as_str()),
+            self.admin_area5.as_deref(),
+            Some(state_postcode.as_str()),
+            self.admin_area1.as_deref(),
+        ]
+        .iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+/// A coordinate pair
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LatLng<T>
+where
+    T: Float + Debug,
+{
+    pub lat: T,
+    pub lng: T,
+}
+
+/// A geocode quality code such as `P1AAA`
+///
+/// See [the documentation](https://developer.mapquest.com/documentation/geocoding-api/quality-codes/) for details.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(into = "String")]
+pub struct QualityCode {
+    pub granularity: Granularity,
+    /// The confidence in the street-level match
+    pub street: Confidence,
+    /// The confidence in the administrative area match
+    pub admin_area: Confidence,
+    /// The confidence in the postal code match
+    pub postal_code: Confidence,
+    code: String,
+}
+
+impl QualityCode {
+    /// The raw quality code
+    pub fn as_str(&self) -> &str {
+        &self.code
+    }
+}
+
+/// An error returned when parsing an invalid quality code
+#[derive(Error, Debug)]
+#[error("Invalid geocode quality code {0:?}")]
+pub struct ParseQualityCodeError(String);
+
+impl FromStr for QualityCode {
+    type Err = ParseQualityCodeError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        if code.len() != 5 || !code.is_ascii() {
+            return Err(ParseQualityCodeError(code.to_string()));
+        }
+        let confidence = |i: usize| Confidence::from(code.as_bytes()[i] as char);
+        Ok(QualityCode {
+            granularity: Granularity::from(&code[..2]),
+            street: confidence(2),
+            admin_area: confidence(3),
+            postal_code: confidence(4),
+            code: code.to_string(),
+        })
+    }
+}
+
+impl From<QualityCode> for String {
+    fn from(code: QualityCode) -> String {
+        code.code
+    }
+}
+
+impl<'de> Deserialize<'de> for QualityCode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// The granularity of a match, from the first two characters of a quality code
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Granularity {
+    /// `P1`: a point, such as an address point or a POI
+    Point,
+    /// `L1`: an interpolated address
+    Address,
+    /// `I1`: an intersection
+    Intersection,
+    /// `B1` to `B3`: a street or street block
+    Street,
+    /// `A1`
+    Country,
+    /// `A3`
+    State,
+    /// `A4`
+    County,
+    /// `A5`
+    City,
+    /// `A6`
+    Neighborhood,
+    /// `Z1` or `Z4`: a postal code
+    PostalCode,
+    /// `Z2` or `Z3`: an extended postal code, such as a ZIP+4
+    ExtendedPostalCode,
+    /// Any other granularity code
+    Other(String),
+}
+
+impl From<&str> for Granularity {
+    fn from(code: &str) -> Self {
+        match code {
+            "P1" => Granularity::Point,
+            "L1" => Granularity::Address,
+            "I1" => Granularity::Intersection,
+            "B1" | "B2" | "B3" => Granularity::Street,
+            "A1" => Granularity::Country,
+            "A3" => Granularity::State,
+            "A4" => Granularity::County,
+            "A5" => Granularity::City,
+            "A6" => Granularity::Neighborhood,
+            "Z1" | "Z4" => Granularity::PostalCode,
+            "Z2" | "Z3" => Granularity::ExtendedPostalCode,
+            other => Granularity::Other(other.to_string()),
+        }
+    }
+}
+
+/// The confidence of one part of a match
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Confidence {
+    /// `A`
+    Exact,
+    /// `B`
+    Good,
+    /// `C`
+    Approximate,
+    /// `X`: not applicable to this granularity
+    NotApplicable,
+}
+
+impl From<char> for Confidence {
+    fn from(code: char) -> Self {
+        match code {
+            'A' => Confidence::Exact,
+            'B' => Confidence::Good,
+            'C' => Confidence::Approximate,
+            _ => Confidence::NotApplicable,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn quality_code_test() {
+        let code: QualityCode = "P1AAA".parse().unwrap();
+        assert_eq!(code.granularity, Granularity::Point);
+        assert_eq!(code.street, Confidence::Exact);
+        assert_eq!(code.postal_code, Confidence::Exact);
+        let code: QualityCode = "A5XAX".parse().unwrap();
+        assert_eq!(code.granularity, Granularity::City);
+        assert_eq!(code.street, Confidence::NotApplicable);
+        assert_eq!(code.admin_area, Confidence::Exact);
+        assert!("P1".parse::<QualityCode>().is_err());
+    }
+
+    #[test]
+    fn bounding_box_test() {
+        let bbox = InputBounds::new((-105.1, 39.6), (-104.8, 39.9));
+        let params = MapQuestParams::new("1555 Blake St")
+            .with_bounding_box(&bbox)
+            .build();
+        assert_eq!(
+            params.as_query()[1],
+            ("boundingBox", "39.9,-105.1,39.6,-104.8".to_string())
+        );
+    }
+
+    #[test]
+    fn response_test() {
+        let res: MapQuestResponse<f64> = serde_json::from_str(
+            r#"{
+                "info": { "statuscode": 0, "messages": [] },
+                "options": { "maxResults": -1, "ignoreLatLngInput": false },
+                "results": [
+                    {
+                        "providedLocation": { "location": "1555 Blake St, Denver, CO" },
+                        "locations": [
+                            {
+                                "street": "1555 Blake St",
+                                "adminArea6": "",
+                                "adminArea5": "Denver",
+                                "adminArea4": "Denver County",
+                                "adminArea3": "CO",
+                                "adminArea1": "US",
+                                "postalCode": "80202-1614",
+                                "geocodeQualityCode": "P1AAA",
+                                "geocodeQuality": "POINT",
+                                "sideOfStreet": "N",
+                                "latLng": { "lat": 39.750307, "lng": -104.999472 }
+                            }
+                        ]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let location = &res.results[0].locations[0];
+        assert_eq!(location.geocode_quality_code.as_str(), "P1AAA");
+        assert_eq!(
+            location.formatted(),
+            "1555 Blake St, Denver, CO 80202-1614, US"
+        );
+    }
+}
@@ -0,0 +1,399 @@
+//! The [Naver Maps](https://www.ncloud.com/product/applicationService/maps) provider.
+//!
+//! Geocoding methods are implemented on the [`Naver`](struct.Naver.html) struct, using the
+//! Naver Cloud Platform Geocoding and Reverse Geocoding APIs. A client ID and client secret
+//! are required; they are sent as request headers.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, Naver, Point};
+//!
+//! let naver = Naver::new("your-client-id".to_string(), "your-client-secret".to_string());
+//! let address = "분당구 불정로 6";
+//! let res: Result<Vec<Point<f64>>, _> = naver.forward(address);
+//! ```
+use crate::deserialize_float;
+use crate::info::ProviderInfo;
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+
+/// An instance of the Naver Maps geocoding service
+pub struct Naver {
+    client_id: String,
+    client_secret: String,
+    client: Client,
+    endpoint: String,
+}
+
+impl Naver {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "Naver Maps",
+        docs_url: "https://api.ncloud-docs.com/docs/en/ai-naver-mapsgeocoding",
+        terms_url: Some("https://www.ncloud.com/policy/terms/service"),
+        attribution: Some("© NAVER Corp."),
+        rate_limit: None,
+    };
+
+    /// Create a new Naver geocoding instance using the default endpoint
+    pub fn new(client_id: String, client_secret: String) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Naver {
+            client_id,
+            client_secret,
+            client,
+            endpoint: "https://naveropenapi.apigw.ntruss.com/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of a Naver geocoding instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://naveropenapi.apigw.ntruss.com/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A forward-geocoding lookup of an address, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://api.ncloud-docs.com/docs/en/ai-naver-mapsgeocoding-geocode) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Naver;
+    /// use geocoding::naver::NaverResponse;
+    ///
+    /// let naver = Naver::new("your-client-id".to_string(), "your-client-secret".to_string());
+    /// let res: Result<NaverResponse<f64>, _> = naver.forward_full("분당구 불정로 6");
+    /// ```
+    pub fn forward_full<T>(&self, address: &str) -> Result<NaverResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let query = vec![("query", address.to_string())];
+        let res: NaverResponse<T> = self.get("map-geocode/v2/geocode", query)?;
+        match res.status.as_str() {
+            "OK" => Ok(res),
+            _ => Err(GeocodingError::Forward),
+        }
+    }
+
+    /// A reverse lookup of a point, returning a full detailed response
+    ///
+    /// Both the road name address and the land-lot address of the point are requested.
+    ///
+    /// Please see [the documentation](https://api.ncloud-docs.com/docs/en/ai-naver-mapsreversegeocoding-gc) for details.
+    pub fn reverse_full<T>(&self, point: &Point<T>) -> Result<NaverReverseResponse, GeocodingError>
+    where
+        T: Float + Debug,
+    {
+        let query = vec![
+            (
+                "coords",
+                format!(
+                    "{},{}",
+                    point.x().to_f64().unwrap(),
+                    point.y().to_f64().unwrap()
+                ),
+            ),
+            ("orders", String::from("roadaddr,addr")),
+            ("output", String::from("json")),
+        ];
+        let res: NaverReverseResponse = self.get("map-reversegeocode/v2/gc", query)?;
+        match res.status.code {
+            // 3 means that no result was found
+            0 | 3 => Ok(res),
+            _ => Err(GeocodingError::Reverse),
+        }
+    }
+
+    fn get<R>(&self, path: &str, query: Vec<(&'static str, String)>) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .header("X-NCP-APIGW-API-KEY-ID", &self.client_id)
+            .header("X-NCP-APIGW-API-KEY", &self.client_secret)
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl<T> Forward<T> for Naver
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of an address. Please see [the documentation](https://api.ncloud-docs.com/docs/en/ai-naver-mapsgeocoding-geocode) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(place)?;
+        Ok(res
+            .addresses
+            .iter()
+            .map(|address| Point::new(address.x, address.y))
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for Naver
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the road name address if there is one,
+    /// and the land-lot address otherwise.
+    /// Please see [the documentation](https://api.ncloud-docs.com/docs/en/ai-naver-mapsreversegeocoding-gc) for details.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point)?;
+        Ok(res.results.first().map(|result| result.formatted()))
+    }
+}
+
+/// The top-level full JSON response returned by a geocoding request
+///
+/// See [the documentation](https://api.ncloud-docs.com/docs/en/ai-naver-mapsgeocoding-geocode) for more details
+///
+///```json
+///{
+///  "status": "OK",
+///  "meta": { "totalCount": 1, "page": 1, "count": 1 },
+///  "addresses": [
+///    {
+///      "roadAddress": "경기도 성남시 분당구 불정로 6 NAVER그린팩토리",
+///      "jibunAddress": "경기도 성남시 분당구 정자동 178-1 NAVER그린팩토리",
+///      "englishAddress": "6, Buljeong-ro, Bundang-gu, Seongnam-si, Gyeonggi-do, Republic of Korea",
+///      "addressElements": [
+///        { "types": ["SIDO"], "longName": "경기도", "shortName": "경기도", "code": "" },
+///        { "types": ["POSTAL_CODE"], "longName": "13561", "shortName": "", "code": "" }
+///      ],
+///      "x": "127.1054328",
+///      "y": "37.3595963",
+///      "distance": 0.0
+///    }
+///  ],
+///  "errorMessage": ""
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NaverResponse<T>
+where
+    T: Float + Debug,
+{
+    pub status: String,
+    pub meta: Option<Meta>,
+    #[serde(default = "Vec::new")]
+    pub addresses: Vec<NaverAddress<T>>,
+    pub error_message: Option<String>,
+}
+
+/// Pagination metadata
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Meta {
+    pub total_count: u32,
+    pub page: Option<u32>,
+    pub count: u32,
+}
+
+/// A geocoding result
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NaverAddress<T>
+where
+    T: Float + Debug,
+{
+    /// The road name (도로명) address
+    pub road_address: String,
+    /// The land-lot (지번) address
+    pub jibun_address: String,
+    pub english_address: String,
+    pub address_elements: Vec<AddressElement>,
+    #[serde(deserialize_with = "deserialize_float")]
+    pub x: T,
+    #[serde(deserialize_with = "deserialize_float")]
+    pub y: T,
+    /// The distance from the `coordinate` passed as a hint, in meters
+    pub distance: f64,
+}
+
+/// A component of an address, e.g. of type `SIDO` (province), `ROAD_NAME` or `POSTAL_CODE`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressElement {
+    pub types: Vec<String>,
+    pub long_name: String,
+    pub short_name: String,
+    pub code: String,
+}
+
+/// The top-level full JSON response returned by a reverse geocoding request
+///
+/// See [the documentation](https://api.ncloud-docs.com/docs/en/ai-naver-mapsreversegeocoding-gc) for more details
+///
+///```json
+///{
+///  "status": { "code": 0, "name": "ok", "message": "done" },
+///  "results": [
+///    {
+///      "name": "roadaddr",
+///      "region": {
+///        "area0": { "name": "kr" },
+///        "area1": { "name": "경기도", "alias": "경기" },
+///        "area2": { "name": "성남시 분당구" },
+///        "area3": { "name": "정자동" },
+///        "area4": { "name": "" }
+///      },
+///      "land": {
+///        "type": "",
+///        "number1": "6",
+///        "number2": "",
+///        "name": "불정로",
+///        "addition0": { "type": "building", "value": "NAVER그린팩토리" }
+///      }
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NaverReverseResponse {
+    pub status: ReverseStatus,
+    #[serde(default)]
+    pub results: Vec<ReverseResult>,
+}
+
+/// The status of a reverse geocoding request; a `code` of 0 means success
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReverseStatus {
+    pub code: i32,
+    pub name: String,
+    pub message: String,
+}
+
+/// A reverse geocoding result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReverseResult {
+    /// The kind of result: `roadaddr` (road name address) or `addr` (land-lot address)
+    pub name: String,
+    pub region: Region,
+    pub land: Option<Land>,
+}
+
+impl ReverseResult {
+    /// The address, formatted from its region and land components
+    pub fn formatted(&self) -> String {
+        let mut parts: Vec<String> = [
+            &self.region.area1,
+            &self.region.area2,
+            &self.region.area3,
+            &self.region.area4,
+        ]
+        .iter()
+        .map(|area| area.name.clone())
+        .collect();
+        // Road name addresses are given by road and building number, not neighbourhood
+        if self.name == "roadaddr" {
+            parts.truncate(2);
+        }
+        if let Some(land) = &self.land {
+            parts.push(land.name.clone().unwrap_or_default());
+            let number = match land.number2.as_str() {
+                "" => land.number1.clone(),
+                number2 => format!("{}-{}", land.number1, number2),
+            };
+            parts.push(number);
+        }
+        parts.retain(|part| !part.is_empty());
+        parts.join(" ")
+    }
+}
+
+/// The administrative areas of a reverse geocoding result, from country (`area0`) to village (`area4`)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Region {
+    pub area0: Area,
+    pub area1: Area,
+    pub area2: Area,
+    pub area3: Area,
+    pub area4: Area,
+}
+
+/// An administrative area
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Area {
+    pub name: String,
+    pub alias: Option<String>,
+}
+
+/// The land (lot or building) of a reverse geocoding result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Land {
+    pub r#type: String,
+    pub number1: String,
+    pub number2: String,
+    /// The road name, for road name addresses
+    pub name: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reverse_formatted_test() {
+        let res: NaverReverseResponse = serde_json::from_str(
+            r#"{
+                "status": { "code": 0, "name": "ok", "message": "done" },
+                "results": [
+                    {
+                        "name": "roadaddr",
+                        "region": {
+                            "area0": { "name": "kr" },
+                            "area1": { "name": "경기도", "alias": "경기" },
+                            "area2": { "name": "성남시 분당구" },
+                            "area3": { "name": "정자동" },
+                            "area4": { "name": "" }
+                        },
+                        "land": { "type": "", "number1": "6", "number2": "", "name": "불정로" }
+                    },
+                    {
+                        "name": "addr",
+                        "region": {
+                            "area0": { "name": "kr" },
+                            "area1": { "name": "경기도", "alias": "경기" },
+                            "area2": { "name": "성남시 분당구" },
+                            "area3": { "name": "정자동" },
+                            "area4": { "name": "" }
+                        },
+                        "land": { "type": "1", "number1": "178", "number2": "1" }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(res.results[0].formatted(), "경기도 성남시 분당구 불정로 6");
+        assert_eq!(
+            res.results[1].formatted(),
+            "경기도 성남시 분당구 정자동 178-1"
+        );
+    }
+}
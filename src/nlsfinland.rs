@@ -0,0 +1,416 @@
+//! The Finnish [National Land Survey](https://www.maanmittauslaitos.fi/en) (Maanmittauslaitos) provider.
+//!
+//! Geocoding methods are implemented on the [`NlsFinland`](struct.NlsFinland.html) struct.
+//! The National Land Survey of Finland geocodes addresses, place names from its Geographic
+//! Names Register, and cadastral units by their property identifiers. Its API follows Pelias
+//! conventions, and requires a free API key. Please see the
+//! [API documentation](https://www.maanmittauslaitos.fi/en/geocoding-api) for details.
+//!
+//! Results are requested in the ETRS-TM35FIN national grid, and converted to WGS84 using
+//! [`ETRS_TM35FIN`](../transform/constant.ETRS_TM35FIN.html); their grid coordinates remain
+//! available through `grid_point`.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, NlsFinland, Point};
+//!
+//! let nls = NlsFinland::new("dcba4321".to_string());
+//! let address = "Opastinsilta 12, Helsinki";
+//! let res: Result<Vec<Point<f64>>, _> = nls.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::transform::ETRS_TM35FIN;
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+
+// Results are requested in ETRS-TM35FIN, and reverse lookups made in WGS84
+const OUTPUT_CRS: &str = "http://www.opengis.net/def/crs/EPSG/0/3067";
+const REQUEST_CRS: &str = "http://www.opengis.net/def/crs/EPSG/0/4326";
+
+/// An instance of the National Land Survey of Finland geocoding service
+pub struct NlsFinland {
+    client: Client,
+    endpoint: String,
+    api_key: String,
+}
+
+/// The data sources which may be searched
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NlsSource {
+    /// Addresses of buildings
+    Addresses,
+    /// Addresses interpolated along roads
+    InterpolatedRoadAddresses,
+    /// Place names from the Geographic Names Register
+    GeographicNames,
+    /// Cadastral units, searched by property identifier
+    CadastralUnits,
+}
+
+impl NlsSource {
+    fn as_str(self) -> &'static str {
+        match self {
+            NlsSource::Addresses => "addresses",
+            NlsSource::InterpolatedRoadAddresses => "interpolated-road-addresses",
+            NlsSource::GeographicNames => "geographic-names",
+            NlsSource::CadastralUnits => "cadastral-units",
+        }
+    }
+}
+
+/// An instance of a parameter builder for National Land Survey searches
+pub struct NlsParams<'a> {
+    text: &'a str,
+    sources: &'a [NlsSource],
+    size: Option<u8>,
+    lang: Option<&'a str>,
+}
+
+impl<'a> NlsParams<'a> {
+    /// Create a new National Land Survey parameter builder
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::nlsfinland::{NlsParams, NlsSource};
+    ///
+    /// let params = NlsParams::new("Pasila")
+    ///     .with_sources(&[NlsSource::GeographicNames])
+    ///     .with_lang("swe")
+    ///     .build();
+    /// ```
+    pub fn new(text: &'a str) -> NlsParams<'a> {
+        NlsParams {
+            text,
+            sources: &[],
+            size: None,
+            lang: None,
+        }
+    }
+
+    /// Set the `sources` property, the data sources to search
+    ///
+    /// All sources except cadastral units are searched by default.
+    pub fn with_sources(&mut self, sources: &'a [NlsSource]) -> &mut Self {
+        self.sources = sources;
+        self
+    }
+
+    /// Set the `size` property, the maximum number of results to return
+    pub fn with_size(&mut self, size: u8) -> &mut Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Set the `lang` property, the language of results: `fin`, `swe` or `eng`
+    pub fn with_lang(&mut self, lang: &'a str) -> &mut Self {
+        self.lang = Some(lang);
+        self
+    }
+
+    /// Build and return an instance of NlsParams
+    pub fn build(&self) -> NlsParams<'a> {
+        NlsParams {
+            text: self.text,
+            sources: self.sources,
+            size: self.size,
+            lang: self.lang,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("text", self.text.to_string())];
+        if !self.sources.is_empty() {
+            query.push(("sources", sources_param(self.sources)));
+        }
+        if let Some(size) = self.size {
+            query.push(("size", size.to_string()));
+        }
+        if let Some(lang) = self.lang {
+            query.push(("lang", lang.to_string()));
+        }
+        query
+    }
+}
+
+fn sources_param(sources: &[NlsSource]) -> String {
+    sources
+        .iter()
+        .map(|source| source.as_str())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+impl NlsFinland {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "National Land Survey of Finland",
+        docs_url: "https://www.maanmittauslaitos.fi/en/geocoding-api",
+        terms_url: Some("https://www.maanmittauslaitos.fi/en/opendata-licence-cc40"),
+        attribution: Some("© National Land Survey of Finland"),
+        rate_limit: None,
+    };
+
+    /// Create a new National Land Survey geocoding instance using an API key
+    pub fn new(api_key: String) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        NlsFinland {
+            client,
+            endpoint: "https://avoin-paikkatieto.maanmittauslaitos.fi/geocoding/v2/".to_string(),
+            api_key,
+        }
+    }
+
+    /// Set a custom endpoint of a National Land Survey geocoding instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://avoin-paikkatieto.maanmittauslaitos.fi/geocoding/v2/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A forward-geocoding search of addresses, place names or cadastral units, returning a
+    /// full detailed response
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::NlsFinland;
+    /// use geocoding::nlsfinland::{NlsParams, NlsResponse, NlsSource};
+    ///
+    /// let nls = NlsFinland::new("dcba4321".to_string());
+    /// let params = NlsParams::new("91-424-4-56")
+    ///     .with_sources(&[NlsSource::CadastralUnits])
+    ///     .build();
+    /// let res: Result<NlsResponse<f64>, _> = nls.forward_full(&params);
+    /// ```
+    pub fn forward_full<T>(&self, params: &NlsParams) -> Result<NlsResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get("pelias/search", params.as_query())
+    }
+
+    /// A reverse lookup of a point, returning at most `size` results from the given sources
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+        sources: &[NlsSource],
+        size: Option<u8>,
+    ) -> Result<NlsResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = vec![
+            ("point.lat", point.y().to_f64().unwrap().to_string()),
+            ("point.lon", point.x().to_f64().unwrap().to_string()),
+            ("request-crs", REQUEST_CRS.to_string()),
+        ];
+        if !sources.is_empty() {
+            query.push(("sources", sources_param(sources)));
+        }
+        if let Some(size) = size {
+            query.push(("size", size.to_string()));
+        }
+        self.get("pelias/reverse", query)
+    }
+
+    fn get<T>(
+        &self,
+        path: &str,
+        mut query: Vec<(&'static str, String)>,
+    ) -> Result<NlsResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        query.push(("crs", OUTPUT_CRS.to_string()));
+        query.push(("api-key", self.api_key.clone()));
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: NlsResponse<T> = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl<T> Forward<T> for NlsFinland
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of an address or place name. Please see [the documentation](https://www.maanmittauslaitos.fi/en/geocoding-api) for details.
+    ///
+    /// This method passes the `text` parameter to the API.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(&NlsParams::new(place))?;
+        Ok(res.features.iter().map(NlsFeature::point).collect())
+    }
+}
+
+impl<T> Reverse<T> for NlsFinland
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the `label` of the nearest address.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(
+            point,
+            &[NlsSource::Addresses, NlsSource::InterpolatedRoadAddresses],
+            Some(1),
+        )?;
+        Ok(res
+            .features
+            .into_iter()
+            .next()
+            .and_then(|feature| feature.properties.label))
+    }
+}
+
+/// The top-level full GeoJSON response returned by a search
+///
+///```json
+///{
+///  "type": "FeatureCollection",
+///  "features": [
+///    {
+///      "type": "Feature",
+///      "geometry": { "type": "Point", "coordinates": [385544.12, 6674021.33] },
+///      "properties": {
+///        "source": "addresses",
+///        "label": "Opastinsilta 12, Helsinki",
+///        "label:municipality": "Helsinki",
+///        "katunimi": "Opastinsilta",
+///        "katunumero": "12",
+///        "postinumero": "00520",
+///        "kuntatunnus": "091",
+///        "kuntanimiFin": "Helsinki",
+///        "kuntanimiSwe": "Helsingfors",
+///        "rank": 1,
+///        "confidence": 1.0
+///      }
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NlsResponse<T>
+where
+    T: Float + Debug,
+{
+    pub r#type: String,
+    #[serde(default = "Vec::new")]
+    pub features: Vec<NlsFeature<T>>,
+}
+
+/// A result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NlsFeature<T>
+where
+    T: Float + Debug,
+{
+    pub r#type: String,
+    pub geometry: NlsGeometry<T>,
+    pub properties: NlsProperties,
+}
+
+impl<T> NlsFeature<T>
+where
+    T: Float + Debug,
+{
+    /// The location of the result in WGS84
+    pub fn point(&self) -> Point<T> {
+        ETRS_TM35FIN.unproject(&self.grid_point())
+    }
+
+    /// The location of the result in ETRS-TM35FIN, as an easting and northing
+    pub fn grid_point(&self) -> Point<T> {
+        let (easting, northing) = self.geometry.coordinates;
+        Point::new(easting, northing)
+    }
+}
+
+/// The point geometry of a result, in ETRS-TM35FIN
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NlsGeometry<T>
+where
+    T: Float + Debug,
+{
+    pub r#type: String,
+    pub coordinates: (T, T),
+}
+
+/// The properties of a result
+///
+/// Properties vary with the source; those of other sources are omitted.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NlsProperties {
+    /// The source of the result, e.g. `addresses` or `geographic-names`
+    pub source: String,
+    pub label: Option<String>,
+    #[serde(rename = "label:municipality")]
+    pub label_municipality: Option<String>,
+    /// The street name of an address
+    pub katunimi: Option<String>,
+    /// The number of an address
+    pub katunumero: Option<String>,
+    pub postinumero: Option<String>,
+    /// The three-digit municipality code
+    pub kuntatunnus: Option<String>,
+    pub kuntanimi_fin: Option<String>,
+    pub kuntanimi_swe: Option<String>,
+    /// The place name of a geographic name
+    pub nimi: Option<String>,
+    /// The property identifier of a cadastral unit, e.g. `91-424-4-56`
+    pub kiinteistotunnus: Option<String>,
+    pub rank: Option<u32>,
+    pub confidence: Option<f64>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn search_test() {
+        let res: NlsResponse<f64> = serde_json::from_str(
+            r#"{
+                "type": "FeatureCollection",
+                "features": [{
+                    "type": "Feature",
+                    "geometry": { "type": "Point", "coordinates": [386379.38, 6672136.60] },
+                    "properties": {
+                        "source": "geographic-names", "label": "Tuomiokirkko, Helsinki",
+                        "label:municipality": "Helsinki", "nimi": "Tuomiokirkko",
+                        "kuntatunnus": "091", "kuntanimiFin": "Helsinki",
+                        "kuntanimiSwe": "Helsingfors", "rank": 1, "confidence": 0.9
+                    }
+                }]
+            }"#,
+        )
+        .unwrap();
+        let feature = &res.features[0];
+        assert_eq!(feature.properties.kuntatunnus.as_deref(), Some("091"));
+        let point = feature.point();
+        assert!((point.x() - 24.952_222).abs() < 1e-6);
+        assert!((point.y() - 60.170_278).abs() < 1e-6);
+    }
+}
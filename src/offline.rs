@@ -0,0 +1,616 @@
+//! Offline geocoders backed by GeoNames datasets.
+//!
+//! The [`OfflineReverse`](struct.OfflineReverse.html) provider loads a
+//! [GeoNames](https://www.geonames.org/) cities dataset into an R-tree, and answers reverse
+//! lookups with the nearest place and its country, without network access. It suits
+//! high-volume, low-precision lookups, such as labelling points with a nearby town.
+//!
+//! The datasets are the tab-separated `cities500.txt`, `cities1000.txt`, `cities5000.txt` and
+//! `cities15000.txt` files from the [GeoNames export](https://download.geonames.org/export/dump/),
+//! listing places with at least that many inhabitants, and are licensed under CC BY 4.0. They
+//! aren't bundled with this crate, and must be downloaded and unzipped.
+//!
+//! The [`OfflinePostcodes`](struct.OfflinePostcodes.html) provider resolves postcodes to their
+//! centroids, and points to their nearest postcode, from the GeoNames
+//! [postal code export](https://download.geonames.org/export/zip/) (e.g. `allCountries.txt` or
+//! `DE.txt`) or a CSV file of your own.
+//!
+//! This module is only available with the `offline` feature.
+//!
+//! ### Example
+//!
+//! ```no_run
+//! use geocoding::{OfflineReverse, Point, Reverse};
+//!
+//! let offline = OfflineReverse::from_path("cities1000.txt")
+//!     .unwrap()
+//!     .with_max_distance(50.0);
+//! let p = Point::new(2.12870, 41.40139);
+//! let res: Option<String> = offline.reverse(&p).unwrap();
+//! assert_eq!(res.as_deref(), Some("Barcelona, ES"));
+//! ```
+use crate::info::ProviderInfo;
+use crate::GeocodingError;
+use crate::Point;
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use rstar::primitives::GeomWithData;
+use rstar::RTree;
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io::Read;
+use std::path::Path;
+
+// The mean radius of the Earth, in kilometres
+const EARTH_RADIUS: f64 = 6371.0;
+
+// Places are indexed by their position on the unit sphere, so that the nearest place by
+// straight-line distance is also the nearest along the surface
+type IndexedPlace = GeomWithData<[f64; 3], usize>;
+
+/// An instance of the offline reverse geocoder
+pub struct OfflineReverse {
+    places: Vec<Place>,
+    tree: RTree<IndexedPlace>,
+    max_distance: Option<f64>,
+}
+
+/// A populated place from the GeoNames dataset
+#[derive(Clone, Debug, PartialEq)]
+pub struct Place {
+    pub geonameid: u64,
+    pub name: String,
+    /// The name in plain ASCII characters
+    pub ascii_name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// The GeoNames feature code, e.g. `PPLC` for a capital
+    pub feature_code: String,
+    /// The ISO 3166-1 alpha-2 country code
+    pub country_code: String,
+    /// The code of the first-level administrative division, e.g. `56` for Catalonia
+    pub admin1_code: String,
+    pub population: u64,
+    /// The IANA time zone, e.g. `Europe/Madrid`
+    pub timezone: String,
+}
+
+impl Place {
+    /// The location of the place
+    pub fn point<T>(&self) -> Point<T>
+    where
+        T: Float + Debug,
+    {
+        Point::new(
+            T::from(self.longitude).unwrap(),
+            T::from(self.latitude).unwrap(),
+        )
+    }
+
+    fn label(&self) -> String {
+        format!("{}, {}", self.name, self.country_code)
+    }
+}
+
+// A row of a GeoNames cities file
+#[derive(Deserialize)]
+struct Record {
+    geonameid: u64,
+    name: String,
+    asciiname: String,
+    _alternatenames: IgnoredAny,
+    latitude: f64,
+    longitude: f64,
+    _feature_class: IgnoredAny,
+    feature_code: String,
+    country_code: String,
+    _cc2: IgnoredAny,
+    admin1_code: String,
+    _admin2_code: IgnoredAny,
+    _admin3_code: IgnoredAny,
+    _admin4_code: IgnoredAny,
+    population: u64,
+    _elevation: IgnoredAny,
+    _dem: IgnoredAny,
+    timezone: String,
+}
+
+impl OfflineReverse {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "GeoNames (offline)",
+        docs_url: "https://download.geonames.org/export/dump/readme.txt",
+        terms_url: Some("https://creativecommons.org/licenses/by/4.0/"),
+        attribution: Some("Data from GeoNames, licensed under CC BY 4.0"),
+        rate_limit: None,
+    };
+
+    /// Load a GeoNames cities dataset from a file
+    pub fn from_path<P>(path: P) -> Result<Self, GeocodingError>
+    where
+        P: AsRef<Path>,
+    {
+        let reader = reader_builder().from_path(path)?;
+        Self::load(reader)
+    }
+
+    /// Load a GeoNames cities dataset from a reader
+    pub fn from_reader<R>(reader: R) -> Result<Self, GeocodingError>
+    where
+        R: Read,
+    {
+        Self::load(reader_builder().from_reader(reader))
+    }
+
+    fn load<R>(mut reader: csv::Reader<R>) -> Result<Self, GeocodingError>
+    where
+        R: Read,
+    {
+        let mut places = vec![];
+        for record in reader.deserialize() {
+            let record: Record = record?;
+            places.push(Place {
+                geonameid: record.geonameid,
+                name: record.name,
+                ascii_name: record.asciiname,
+                latitude: record.latitude,
+                longitude: record.longitude,
+                feature_code: record.feature_code,
+                country_code: record.country_code,
+                admin1_code: record.admin1_code,
+                population: record.population,
+                timezone: record.timezone,
+            });
+        }
+        let indexed = places
+            .iter()
+            .enumerate()
+            .map(|(index, place)| {
+                GeomWithData::new(to_unit_sphere(place.latitude, place.longitude), index)
+            })
+            .collect();
+        Ok(OfflineReverse {
+            places,
+            tree: RTree::bulk_load(indexed),
+            max_distance: None,
+        })
+    }
+
+    /// Only return places within `max_distance` kilometres of the point
+    pub fn with_max_distance(mut self, max_distance: f64) -> Self {
+        self.max_distance = Some(max_distance);
+        self
+    }
+
+    /// The number of places loaded
+    pub fn len(&self) -> usize {
+        self.places.len()
+    }
+
+    /// Whether no places were loaded
+    pub fn is_empty(&self) -> bool {
+        self.places.is_empty()
+    }
+
+    /// Return the nearest place to a point, with its distance in kilometres
+    pub fn reverse_full<T>(&self, point: &Point<T>) -> Option<(&Place, f64)>
+    where
+        T: Float + Debug,
+    {
+        self.nearest(point, 1).into_iter().next()
+    }
+
+    /// Return the `n` nearest places to a point, nearest first, with their distances in
+    /// kilometres
+    pub fn nearest<T>(&self, point: &Point<T>, n: usize) -> Vec<(&Place, f64)>
+    where
+        T: Float + Debug,
+    {
+        let query = to_unit_sphere(point.y().to_f64().unwrap(), point.x().to_f64().unwrap());
+        self.tree
+            .nearest_neighbor_iter_with_distance_2(&query)
+            .map(|(indexed, chord2)| (&self.places[indexed.data], to_kilometres(chord2)))
+            .take_while(|(_, distance)| self.max_distance.map_or(true, |max| *distance <= max))
+            .take(n)
+            .collect()
+    }
+}
+
+impl<T> Reverse<T> for OfflineReverse
+where
+    T: Float + Debug,
+{
+    /// A reverse lookup of a point, returning the name and country code of the nearest place.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        Ok(self.reverse_full(point).map(|(place, _)| place.label()))
+    }
+}
+
+/// An instance of the offline postcode geocoder
+pub struct OfflinePostcodes {
+    postcodes: Vec<Postcode>,
+    index: HashMap<String, Vec<usize>>,
+    tree: RTree<IndexedPlace>,
+    max_distance: Option<f64>,
+}
+
+/// A postcode and the place it serves
+///
+/// A postcode may serve several places, and appear in several countries, so it can have several
+/// entries.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Postcode {
+    /// The ISO 3166-1 alpha-2 country code
+    pub country_code: String,
+    pub postal_code: String,
+    pub place_name: String,
+    /// The name of the first-level administrative division, e.g. `Berlin`
+    pub admin_name1: String,
+    pub admin_code1: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// The GeoNames accuracy of the location: 1 if estimated, 4 if from a GeoNames place, and
+    /// 6 if a centroid
+    pub accuracy: Option<u8>,
+}
+
+impl Postcode {
+    /// The location of the postcode
+    pub fn point<T>(&self) -> Point<T>
+    where
+        T: Float + Debug,
+    {
+        Point::new(
+            T::from(self.longitude).unwrap(),
+            T::from(self.latitude).unwrap(),
+        )
+    }
+}
+
+// A row of a GeoNames postal code file
+#[derive(Deserialize)]
+struct PostcodeRecord {
+    country_code: String,
+    postal_code: String,
+    place_name: String,
+    admin_name1: String,
+    admin_code1: String,
+    _admin_name2: IgnoredAny,
+    _admin_code2: IgnoredAny,
+    _admin_name3: IgnoredAny,
+    _admin_code3: IgnoredAny,
+    latitude: f64,
+    longitude: f64,
+    accuracy: Option<u8>,
+}
+
+// A row of a user-supplied postcode CSV file
+#[derive(Deserialize)]
+struct CsvRecord {
+    postcode: String,
+    latitude: f64,
+    longitude: f64,
+    #[serde(default)]
+    country_code: String,
+    #[serde(default)]
+    place_name: String,
+}
+
+impl OfflinePostcodes {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "GeoNames postal codes (offline)",
+        docs_url: "https://download.geonames.org/export/zip/readme.txt",
+        terms_url: Some("https://creativecommons.org/licenses/by/4.0/"),
+        attribution: Some("Data from GeoNames, licensed under CC BY 4.0"),
+        rate_limit: None,
+    };
+
+    /// Load a GeoNames postal code dataset from a file
+    pub fn from_path<P>(path: P) -> Result<Self, GeocodingError>
+    where
+        P: AsRef<Path>,
+    {
+        let reader = reader_builder().from_path(path)?;
+        Self::load_geonames(reader)
+    }
+
+    /// Load a GeoNames postal code dataset from a reader
+    pub fn from_reader<R>(reader: R) -> Result<Self, GeocodingError>
+    where
+        R: Read,
+    {
+        Self::load_geonames(reader_builder().from_reader(reader))
+    }
+
+    /// Load postcodes from a CSV file
+    ///
+    /// The file must have a header row, with `postcode`, `latitude` and `longitude` columns,
+    /// and optional `country_code` and `place_name` columns.
+    pub fn from_csv_path<P>(path: P) -> Result<Self, GeocodingError>
+    where
+        P: AsRef<Path>,
+    {
+        Self::load_csv(csv::Reader::from_path(path)?)
+    }
+
+    /// Load postcodes from a CSV reader, as in [`from_csv_path`](#method.from_csv_path)
+    pub fn from_csv_reader<R>(reader: R) -> Result<Self, GeocodingError>
+    where
+        R: Read,
+    {
+        Self::load_csv(csv::Reader::from_reader(reader))
+    }
+
+    fn load_geonames<R>(mut reader: csv::Reader<R>) -> Result<Self, GeocodingError>
+    where
+        R: Read,
+    {
+        let mut postcodes = vec![];
+        for record in reader.deserialize() {
+            let record: PostcodeRecord = record?;
+            postcodes.push(Postcode {
+                country_code: record.country_code,
+                postal_code: record.postal_code,
+                place_name: record.place_name,
+                admin_name1: record.admin_name1,
+                admin_code1: record.admin_code1,
+                latitude: record.latitude,
+                longitude: record.longitude,
+                accuracy: record.accuracy,
+            });
+        }
+        Ok(Self::index(postcodes))
+    }
+
+    fn load_csv<R>(mut reader: csv::Reader<R>) -> Result<Self, GeocodingError>
+    where
+        R: Read,
+    {
+        let mut postcodes = vec![];
+        for record in reader.deserialize() {
+            let record: CsvRecord = record?;
+            postcodes.push(Postcode {
+                country_code: record.country_code,
+                postal_code: record.postcode,
+                place_name: record.place_name,
+                admin_name1: String::new(),
+                admin_code1: String::new(),
+                latitude: record.latitude,
+                longitude: record.longitude,
+                accuracy: None,
+            });
+        }
+        Ok(Self::index(postcodes))
+    }
+
+    fn index(postcodes: Vec<Postcode>) -> Self {
+        let mut index: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, postcode) in postcodes.iter().enumerate() {
+            index
+                .entry(normalize_postcode(&postcode.postal_code))
+                .or_default()
+                .push(i);
+        }
+        let indexed = postcodes
+            .iter()
+            .enumerate()
+            .map(|(i, postcode)| {
+                GeomWithData::new(to_unit_sphere(postcode.latitude, postcode.longitude), i)
+            })
+            .collect();
+        OfflinePostcodes {
+            postcodes,
+            index,
+            tree: RTree::bulk_load(indexed),
+            max_distance: None,
+        }
+    }
+
+    /// Only return postcodes within `max_distance` kilometres of the point
+    pub fn with_max_distance(mut self, max_distance: f64) -> Self {
+        self.max_distance = Some(max_distance);
+        self
+    }
+
+    /// The number of postcode entries loaded
+    pub fn len(&self) -> usize {
+        self.postcodes.len()
+    }
+
+    /// Whether no postcodes were loaded
+    pub fn is_empty(&self) -> bool {
+        self.postcodes.is_empty()
+    }
+
+    /// Return the entries of a postcode, ignoring case and spaces
+    ///
+    /// If `country_code` is given, only entries in that country are returned.
+    pub fn lookup(&self, postcode: &str, country_code: Option<&str>) -> Vec<&Postcode> {
+        self.index
+            .get(&normalize_postcode(postcode))
+            .map(|entries| {
+                entries
+                    .iter()
+                    .map(|&i| &self.postcodes[i])
+                    .filter(|entry| {
+                        country_code.map_or(true, |country_code| {
+                            entry.country_code.eq_ignore_ascii_case(country_code)
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Return the centroid of a postcode's entries in a country
+    pub fn centroid<T>(&self, postcode: &str, country_code: &str) -> Option<Point<T>>
+    where
+        T: Float + Debug,
+    {
+        centroid(&self.lookup(postcode, Some(country_code)))
+    }
+
+    /// Return the nearest postcode entry to a point, with its distance in kilometres
+    pub fn reverse_full<T>(&self, point: &Point<T>) -> Option<(&Postcode, f64)>
+    where
+        T: Float + Debug,
+    {
+        self.nearest(point, 1).into_iter().next()
+    }
+
+    /// Return the `n` nearest postcode entries to a point, nearest first, with their distances
+    /// in kilometres
+    pub fn nearest<T>(&self, point: &Point<T>, n: usize) -> Vec<(&Postcode, f64)>
+    where
+        T: Float + Debug,
+    {
+        let query = to_unit_sphere(point.y().to_f64().unwrap(), point.x().to_f64().unwrap());
+        self.tree
+            .nearest_neighbor_iter_with_distance_2(&query)
+            .map(|(indexed, chord2)| (&self.postcodes[indexed.data], to_kilometres(chord2)))
+            .take_while(|(_, distance)| self.max_distance.map_or(true, |max| *distance <= max))
+            .take(n)
+            .collect()
+    }
+}
+
+impl<T> Forward<T> for OfflinePostcodes
+where
+    T: Float + Debug,
+{
+    /// A lookup of a postcode, returning its centroid in each country it appears in.
+    fn forward(&self, postcode: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let mut countries: Vec<&str> = vec![];
+        let entries = self.lookup(postcode, None);
+        for entry in &entries {
+            if !countries.contains(&entry.country_code.as_str()) {
+                countries.push(&entry.country_code);
+            }
+        }
+        Ok(countries
+            .into_iter()
+            .filter_map(|country_code| {
+                let in_country: Vec<&Postcode> = entries
+                    .iter()
+                    .copied()
+                    .filter(|entry| entry.country_code == country_code)
+                    .collect();
+                centroid(&in_country)
+            })
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for OfflinePostcodes
+where
+    T: Float + Debug,
+{
+    /// A reverse lookup of a point, returning the nearest postcode.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        Ok(self
+            .reverse_full(point)
+            .map(|(postcode, _)| postcode.postal_code.clone()))
+    }
+}
+
+fn normalize_postcode(postcode: &str) -> String {
+    postcode
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+// The mean location of a postcode's entries, which are close enough to average their
+// coordinates
+fn centroid<T>(entries: &[&Postcode]) -> Option<Point<T>>
+where
+    T: Float + Debug,
+{
+    if entries.is_empty() {
+        return None;
+    }
+    let n = entries.len() as f64;
+    let latitude = entries.iter().map(|entry| entry.latitude).sum::<f64>() / n;
+    let longitude = entries.iter().map(|entry| entry.longitude).sum::<f64>() / n;
+    Some(Point::new(T::from(longitude)?, T::from(latitude)?))
+}
+
+fn reader_builder() -> csv::ReaderBuilder {
+    let mut builder = csv::ReaderBuilder::new();
+    builder.delimiter(b'\t').has_headers(false).quoting(false);
+    builder
+}
+
+fn to_unit_sphere(lat: f64, lon: f64) -> [f64; 3] {
+    let (lat, lon) = (lat.to_radians(), lon.to_radians());
+    [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+}
+
+// The great-circle distance spanned by a squared chord of the unit sphere
+fn to_kilometres(chord2: f64) -> f64 {
+    2.0 * (chord2.sqrt() / 2.0).min(1.0).asin() * EARTH_RADIUS
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const CITIES: &str = "\
+3128760\tBarcelona\tBarcelona\tBarcelone,Barcellona\t41.38879\t2.15899\tP\tPPLA\tES\t\t56\tB\t08019\t\t1620343\t\t47\tEurope/Madrid\t2024-01-11
+2643743\tLondon\tLondon\tLondres,Londra\t51.50853\t-0.12574\tP\tPPLC\tGB\t\tENG\tGLA\t\t\t8961989\t\t25\tEurope/London\t2023-01-12
+4036284\tAlofi\tAlofi\t\t-19.05451\t-169.91768\tP\tPPLC\tNU\t\t\t\t\t\t624\t\t23\tPacific/Niue\t2019-09-05
+4032243\tVaini\tVaini\t\t-21.2\t-175.2\tP\tPPLA\tTO\t\t04\t\t\t\t3073\t\t\tPacific/Tongatapu\t2012-01-18
+";
+
+    #[test]
+    fn nearest_test() {
+        let offline = OfflineReverse::from_reader(CITIES.as_bytes()).unwrap();
+        assert_eq!(offline.len(), 4);
+        let res = offline.reverse(&Point::new(2.12870, 41.40139)).unwrap();
+        assert_eq!(res.as_deref(), Some("Barcelona, ES"));
+        let (place, distance) = offline.reverse_full(&Point::new(-0.13, 51.52)).unwrap();
+        assert_eq!(place.admin1_code, "ENG");
+        assert!(distance < 2.0);
+        // Across the antimeridian, Vaini is nearer than Alofi
+        let (place, _) = offline.reverse_full(&Point::new(179.9, -21.0)).unwrap();
+        assert_eq!(place.name, "Vaini");
+        let offline = offline.with_max_distance(100.0);
+        assert!(offline.reverse_full(&Point::new(0.0, 0.0)).is_none());
+    }
+
+    const POSTCODES: &str = "\
+DE\t10115\tBerlin\tBerlin\tBE\t\t00\tBerlin, Stadt\t11000\t52.5323\t13.3846\t6
+DE\t79098\tFreiburg im Breisgau\tBaden-Württemberg\tBW\tRegierungsbezirk Freiburg\t083\tFreiburg im Breisgau\t08311\t48.0\t7.84\t4
+DE\t79098\tFreiburg im Breisgau\tBaden-Württemberg\tBW\tRegierungsbezirk Freiburg\t083\tFreiburg im Breisgau\t08311\t48.0\t7.86\t4
+FR\t79098\tNowhere\tNouvelle-Aquitaine\t75\t\t\t\t\t46.5\t-0.3\t
+";
+
+    #[test]
+    fn postcodes_test() {
+        let postcodes = OfflinePostcodes::from_reader(POSTCODES.as_bytes()).unwrap();
+        assert_eq!(postcodes.len(), 4);
+        assert_eq!(postcodes.lookup("10115", None)[0].accuracy, Some(6));
+        let res: Vec<Point<f64>> = postcodes.forward("79098").unwrap();
+        assert_eq!(res.len(), 2);
+        assert!((res[0].x() - 7.85).abs() < 1e-9);
+        assert_eq!(
+            postcodes.centroid("79098", "fr"),
+            Some(Point::new(-0.3, 46.5))
+        );
+        let res = postcodes.reverse(&Point::new(13.4, 52.5)).unwrap();
+        assert_eq!(res.as_deref(), Some("10115"));
+    }
+
+    #[test]
+    fn postcodes_csv_test() {
+        let csv = "postcode,latitude,longitude\nSW1A 1AA,51.501009,-0.141588\n";
+        let postcodes = OfflinePostcodes::from_csv_reader(csv.as_bytes()).unwrap();
+        let entries = postcodes.lookup("sw1a1aa", None);
+        assert_eq!(entries[0].postal_code, "SW1A 1AA");
+        assert_eq!(entries[0].point::<f64>(), Point::new(-0.141588, 51.501009));
+    }
+}
@@ -18,48 +18,109 @@
 //! use geocoding::{Opencage, Point, Reverse};
 //!
 //! let mut oc = Opencage::new("dcdbf0d783374909b3debee728c7cc10".to_string());
-//! oc.parameters.language = Some("fr");
+//! oc.parameters.language = Some("fr".to_string());
 //! let p = Point::new(2.12870, 41.40139);
 //! let res = oc.reverse(&p);
 //! // "Carrer de Calatrava, 68, 08017 Barcelone, Espagne"
 //! println!("{:?}", res.unwrap());
 //! ```
+use crate::deserialize_float;
+use crate::info::{ProviderInfo, RateLimit};
+use crate::openstreetmap::{OsmId, OsmType};
 use crate::DeserializeOwned;
 use crate::GeocodingError;
 use crate::InputBounds;
 use crate::Point;
+use crate::StrictBounds;
 use crate::UA_STRING;
 use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
 use crate::{Deserialize, Serialize};
 use crate::{Forward, Reverse};
+#[cfg(feature = "chrono-tz")]
+use chrono::{DateTime, TimeZone, Utc};
+#[cfg(feature = "chrono-tz")]
+use chrono_tz::Tz;
+use geo_types::Rect;
 use num_traits::Float;
+use reqwest::StatusCode;
 use serde::Deserializer;
 use std::collections::HashMap;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
+use std::num::ParseIntError;
+use std::str::FromStr;
 use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 macro_rules! add_optional_param {
     ($query:expr, $param:expr, $name:expr) => {
         if let Some(p) = $param {
-            $query.push(($name, p))
+            $query.push(($name, p.to_string()))
         }
     };
 }
 
 // Please see the [API documentation](https://opencagedata.com/api#forward-opt) for details.
-#[derive(Default)]
-pub struct Parameters<'a> {
-    pub language: Option<&'a str>,
-    pub countrycode: Option<&'a str>,
-    pub limit: Option<&'a str>,
+#[derive(Debug, Default)]
+pub struct Parameters {
+    /// An IETF language tag for the results, e.g. `de` or `pt-BR`
+    pub language: Option<String>,
+    /// Restrict results to these countries
+    pub countrycode: Option<Vec<CountryCode>>,
+    /// The maximum number of results, from 1 to 100 (default: 10)
+    pub limit: Option<u8>,
+    /// Bias forward results towards a location
+    pub proximity: Option<Point<f64>>,
+    /// Abbreviate the `formatted` address, e.g. "Rd" instead of "Road"
+    pub abbrv: bool,
+    /// Exclude POI names from the `formatted` address and components
+    pub address_only: bool,
+    /// Add the [`RoadInfo`](struct.RoadInfo.html) annotation, describing the nearest road
+    pub roadinfo: bool,
+    /// Only return results with at least this confidence, from 1 to 10
+    pub min_confidence: Option<u8>,
+    /// Return all candidates, without collapsing duplicates
+    pub no_dedupe: bool,
+    /// Echo the request in the response's `request` field
+    pub add_request: bool,
+    /// Whether to ask OpenCage not to log the query (default: `true`)
+    pub no_record: Option<bool>,
+    /// Check the results of `forward_full` against its bounds, which OpenCage only uses as a hint
+    pub strict_bounds: Option<StrictBounds>,
 }
 
-impl<'a> Parameters<'a> {
-    fn as_query(&self) -> Vec<(&'a str, &'a str)> {
+impl Parameters {
+    fn as_query(&self) -> Vec<(&'static str, String)> {
         let mut query = vec![];
-        add_optional_param!(query, self.language, "language");
-        add_optional_param!(query, self.countrycode, "countrycode");
+        add_optional_param!(query, &self.language, "language");
+        if let Some(countrycode) = &self.countrycode {
+            let codes: Vec<&str> = countrycode.iter().map(CountryCode::as_str).collect();
+            query.push(("countrycode", codes.join(",")));
+        }
         add_optional_param!(query, self.limit, "limit");
+        if let Some(proximity) = self.proximity {
+            // OpenCage expects lat, lon order
+            query.push(("proximity", format!("{},{}", proximity.y(), proximity.x())));
+        }
+        if self.abbrv {
+            query.push(("abbrv", "1".to_string()));
+        }
+        if self.address_only {
+            query.push(("address_only", "1".to_string()));
+        }
+        if self.roadinfo {
+            query.push(("roadinfo", "1".to_string()));
+        }
+        add_optional_param!(query, self.min_confidence, "min_confidence");
+        if self.no_dedupe {
+            query.push(("no_dedupe", "1".to_string()));
+        }
+        if self.add_request {
+            query.push(("add_request", "1".to_string()));
+        }
+        if self.no_record.unwrap_or(true) {
+            query.push(("no_record", "1".to_string()));
+        }
         query
     }
 }
@@ -81,22 +142,128 @@ where
     }
 }
 
-// OpenCage has a custom rate-limit header, indicating remaining calls
-// header! { (XRatelimitRemaining, "X-RateLimit-Remaining") => [i32] }
+// OpenCage has custom rate-limit headers, indicating remaining calls, the daily limit, and when
+// it resets
 static XRL: &str = "x-ratelimit-remaining";
+static XRL_LIMIT: &str = "x-ratelimit-limit";
+static XRL_RESET: &str = "x-ratelimit-reset";
+// not ideal, but typed headers are currently impossible in 0.9.x
+fn parse_header<V>(headers: &HeaderMap, name: &str) -> Result<Option<V>, GeocodingError>
+where
+    V: FromStr<Err = ParseIntError>,
+{
+    match headers.get(name) {
+        Some(value) => Ok(Some(value.to_str()?.parse()?)),
+        None => Ok(None),
+    }
+}
+
+// The body of an error response
+#[derive(Deserialize)]
+struct ErrorResponse {
+    status: Status,
+}
+
+// OpenCage explains quota, key and rate-limit errors in the `status` of the response body
+fn status_error(status: StatusCode, body: &str) -> GeocodingError {
+    let message = serde_json::from_str::<ErrorResponse>(body)
+        .map(|res| res.status.message)
+        .unwrap_or_else(|_| status.to_string());
+    match status.as_u16() {
+        402 => GeocodingError::QuotaExceeded(message),
+        403 => GeocodingError::Forbidden(message),
+        _ => GeocodingError::RateLimited {
+            message,
+            retry_after: None,
+        },
+    }
+}
+
+/// An ISO 3166-1 alpha-2 country code, e.g. `gb`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CountryCode(String);
+
+impl CountryCode {
+    /// Create a country code from two ASCII letters, in either case
+    pub fn new(code: &str) -> Option<Self> {
+        if code.len() == 2 && code.bytes().all(|b| b.is_ascii_alphabetic()) {
+            Some(CountryCode(code.to_ascii_lowercase()))
+        } else {
+            None
+        }
+    }
+
+    /// The country code, in lower case
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for CountryCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
 /// Use this constant if you don't need to restrict a `forward_full` call with a bounding box
 pub static NOBOX: Option<InputBounds<f64>> = None::<InputBounds<f64>>;
 
+/// The outcome of [`Opencage::forward_batch`](struct.Opencage.html#method.forward_batch) or
+/// [`Opencage::reverse_batch`](struct.Opencage.html#method.reverse_batch)
+#[derive(Debug)]
+pub struct BatchResult<'q, Q, T>
+where
+    T: Float + Debug,
+{
+    /// The result of each processed input, in input order
+    pub results: Vec<Result<OpencageResponse<T>, GeocodingError>>,
+    /// The inputs which weren't processed because the daily quota was exhausted
+    pub unprocessed: &'q [Q],
+}
+
+impl<'q, Q, T> BatchResult<'q, Q, T>
+where
+    T: Float + Debug,
+{
+    /// Whether every input was processed
+    pub fn is_complete(&self) -> bool {
+        self.unprocessed.is_empty()
+    }
+}
+
 /// An instance of the Opencage Geocoding service
-pub struct Opencage<'a> {
+pub struct Opencage {
     api_key: String,
     client: Client,
     endpoint: String,
-    pub parameters: Parameters<'a>,
+    pub parameters: Parameters,
     remaining: Arc<Mutex<Option<i32>>>,
+    limit: Arc<Mutex<Option<i32>>>,
+    reset: Arc<Mutex<Option<UnixTime>>>,
+}
+
+// The API key is redacted, so that it can't leak into logs
+impl Debug for Opencage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Opencage")
+            .field("api_key", &"[redacted]")
+            .field("endpoint", &self.endpoint)
+            .field("parameters", &self.parameters)
+            .field("remaining", &self.remaining_calls())
+            .finish()
+    }
 }
 
-impl<'a> Opencage<'a> {
+impl Opencage {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "OpenCage",
+        docs_url: "https://opencagedata.com/api",
+        terms_url: Some("https://opencagedata.com/terms"),
+        attribution: Some("© OpenStreetMap contributors"),
+        rate_limit: Some(RateLimit::per_second(1)),
+    };
+
     /// Create a new OpenCage geocoding instance
     pub fn new(api_key: String) -> Self {
         let mut headers = HeaderMap::new();
@@ -105,7 +272,26 @@ impl<'a> Opencage<'a> {
             .default_headers(headers)
             .build()
             .expect("Couldn't build a client!");
-
+        Self::new_with_client(api_key, client)
+    }
+    /// Create a new OpenCage geocoding instance using an existing `reqwest` client, e.g. to
+    /// share its connection pool, proxy and TLS settings
+    ///
+    /// The client's own default headers are sent in place of this crate's `User-Agent`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Opencage;
+    /// use std::time::Duration;
+    ///
+    /// let client = reqwest::blocking::Client::builder()
+    ///     .timeout(Duration::from_secs(10))
+    ///     .build()
+    ///     .unwrap();
+    /// let oc = Opencage::new_with_client("dcdbf0d783374909b3debee728c7cc10".to_string(), client);
+    /// ```
+    pub fn new_with_client(api_key: String, client: Client) -> Self {
         let parameters = Parameters::default();
         Opencage {
             api_key,
@@ -113,8 +299,17 @@ impl<'a> Opencage<'a> {
             parameters,
             endpoint: "https://api.opencagedata.com/geocode/v1/json".to_string(),
             remaining: Arc::new(Mutex::new(None)),
+            limit: Arc::new(Mutex::new(None)),
+            reset: Arc::new(Mutex::new(None)),
         }
     }
+    /// Set a custom endpoint of an OpenCage instance, such as a mock server or an API gateway
+    ///
+    /// Endpoint should be the full URL of the JSON API (i.e. "https://api.opencagedata.com/geocode/v1/json")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
     /// Retrieve the remaining API calls in your daily quota
     ///
     /// Initially, this value is `None`. Any OpenCage API call using a "Free Tier" key
@@ -123,9 +318,61 @@ impl<'a> Opencage<'a> {
     pub fn remaining_calls(&self) -> Option<i32> {
         *self.remaining.lock().unwrap()
     }
+    /// Retrieve the number of API calls in your daily quota
+    ///
+    /// Like [`remaining_calls()`](#method.remaining_calls), this value is `None` until a call
+    /// using a "Free Tier" key is made.
+    pub fn quota_limit(&self) -> Option<i32> {
+        *self.limit.lock().unwrap()
+    }
+    /// Retrieve when your daily quota resets
+    ///
+    /// Like [`remaining_calls()`](#method.remaining_calls), this value is `None` until a call
+    /// using a "Free Tier" key is made.
+    pub fn quota_reset(&self) -> Option<UnixTime> {
+        *self.reset.lock().unwrap()
+    }
+    fn get<T>(&self, query: &[(&str, &str)]) -> Result<OpencageResponse<T>, GeocodingError>
+    where
+        T: Float + DeserializeOwned + Debug,
+    {
+        let resp = self
+            .client
+            .get(&self.endpoint)
+            .query(query)
+            .query(&self.parameters.as_query())
+            .send()?;
+        self.update_quota(resp.headers())?;
+        let status = resp.status();
+        if let 402 | 403 | 429 = status.as_u16() {
+            return Err(status_error(status, &resp.text()?));
+        }
+        let res: OpencageResponse<T> = resp.error_for_status()?.json()?;
+        Ok(res)
+    }
+    // Record the quota headers sent in responses to "Free Tier" keys
+    fn update_quota(&self, headers: &HeaderMap) -> Result<(), GeocodingError> {
+        if let Some(remaining) = parse_header(headers, XRL)? {
+            if let Ok(mut lock) = self.remaining.try_lock() {
+                *lock = Some(remaining);
+            }
+        }
+        if let Some(limit) = parse_header(headers, XRL_LIMIT)? {
+            if let Ok(mut lock) = self.limit.try_lock() {
+                *lock = Some(limit);
+            }
+        }
+        if let Some(reset) = parse_header(headers, XRL_RESET)? {
+            if let Ok(mut lock) = self.reset.try_lock() {
+                *lock = Some(UnixTime::from_seconds(reset));
+            }
+        }
+        Ok(())
+    }
     /// A reverse lookup of a point, returning an annotated response.
     ///
-    /// This method passes the `no_record` parameter to the API.
+    /// This method passes the `no_record` parameter to the API, unless
+    /// [`Parameters::no_record`](struct.Parameters.html#structfield.no_record) is `Some(false)`.
     ///
     /// # Examples
     ///
@@ -139,8 +386,8 @@ impl<'a> Opencage<'a> {
     /// // responses may include multiple results
     /// let first_result = &res.results[0];
     /// assert_eq!(
-    ///     first_result.components["road"],
-    ///     "Carrer de Calatrava"
+    ///     first_result.components.road.as_deref(),
+    ///     Some("Carrer de Calatrava")
     /// );
     ///```
     pub fn reverse_full<T>(&self, point: &Point<T>) -> Result<OpencageResponse<T>, GeocodingError>
@@ -153,32 +400,13 @@ impl<'a> Opencage<'a> {
             (&point.y().to_f64().unwrap().to_string()),
             &point.x().to_f64().unwrap().to_string()
         );
-        let mut query = vec![
+        let query = vec![
             ("q", q.as_str()),
             ("key", &self.api_key),
             ("no_annotations", "0"),
-            ("no_record", "1"),
         ];
-        query.extend(self.parameters.as_query());
 
-        let resp = self
-            .client
-            .get(&self.endpoint)
-            .query(&query)
-            .send()?
-            .error_for_status()?;
-        // it's OK to index into this vec, because reverse-geocoding only returns a single result
-        if let Some(headers) = resp.headers().get::<_>(XRL) {
-            let mut lock = self.remaining.try_lock();
-            if let Ok(ref mut mutex) = lock {
-                // not ideal, but typed headers are currently impossible in 0.9.x
-                let h = headers.to_str()?;
-                let h: i32 = h.parse()?;
-                **mutex = Some(h)
-            }
-        }
-        let res: OpencageResponse<T> = resp.json()?;
-        Ok(res)
+        self.get(&query)
     }
     /// A forward-geocoding lookup of an address, returning an annotated response.
     ///
@@ -190,7 +418,8 @@ impl<'a> Opencage<'a> {
     /// Please see [the documentation](https://opencagedata.com/api#ambiguous-results) for details
     /// of best practices in order to obtain good-quality results.
     ///
-    /// This method passes the `no_record` parameter to the API.
+    /// This method passes the `no_record` parameter to the API, unless
+    /// [`Parameters::no_record`](struct.Parameters.html#structfield.no_record) is `Some(false)`.
     ///
     /// # Examples
     ///
@@ -251,51 +480,146 @@ impl<'a> Opencage<'a> {
         U: Into<Option<InputBounds<T>>>,
     {
         let ann = String::from("0");
-        let record = String::from("1");
         // we need this to avoid lifetime inconvenience
         let bd;
         let mut query = vec![
             ("q", place),
             ("key", &self.api_key),
             ("no_annotations", &ann),
-            ("no_record", &record),
         ];
 
         // If search bounds are passed, use them
-        if let Some(bds) = bounds.into() {
+        let bounds = bounds.into();
+        if let Some(bds) = bounds {
             bd = String::from(bds);
             query.push(("bounds", &bd));
         }
-        query.extend(self.parameters.as_query());
 
-        let resp = self
-            .client
-            .get(&self.endpoint)
-            .query(&query)
-            .send()?
-            .error_for_status()?;
-        if let Some(headers) = resp.headers().get::<_>(XRL) {
-            let mut lock = self.remaining.try_lock();
-            if let Ok(ref mut mutex) = lock {
-                // not ideal, but typed headers are currently impossible in 0.9.x
-                let h = headers.to_str()?;
-                let h: i32 = h.parse()?;
-                **mutex = Some(h)
-            }
+        let mut res: OpencageResponse<T> = self.get(&query)?;
+        if let (Some(mode), Some(bds)) = (self.parameters.strict_bounds, bounds) {
+            res.results = bds.enforce(res.results, mode, |result| result.geometry.to_point())?;
         }
-        let res: OpencageResponse<T> = resp.json()?;
         Ok(res)
     }
+
+    /// Forward-geocode several addresses in turn, within the free-tier rate limit of one request
+    /// per second.
+    ///
+    /// The batch stops before the first address that can't be geocoded because a limit was hit:
+    /// either [`remaining_calls()`](#method.remaining_calls) reached zero before
+    /// [`quota_reset()`](#method.quota_reset), or the API returned
+    /// [`GeocodingError::QuotaExceeded`](../enum.GeocodingError.html#variant.QuotaExceeded) or
+    /// [`GeocodingError::RateLimited`](../enum.GeocodingError.html#variant.RateLimited).
+    /// That address and the ones after it are returned as the
+    /// [`unprocessed`](struct.BatchResult.html#structfield.unprocessed) remainder, which can be
+    /// passed to another call once the quota resets. Any other error is recorded in the results
+    /// and the batch goes on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Opencage;
+    /// use geocoding::opencage::BatchResult;
+    ///
+    /// let oc = Opencage::new("dcdbf0d783374909b3debee728c7cc10".to_string());
+    /// let addresses = ["Moabit, Berlin", "Carrer de Calatrava 68, Barcelona"];
+    /// let batch: BatchResult<_, f64> = oc.forward_batch(&addresses);
+    /// if !batch.is_complete() {
+    ///     println!("{} addresses left until the quota resets", batch.unprocessed.len());
+    /// }
+    /// ```
+    pub fn forward_batch<'q, S, T>(&self, places: &'q [S]) -> BatchResult<'q, S, T>
+    where
+        S: AsRef<str>,
+        T: Float + DeserializeOwned + Debug,
+    {
+        self.batch(places, Self::batch_interval(), |place| {
+            self.forward_full(place.as_ref(), None)
+        })
+    }
+
+    /// Reverse-geocode several points in turn, within the free-tier rate limit of one request
+    /// per second.
+    ///
+    /// Like [`forward_batch()`](#method.forward_batch), the batch stops once the daily quota is
+    /// exhausted or the API rate-limits it, returning the points left to process.
+    pub fn reverse_batch<'q, T>(&self, points: &'q [Point<T>]) -> BatchResult<'q, Point<T>, T>
+    where
+        T: Float + DeserializeOwned + Debug,
+    {
+        self.batch(points, Self::batch_interval(), |point| {
+            self.reverse_full(point)
+        })
+    }
+
+    fn batch_interval() -> Duration {
+        Self::INFO
+            .rate_limit
+            .map_or(Duration::from_secs(0), |limit| limit.min_interval())
+    }
+
+    // Whether the last response reported no calls left, and the quota hasn't reset since
+    fn quota_exhausted(&self) -> bool {
+        self.remaining_calls() == Some(0)
+            && self
+                .quota_reset()
+                .map_or(true, |reset| reset.to_system_time() > SystemTime::now())
+    }
+
+    // Make a request for each input, at most once per `interval`, until the quota runs out or
+    // the API rate-limits us
+    fn batch<'q, Q, T, F>(
+        &self,
+        inputs: &'q [Q],
+        interval: Duration,
+        mut request: F,
+    ) -> BatchResult<'q, Q, T>
+    where
+        T: Float + Debug,
+        F: FnMut(&Q) -> Result<OpencageResponse<T>, GeocodingError>,
+    {
+        let mut results = Vec::with_capacity(inputs.len());
+        let mut last_request: Option<Instant> = None;
+        for (i, input) in inputs.iter().enumerate() {
+            if self.quota_exhausted() {
+                return BatchResult {
+                    results,
+                    unprocessed: &inputs[i..],
+                };
+            }
+            if let Some(elapsed) = last_request.map(|instant| instant.elapsed()) {
+                if elapsed < interval {
+                    thread::sleep(interval - elapsed);
+                }
+            }
+            last_request = Some(Instant::now());
+            match request(input) {
+                Err(GeocodingError::QuotaExceeded(_)) | Err(GeocodingError::RateLimited { .. }) => {
+                    return BatchResult {
+                        results,
+                        unprocessed: &inputs[i..],
+                    }
+                }
+                res => results.push(res),
+            }
+        }
+        BatchResult {
+            results,
+            unprocessed: &[],
+        }
+    }
 }
 
-impl<'a, T> Reverse<T> for Opencage<'a>
+impl<T> Reverse<T> for Opencage
 where
     T: Float + DeserializeOwned + Debug,
 {
     /// A reverse lookup of a point. More detail on the format of the
     /// returned `String` can be found [here](https://blog.opencagedata.com/post/99059889253/good-looking-addresses-solving-the-berlin-berlin)
     ///
-    /// This method passes the `no_annotations` and `no_record` parameters to the API.
+    /// This method passes the `no_annotations` parameter to the API, and the `no_record`
+    /// parameter unless
+    /// [`Parameters::no_record`](struct.Parameters.html#structfield.no_record) is `Some(false)`.
     fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
         let q = format!(
             "{}, {}",
@@ -303,73 +627,41 @@ where
             (&point.y().to_f64().unwrap().to_string()),
             &point.x().to_f64().unwrap().to_string()
         );
-        let mut query = vec![
+        let query = vec![
             ("q", q.as_str()),
             ("key", &self.api_key),
             ("no_annotations", "1"),
-            ("no_record", "1"),
         ];
-        query.extend(self.parameters.as_query());
 
-        let resp = self
-            .client
-            .get(&self.endpoint)
-            .query(&query)
-            .send()?
-            .error_for_status()?;
-        if let Some(headers) = resp.headers().get::<_>(XRL) {
-            let mut lock = self.remaining.try_lock();
-            if let Ok(ref mut mutex) = lock {
-                // not ideal, but typed headers are currently impossible in 0.9.x
-                let h = headers.to_str()?;
-                let h: i32 = h.parse()?;
-                **mutex = Some(h)
-            }
-        }
-        let res: OpencageResponse<T> = resp.json()?;
+        let res: OpencageResponse<T> = self.get(&query)?;
         // it's OK to index into this vec, because reverse-geocoding only returns a single result
         let address = &res.results[0];
         Ok(Some(address.formatted.to_string()))
     }
 }
 
-impl<'a, T> Forward<T> for Opencage<'a>
+impl<T> Forward<T> for Opencage
 where
     T: Float + DeserializeOwned + Debug,
 {
     /// A forward-geocoding lookup of an address. Please see [the documentation](https://opencagedata.com/api#ambiguous-results) for details
     /// of best practices in order to obtain good-quality results.
     ///
-    /// This method passes the `no_annotations` and `no_record` parameters to the API.
+    /// This method passes the `no_annotations` parameter to the API, and the `no_record`
+    /// parameter unless
+    /// [`Parameters::no_record`](struct.Parameters.html#structfield.no_record) is `Some(false)`.
     fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
-        let mut query = vec![
+        let query = vec![
             ("q", place),
             ("key", &self.api_key),
             ("no_annotations", "1"),
-            ("no_record", "1"),
         ];
-        query.extend(self.parameters.as_query());
 
-        let resp = self
-            .client
-            .get(&self.endpoint)
-            .query(&query)
-            .send()?
-            .error_for_status()?;
-        if let Some(headers) = resp.headers().get::<_>(XRL) {
-            let mut lock = self.remaining.try_lock();
-            if let Ok(ref mut mutex) = lock {
-                // not ideal, but typed headers are currently impossible in 0.9.x
-                let h = headers.to_str()?;
-                let h: i32 = h.parse()?;
-                **mutex = Some(h)
-            }
-        }
-        let res: OpencageResponse<T> = resp.json()?;
+        let res: OpencageResponse<T> = self.get(&query)?;
         Ok(res
             .results
             .iter()
-            .map(|res| Point::new(res.geometry["lng"], res.geometry["lat"]))
+            .map(|res| res.geometry.to_point())
             .collect())
     }
 }
@@ -510,11 +802,13 @@ where
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OpencageResponse<T>
 where
-    T: Float,
+    T: Float + Debug,
 {
     pub documentation: String,
-    pub licenses: Vec<HashMap<String, String>>,
-    pub rate: Option<HashMap<String, i32>>,
+    pub licenses: Vec<License>,
+    pub rate: Option<Rate>,
+    /// The parameters of the request, if `add_request` was set
+    pub request: Option<HashMap<String, serde_json::Value>>,
     pub results: Vec<Results<T>>,
     pub status: Status,
     pub stay_informed: HashMap<String, String>,
@@ -523,18 +817,101 @@ where
     pub total_results: i32,
 }
 
+impl<T> OpencageResponse<T>
+where
+    T: Float + Debug,
+{
+    /// The result with the highest confidence, the most relevant first if several tie
+    ///
+    /// Confidence measures the precision of a result, from 10 (within 250 metres) down to 1
+    /// (over 25 kilometres), rather than its relevance to the query.
+    pub fn best_result(&self) -> Option<&Results<T>> {
+        self.results.iter().fold(None, |best, result| match best {
+            Some(best) if best.confidence >= result.confidence => Some(best),
+            _ => Some(result),
+        })
+    }
+
+    /// The results with at least the given confidence, in order of relevance
+    pub fn results_with_min_confidence(&self, confidence: i8) -> Vec<&Results<T>> {
+        self.results
+            .iter()
+            .filter(|result| result.confidence >= confidence)
+            .collect()
+    }
+}
+
 /// A forward geocoding result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Results<T>
 where
-    T: Float,
+    T: Float + Debug,
 {
     pub annotations: Option<Annotations<T>>,
     pub bounds: Option<Bounds<T>>,
-    pub components: HashMap<String, serde_json::Value>,
+    pub components: Components,
     pub confidence: i8,
     pub formatted: String,
-    pub geometry: HashMap<String, T>,
+    pub geometry: Geometry<T>,
+}
+
+/// The location of a geocoding result
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Geometry<T>
+where
+    T: Float,
+{
+    pub lat: T,
+    pub lng: T,
+}
+
+impl<T> Geometry<T>
+where
+    T: Float + Debug,
+{
+    /// The location as a `Point`, in longitude, latitude order
+    pub fn to_point(&self) -> Point<T> {
+        Point::new(self.lng, self.lat)
+    }
+}
+
+/// The address components of a geocoding result
+///
+/// Please see [the documentation](https://opencagedata.com/api#components) for details.
+/// Components without a field of their own are kept in `extra`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Components {
+    /// The type of the result, e.g. `building` or `city`
+    #[serde(rename = "_type")]
+    pub component_type: Option<String>,
+    /// The category of the result, e.g. `building` or `place`
+    #[serde(rename = "_category")]
+    pub category: Option<String>,
+    /// The name of the city, town or village the result lies in
+    #[serde(rename = "_normalized_city")]
+    pub normalized_city: Option<String>,
+    pub house_number: Option<String>,
+    pub road: Option<String>,
+    pub neighbourhood: Option<String>,
+    pub suburb: Option<String>,
+    pub city_district: Option<String>,
+    pub city: Option<String>,
+    pub town: Option<String>,
+    pub village: Option<String>,
+    pub county: Option<String>,
+    pub state: Option<String>,
+    pub state_code: Option<String>,
+    pub postcode: Option<String>,
+    pub country: Option<String>,
+    /// The ISO 3166-1 alpha-2 country code, in lowercase
+    pub country_code: Option<String>,
+    pub continent: Option<String>,
+    #[serde(rename = "ISO_3166-1_alpha-2")]
+    pub iso_3166_1_alpha_2: Option<String>,
+    #[serde(rename = "ISO_3166-1_alpha-3")]
+    pub iso_3166_1_alpha_3: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Annotations pertaining to the geocoding result
@@ -547,15 +924,196 @@ where
     pub mgrs: Option<String>,
     pub maidenhead: Option<String>,
     pub mercator: Option<HashMap<String, T>>,
-    pub osm: Option<HashMap<String, String>>,
-    pub callingcode: i16,
+    /// Links to the OpenStreetMap element the result was derived from
+    #[serde(rename = "OSM")]
+    pub osm: Option<Osm>,
+    /// The Q-id of the Wikidata item about the result, e.g. `Q1492`
+    pub wikidata: Option<String>,
+    pub callingcode: Option<i16>,
     pub currency: Option<Currency>,
-    pub flag: String,
-    pub geohash: String,
-    pub qibla: T,
-    pub sun: Sun,
-    pub timezone: Timezone,
-    pub what3words: HashMap<String, String>,
+    pub flag: Option<String>,
+    pub geohash: Option<String>,
+    pub qibla: Option<T>,
+    pub roadinfo: Option<RoadInfo>,
+    pub sun: Option<Sun>,
+    pub timezone: Option<Timezone>,
+    pub what3words: Option<What3Words>,
+    #[serde(rename = "UN_M49")]
+    pub un_m49: Option<UnM49>,
+    #[serde(rename = "FIPS")]
+    pub fips: Option<Fips>,
+    #[serde(rename = "OSGB")]
+    pub osgb: Option<Osgb<T>>,
+    #[serde(rename = "ITM")]
+    pub itm: Option<Itm<T>>,
+    #[serde(rename = "NUTS")]
+    pub nuts: Option<Nuts>,
+    /// The distance from the queried point, for reverse results
+    pub distance_from_q: Option<DistanceFromQ<T>>,
+}
+
+/// OpenStreetMap links for the geocoding result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Osm {
+    pub edit_url: Option<String>,
+    pub note_url: Option<String>,
+    pub url: String,
+}
+
+impl Osm {
+    /// The OSM element this result was derived from, if OpenCage links to one
+    ///
+    /// The element is recovered from `edit_url`, which has the form
+    /// `https://www.openstreetmap.org/edit?way=355421084#map=17/41.40141/2.12872`.
+    pub fn osm_id(&self) -> Option<OsmId> {
+        let edit_url = self.edit_url.as_ref()?;
+        let query = edit_url.split_once('?')?.1;
+        let query = query.split('#').next()?;
+        query.split('&').find_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            let osm_type = match key {
+                "node" => OsmType::Node,
+                "way" => OsmType::Way,
+                "relation" => OsmType::Relation,
+                _ => return None,
+            };
+            Some(OsmId::new(osm_type, value.parse().ok()?))
+        })
+    }
+}
+
+/// Road metadata, requested with the `roadinfo` parameter
+///
+///```json
+///{
+///  "drive_on": "right",
+///  "road": "Carrer de Calatrava",
+///  "road_type": "residential",
+///  "speed_in": "km/h",
+///  "surface": "paved"
+///}
+///```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoadInfo {
+    /// The side of the road vehicles drive on: `left` or `right`
+    pub drive_on: String,
+    /// The unit of speed limits: `km/h` or `mph`
+    pub speed_in: String,
+    pub road: Option<String>,
+    /// The OpenStreetMap `highway` value, e.g. `residential`
+    pub road_type: Option<String>,
+    pub surface: Option<String>,
+}
+
+/// United Nations M49 statistical region codes
+///
+///```json
+///{
+///  "regions": {
+///    "ES": "724",
+///    "EUROPE": "150",
+///    "SOUTHERN_EUROPE": "039",
+///    "WORLD": "001"
+///  },
+///  "statistical_groupings": ["MEDC"]
+///}
+///```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnM49 {
+    /// The codes of the regions the result lies in, by region name
+    pub regions: HashMap<String, String>,
+    /// Development groupings, e.g. `MEDC` for more economically developed countries
+    #[serde(default = "Vec::new")]
+    pub statistical_groupings: Vec<String>,
+}
+
+/// US Federal Information Processing Standards codes, for results in the United States
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fips {
+    pub state: Option<String>,
+    pub county: Option<String>,
+}
+
+/// The distance between a reverse result and the queried point
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistanceFromQ<T>
+where
+    T: Float,
+{
+    pub meters: T,
+}
+
+/// British National Grid coordinates, for results in Great Britain
+///
+///```json
+///{
+///  "easting": 529784.0,
+///  "gridref": "TQ 297 822",
+///  "northing": 182223.0
+///}
+///```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Osgb<T>
+where
+    T: Float,
+{
+    #[serde(deserialize_with = "deserialize_float")]
+    pub easting: T,
+    #[serde(deserialize_with = "deserialize_float")]
+    pub northing: T,
+    /// The grid reference, e.g. `TQ 297 822`
+    pub gridref: String,
+}
+
+/// Irish Transverse Mercator coordinates, for results in Ireland
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Itm<T>
+where
+    T: Float,
+{
+    #[serde(deserialize_with = "deserialize_float")]
+    pub easting: T,
+    #[serde(deserialize_with = "deserialize_float")]
+    pub northing: T,
+}
+
+/// Eurostat NUTS region codes, for results in the European Union
+///
+///```json
+///{
+///  "NUTS0": { "code": "ES" },
+///  "NUTS1": { "code": "ES5" },
+///  "NUTS2": { "code": "ES51" },
+///  "NUTS3": { "code": "ES511" }
+///}
+///```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Nuts {
+    /// The country
+    #[serde(rename = "NUTS0")]
+    pub nuts0: Option<NutsCode>,
+    /// The major socio-economic region
+    #[serde(rename = "NUTS1")]
+    pub nuts1: Option<NutsCode>,
+    /// The basic region for the application of regional policies
+    #[serde(rename = "NUTS2")]
+    pub nuts2: Option<NutsCode>,
+    /// The small region for specific diagnoses
+    #[serde(rename = "NUTS3")]
+    pub nuts3: Option<NutsCode>,
+}
+
+/// A NUTS region code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NutsCode {
+    pub code: String,
+}
+
+/// The [what3words](https://what3words.com/) address of the result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct What3Words {
+    /// Three words separated by dots, e.g. `chins.pictures.passes`
+    pub words: String,
 }
 
 /// Currency metadata
@@ -595,6 +1153,27 @@ pub struct Timezone {
     pub short_name: String,
 }
 
+#[cfg(feature = "chrono-tz")]
+impl Timezone {
+    /// The IANA time zone, if `chrono-tz` knows its name
+    pub fn tz(&self) -> Option<Tz> {
+        self.name.parse().ok()
+    }
+
+    /// A timestamp as the local time at the result
+    pub fn localize(&self, time: UnixTime) -> Option<DateTime<Tz>> {
+        let utc = Utc.timestamp_opt(time.as_seconds(), 0).single()?;
+        Some(utc.with_timezone(&self.tz()?))
+    }
+}
+
+/// A license of the data in a response, which may require attribution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct License {
+    pub name: String,
+    pub url: String,
+}
+
 /// HTTP status metadata
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Status {
@@ -610,7 +1189,7 @@ pub struct Timestamp {
 }
 
 /// Primitive unix timestamp
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct UnixTime(i64);
 
 impl UnixTime {
@@ -620,16 +1199,78 @@ impl UnixTime {
     pub const fn from_seconds(seconds: i64) -> Self {
         Self(seconds)
     }
+    /// The timestamp as a `SystemTime`, e.g. to compare it with `SystemTime::now()`
+    pub fn to_system_time(self) -> SystemTime {
+        let offset = Duration::from_secs(self.0.unsigned_abs());
+        if self.0 >= 0 {
+            UNIX_EPOCH + offset
+        } else {
+            UNIX_EPOCH - offset
+        }
+    }
+}
+
+/// The daily quota of free-tier accounts
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Rate {
+    /// The number of calls allowed per day
+    pub limit: i32,
+    /// The number of calls remaining today
+    pub remaining: i32,
+    /// When the quota resets, at midnight UTC
+    pub reset: UnixTime,
 }
 
 /// Bounding-box metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>"))]
 pub struct Bounds<T>
 where
-    T: Float,
+    T: Float + Debug,
+{
+    #[serde(with = "lat_lng")]
+    pub northeast: Point<T>,
+    #[serde(with = "lat_lng")]
+    pub southwest: Point<T>,
+}
+
+impl<T> Bounds<T>
+where
+    T: Float + Debug,
 {
-    pub northeast: HashMap<String, T>,
-    pub southwest: HashMap<String, T>,
+    /// The bounding box as a `Rect`
+    pub fn to_rect(&self) -> Rect<T> {
+        Rect::new(self.southwest, self.northeast)
+    }
+}
+
+// OpenCage represents points as `{"lat": …, "lng": …}` objects
+mod lat_lng {
+    use super::Geometry;
+    use crate::{Deserialize, Point, Serialize};
+    use num_traits::Float;
+    use serde::{Deserializer, Serializer};
+    use std::fmt::Debug;
+
+    pub fn serialize<S, T>(point: &Point<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Float + Debug + Serialize,
+    {
+        Geometry {
+            lat: point.y(),
+            lng: point.x(),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Point<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Float + Debug + Deserialize<'de>,
+    {
+        Geometry::deserialize(deserializer).map(|geometry| geometry.to_point())
+    }
 }
 
 #[cfg(test)]
@@ -648,10 +1289,375 @@ mod test {
         );
     }
 
+    #[test]
+    fn osm_annotation_test() {
+        let osm: Osm = serde_json::from_str(
+            r#"{
+                "edit_url": "https://www.openstreetmap.org/edit?way=355421084#map=17/41.40141/2.12872",
+                "note_url": "https://www.openstreetmap.org/note/new#map=17/41.40141/2.12872&layers=N",
+                "url": "https://www.openstreetmap.org/?mlat=41.40141&mlon=2.12872#map=17/41.40141/2.12872"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(osm.osm_id(), Some(OsmId::new(OsmType::Way, 355421084)));
+    }
+
+    #[test]
+    fn status_error_test() {
+        let err = status_error(
+            StatusCode::PAYMENT_REQUIRED,
+            r#"{"status": {"code": 402, "message": "quota exceeded"}}"#,
+        );
+        assert!(
+            matches!(err, GeocodingError::QuotaExceeded(message) if message == "quota exceeded")
+        );
+        let err = status_error(StatusCode::TOO_MANY_REQUESTS, "");
+        assert!(matches!(err, GeocodingError::RateLimited { .. }));
+    }
+
+    #[test]
+    fn quota_test() {
+        let oc = Opencage::new("dcdbf0d783374909b3debee728c7cc10".to_string());
+        assert_eq!(oc.quota_limit(), None);
+        let mut headers = HeaderMap::new();
+        headers.insert(XRL, HeaderValue::from_static("2499"));
+        headers.insert(XRL_LIMIT, HeaderValue::from_static("2500"));
+        headers.insert(XRL_RESET, HeaderValue::from_static("1523318400"));
+        oc.update_quota(&headers).unwrap();
+        assert_eq!(oc.remaining_calls(), Some(2499));
+        assert_eq!(oc.quota_limit(), Some(2500));
+        assert_eq!(oc.quota_reset(), Some(UnixTime::from_seconds(1523318400)));
+    }
+
+    #[test]
+    fn debug_test() {
+        let oc = Opencage::new("dcdbf0d783374909b3debee728c7cc10".to_string());
+        let debug = format!("{:?}", oc);
+        assert!(debug.contains("[redacted]"));
+        assert!(!debug.contains("dcdbf0d783374909b3debee728c7cc10"));
+    }
+
+    #[test]
+    fn batch_test() {
+        let oc = Opencage::new("dcdbf0d783374909b3debee728c7cc10".to_string());
+        let empty = || -> OpencageResponse<f64> {
+            serde_json::from_value(serde_json::json!({
+                "documentation": "https://opencagedata.com/api",
+                "licenses": [],
+                "results": [],
+                "status": {"code": 200, "message": "OK"},
+                "stay_informed": {},
+                "thanks": "For using an OpenCage API",
+                "timestamp": {"created_http": "Mon, 09 Apr 2018 12:33:01 GMT", "created_unix": 1523277181},
+                "total_results": 0
+            }))
+            .unwrap()
+        };
+        let inputs = ["a", "b", "c", "d"];
+
+        // the API refuses the third request
+        let batch = oc.batch(&inputs, Duration::from_secs(0), |input| match *input {
+            "c" => Err(GeocodingError::QuotaExceeded("quota exceeded".to_string())),
+            "b" => Err(GeocodingError::Forward),
+            _ => Ok(empty()),
+        });
+        assert_eq!(batch.results.len(), 2);
+        assert!(batch.results[1].is_err());
+        assert_eq!(batch.unprocessed, &["c", "d"]);
+
+        // the second request spends the last call of the quota
+        let batch = oc.batch(&inputs, Duration::from_secs(0), |input| {
+            if *input == "b" {
+                *oc.remaining.lock().unwrap() = Some(0);
+            }
+            Ok(empty())
+        });
+        assert_eq!(batch.results.len(), 2);
+        assert_eq!(batch.unprocessed, &["c", "d"]);
+        assert!(!batch.is_complete());
+
+        // a rate-limited request pauses the batch as well
+        let batch = oc.batch(&inputs, Duration::from_secs(0), |input| match *input {
+            "a" => Err(GeocodingError::RateLimited {
+                message: "rate limit exceeded".to_string(),
+                retry_after: None,
+            }),
+            _ => Ok(empty()),
+        });
+        assert!(batch.results.is_empty());
+        assert_eq!(batch.unprocessed, &inputs[..]);
+    }
+
+    #[test]
+    fn batch_after_quota_reset_test() {
+        let oc = Opencage::new("dcdbf0d783374909b3debee728c7cc10".to_string());
+        // the quota was spent by an earlier batch, and has reset since
+        let rate = Rate {
+            limit: 2500,
+            remaining: 0,
+            reset: UnixTime::from_seconds(1523318400),
+        };
+        *oc.limit.lock().unwrap() = Some(rate.limit);
+        *oc.remaining.lock().unwrap() = Some(rate.remaining);
+        *oc.reset.lock().unwrap() = Some(rate.reset);
+        let inputs = ["a", "b"];
+        let mut requests = 0;
+        let batch = oc.batch(&inputs, Duration::from_secs(0), |_| {
+            requests += 1;
+            Err::<OpencageResponse<f64>, _>(GeocodingError::Forward)
+        });
+        assert_eq!(requests, 2);
+        assert_eq!(batch.results.len(), 2);
+        assert!(batch.is_complete());
+
+        // it is still exhausted until it resets
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let tomorrow = UnixTime::from_seconds(now.as_secs() as i64 + 86_400);
+        *oc.reset.lock().unwrap() = Some(tomorrow);
+        let batch = oc.batch(&inputs, Duration::from_secs(0), |_| {
+            Err::<OpencageResponse<f64>, _>(GeocodingError::Forward)
+        });
+        assert!(batch.results.is_empty());
+        assert_eq!(batch.unprocessed, &inputs[..]);
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn localize_test() {
+        let timezone: Timezone = serde_json::from_str(
+            r#"{
+                "name": "Europe/Madrid",
+                "now_in_dst": 1,
+                "offset_sec": 7200,
+                "offset_string": "+0200",
+                "short_name": "CEST"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(timezone.tz(), Some(chrono_tz::Europe::Madrid));
+        let local = timezone
+            .localize(UnixTime::from_seconds(1523277181))
+            .unwrap();
+        assert_eq!(
+            local,
+            chrono_tz::Europe::Madrid
+                .with_ymd_and_hms(2018, 4, 9, 14, 33, 1)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn rate_test() {
+        let rate: Rate =
+            serde_json::from_str(r#"{"limit": 2500, "remaining": 2499, "reset": 1523318400}"#)
+                .unwrap();
+        assert_eq!(rate.reset, UnixTime::from_seconds(1523318400));
+        assert_eq!(
+            rate.reset.to_system_time(),
+            UNIX_EPOCH + Duration::from_secs(1523318400)
+        );
+    }
+
+    #[test]
+    fn best_result_test() {
+        let result = |confidence: i8, formatted: &str| {
+            serde_json::json!({
+                "components": {},
+                "confidence": confidence,
+                "formatted": formatted,
+                "geometry": {"lat": 0.0, "lng": 0.0}
+            })
+        };
+        let res: OpencageResponse<f64> = serde_json::from_value(serde_json::json!({
+            "documentation": "https://opencagedata.com/api",
+            "licenses": [],
+            "results": [result(7, "a"), result(9, "b"), result(9, "c")],
+            "status": {"code": 200, "message": "OK"},
+            "stay_informed": {},
+            "thanks": "For using an OpenCage API",
+            "timestamp": {"created_http": "Mon, 09 Apr 2018 12:33:01 GMT", "created_unix": 1523277181},
+            "total_results": 3
+        }))
+        .unwrap();
+        assert_eq!(res.best_result().unwrap().formatted, "b");
+        assert_eq!(res.results_with_min_confidence(8).len(), 2);
+    }
+
+    #[test]
+    fn license_test() {
+        let licenses: Vec<License> = serde_json::from_str(
+            r#"[{"name": "see attribution guide", "url": "https://opencagedata.com/credits"}]"#,
+        )
+        .unwrap();
+        assert_eq!(licenses[0].name, "see attribution guide");
+        assert_eq!(licenses[0].url, "https://opencagedata.com/credits");
+    }
+
+    #[test]
+    fn bounds_test() {
+        let bounds: Bounds<f64> = serde_json::from_str(
+            r#"{
+                "northeast": {"lat": 41.4015815, "lng": 2.128952},
+                "southwest": {"lat": 41.401227, "lng": 2.1284918}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(bounds.northeast, Point::new(2.128952, 41.4015815));
+        assert_eq!(bounds.to_rect().min(), Coord::from((2.1284918, 41.401227)));
+        let json = serde_json::to_value(&bounds).unwrap();
+        assert_eq!(json["southwest"]["lng"], 2.1284918);
+    }
+
+    #[test]
+    fn geometry_test() {
+        let geometry: Geometry<f64> =
+            serde_json::from_str(r#"{"lat": 41.4014067, "lng": 2.1287224}"#).unwrap();
+        assert_eq!(geometry.to_point(), Point::new(2.1287224, 41.4014067));
+    }
+
+    #[test]
+    fn components_test() {
+        let components: Components = serde_json::from_str(
+            r#"{
+                "ISO_3166-1_alpha-2": "ES",
+                "_category": "building",
+                "_type": "building",
+                "city": "Barcelona",
+                "house_number": "68",
+                "political_union": "European Union"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(components.component_type.as_deref(), Some("building"));
+        assert_eq!(components.iso_3166_1_alpha_2.as_deref(), Some("ES"));
+        assert_eq!(components.house_number.as_deref(), Some("68"));
+        assert!(components.road.is_none());
+        assert_eq!(components.extra["political_union"], "European Union");
+    }
+
+    #[test]
+    fn un_m49_annotation_test() {
+        let un_m49: UnM49 = serde_json::from_str(
+            r#"{
+                "regions": {"ES": "724", "EUROPE": "150", "WORLD": "001"},
+                "statistical_groupings": ["MEDC"]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(un_m49.regions["EUROPE"], "150");
+        assert_eq!(un_m49.statistical_groupings, vec!["MEDC"]);
+    }
+
+    #[test]
+    fn distance_from_q_annotation_test() {
+        let distance: DistanceFromQ<f64> = serde_json::from_str(r#"{"meters": 12}"#).unwrap();
+        assert_eq!(distance.meters, 12.0);
+    }
+
+    #[test]
+    fn grid_annotations_test() {
+        let itm: Itm<f64> =
+            serde_json::from_str(r#"{"easting": "716705.883", "northing": 734337.416}"#).unwrap();
+        assert_eq!(itm.easting, 716705.883);
+        assert_eq!(itm.northing, 734337.416);
+        let osgb: Osgb<f64> = serde_json::from_str(
+            r#"{"easting": 529709.0, "northing": "182003.0", "gridref": "TQ 297 820"}"#,
+        )
+        .unwrap();
+        assert_eq!(osgb.easting, 529709.0);
+        assert_eq!(osgb.northing, 182003.0);
+        let nuts: Nuts = serde_json::from_str(
+            r#"{"NUTS0": {"code": "IE"}, "NUTS1": {"code": "IE0"}, "NUTS2": {"code": "IE06"}}"#,
+        )
+        .unwrap();
+        assert_eq!(nuts.nuts2.unwrap().code, "IE06");
+        assert!(nuts.nuts3.is_none());
+    }
+
+    #[test]
+    fn sparse_annotations_test() {
+        // results in bodies of water lack most annotations
+        let result: Results<f64> = serde_json::from_str(
+            r#"{
+                "annotations": {
+                    "DMS": {"lat": "0° 0' 0.00000'' N", "lng": "0° 0' 0.00000'' E"},
+                    "MGRS": "31NAA6602100000",
+                    "Maidenhead": "JJ00aa00aa",
+                    "Mercator": {"x": 0, "y": 0},
+                    "OSM": {"url": "https://www.openstreetmap.org/?mlat=0.00000&mlon=0.00000#map=16/0.00000/0.00000"}
+                },
+                "components": {"_category": "natural/water", "_type": "body_of_water", "body_of_water": "Gulf of Guinea"},
+                "confidence": 1,
+                "formatted": "Gulf of Guinea",
+                "geometry": {"lat": 0, "lng": 0}
+            }"#,
+        )
+        .unwrap();
+        let annotations = result.annotations.unwrap();
+        assert!(annotations.osm.unwrap().url.contains("mlat=0.00000"));
+        assert!(annotations.timezone.is_none());
+        assert!(annotations.sun.is_none());
+        assert!(annotations.qibla.is_none());
+        assert!(annotations.callingcode.is_none());
+    }
+
+    #[test]
+    fn roadinfo_annotation_test() {
+        let roadinfo: RoadInfo = serde_json::from_str(
+            r#"{"drive_on": "left", "speed_in": "mph", "road_type": "motorway"}"#,
+        )
+        .unwrap();
+        assert_eq!(roadinfo.drive_on, "left");
+        assert_eq!(roadinfo.road_type.as_deref(), Some("motorway"));
+        assert!(roadinfo.road.is_none());
+    }
+
+    #[test]
+    fn parameters_test() {
+        let params = Parameters {
+            language: Some("de".to_string()),
+            countrycode: Some(vec![
+                CountryCode::new("DE").unwrap(),
+                CountryCode::new("at").unwrap(),
+            ]),
+            limit: Some(5),
+            proximity: Some(Point::new(13.4, 52.5)),
+            abbrv: true,
+            address_only: true,
+            roadinfo: true,
+            min_confidence: Some(7),
+            no_dedupe: true,
+            add_request: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            params.as_query(),
+            vec![
+                ("language", "de".to_string()),
+                ("countrycode", "de,at".to_string()),
+                ("limit", "5".to_string()),
+                ("proximity", "52.5,13.4".to_string()),
+                ("abbrv", "1".to_string()),
+                ("address_only", "1".to_string()),
+                ("roadinfo", "1".to_string()),
+                ("min_confidence", "7".to_string()),
+                ("no_dedupe", "1".to_string()),
+                ("add_request", "1".to_string()),
+                ("no_record", "1".to_string()),
+            ]
+        );
+        let params = Parameters {
+            no_record: Some(false),
+            ..Default::default()
+        };
+        assert!(params.as_query().is_empty());
+        assert_eq!(CountryCode::new("deu"), None);
+    }
+
     #[test]
     fn reverse_test_with_params() {
         let mut oc = Opencage::new("dcdbf0d783374909b3debee728c7cc10".to_string());
-        oc.parameters.language = Some("fr");
+        oc.parameters.language = Some("fr".to_string());
         let p = Point::new(2.12870, 41.40139);
         let res = oc.reverse(&p);
         assert_eq!(
@@ -664,7 +1670,7 @@ mod test {
     fn forward_test() {
         let oc = Opencage::new("dcdbf0d783374909b3debee728c7cc10".to_string());
         let address = "Schwabing, München";
-        let res = oc.forward(&address);
+        let res = oc.forward(address);
         assert_eq!(
             res.unwrap(),
             vec![Point(Coord {
@@ -676,11 +1682,14 @@ mod test {
     #[test]
     fn reverse_full_test() {
         let mut oc = Opencage::new("dcdbf0d783374909b3debee728c7cc10".to_string());
-        oc.parameters.language = Some("fr");
+        oc.parameters.language = Some("fr".to_string());
         let p = Point::new(2.12870, 41.40139);
         let res = oc.reverse_full(&p).unwrap();
         let first_result = &res.results[0];
-        assert_eq!(first_result.components["road"], "Carrer de Calatrava");
+        assert_eq!(
+            first_result.components.road.as_deref(),
+            Some("Carrer de Calatrava")
+        );
     }
     #[test]
     fn forward_full_test() {
@@ -690,7 +1699,7 @@ mod test {
             minimum_lonlat: Point::new(-0.13806939125061035, 51.51989264641164),
             maximum_lonlat: Point::new(-0.13427138328552246, 51.52319711775629),
         };
-        let res = oc.forward_full(&address, bbox).unwrap();
+        let res = oc.forward_full(address, bbox).unwrap();
         let first_result = &res.results[0];
         assert!(first_result.formatted.contains("UCL"));
     }
@@ -702,7 +1711,7 @@ mod test {
             Point::new(-0.13806939125061035, 51.51989264641164),
             Point::new(-0.13427138328552246, 51.52319711775629),
         );
-        let res = oc.forward_full(&address, bbox).unwrap();
+        let res = oc.forward_full(address, bbox).unwrap();
         let first_result = &res.results[0];
         assert!(
             first_result.formatted.contains("UCL")
@@ -717,7 +1726,7 @@ mod test {
             Point::from((-0.13806939125061035, 51.51989264641164)),
             Point::from((-0.13427138328552246, 51.52319711775629)),
         );
-        let res = oc.forward_full(&address, bbox).unwrap();
+        let res = oc.forward_full(address, bbox).unwrap();
         let first_result = &res.results[0];
         assert!(
             first_result.formatted.contains("UCL")
@@ -732,7 +1741,7 @@ mod test {
             (-0.13806939125061035, 51.51989264641164),
             (-0.13427138328552246, 51.52319711775629),
         );
-        let res = oc.forward_full(&address, bbox).unwrap();
+        let res = oc.forward_full(address, bbox).unwrap();
         let first_result = &res.results[0];
         assert!(first_result
             .formatted
@@ -742,7 +1751,7 @@ mod test {
     fn forward_full_test_nobox() {
         let oc = Opencage::new("dcdbf0d783374909b3debee728c7cc10".to_string());
         let address = "Moabit, Berlin, Germany";
-        let res = oc.forward_full(&address, NOBOX).unwrap();
+        let res = oc.forward_full(address, NOBOX).unwrap();
         let first_result = &res.results[0];
         assert_eq!(first_result.formatted, "Moabit, Berlin, Germany");
     }
@@ -16,20 +16,73 @@
 //! let res = osm.forward(&address);
 //! assert_eq!(res.unwrap(), vec![Point::new(11.5884858, 48.1700887)]);
 //! ```
+use crate::info::{ProviderInfo, RateLimit};
 use crate::GeocodingError;
 use crate::InputBounds;
 use crate::Point;
+use crate::StrictBounds;
 use crate::UA_STRING;
+use crate::{deserialize_float, deserialize_float_vec, DeserializeOwned};
 use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
 use crate::{Deserialize, Serialize};
 use crate::{Forward, Reverse};
+use geo_types::{
+    Geometry, GeometryCollection, LineString, MultiLineString, MultiPolygon, Polygon, Rect,
+};
 use num_traits::Float;
-use std::fmt::Debug;
+use reqwest::blocking::RequestBuilder;
+use reqwest::header::{REFERER, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::cmp::{Ordering, Reverse as Descending};
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Display};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 
 /// An instance of the Openstreetmap geocoding service
 pub struct Openstreetmap {
     client: Client,
     endpoint: String,
+    email: Option<String>,
+    user_agent: Option<String>,
+    referer: Option<String>,
+    basic_auth: Option<(String, Option<String>)>,
+    headers: Vec<(String, String)>,
+    last_request: Option<Mutex<Option<Instant>>>,
+    retry_rate_limited: bool,
+}
+
+// The longest `Retry-After` delay waited for before retrying
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+// The delay asked for by a `Retry-After` header
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    parse_retry_after(headers.get(RETRY_AFTER)?.to_str().ok()?, SystemTime::now())
+}
+
+// `Retry-After` is either a number of seconds or an HTTP date, which may already have passed
+fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    match value.parse() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => {
+            let date = httpdate::parse_http_date(value).ok()?;
+            Some(date.duration_since(now).unwrap_or_default())
+        }
+    }
+}
+
+// Nominatim answers clients exceeding its usage policy with a 429, or a 403 once blocked
+fn status_error(status: StatusCode, headers: &HeaderMap) -> Option<GeocodingError> {
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => Some(GeocodingError::RateLimited {
+            message: status.to_string(),
+            retry_after: retry_after(headers),
+        }),
+        StatusCode::FORBIDDEN => Some(GeocodingError::Forbidden(status.to_string())),
+        _ => None,
+    }
 }
 
 /// An instance of a parameter builder for Openstreetmap geocoding
@@ -40,6 +93,105 @@ where
     query: &'a str,
     addressdetails: bool,
     viewbox: Option<&'a InputBounds<T>>,
+    strict_bounds: Option<StrictBounds>,
+    sort_by: Option<SortBy>,
+    accept_language: Option<&'a str>,
+    countrycodes: Option<&'a [&'a str]>,
+    bounded: bool,
+    exclude_place_ids: Option<&'a [u64]>,
+    dedupe: bool,
+    limit: Option<u8>,
+    extratags: bool,
+    namedetails: bool,
+    polygon_geojson: bool,
+    polygon_threshold: Option<f64>,
+    layers: Option<&'a [Layer]>,
+    feature_type: Option<FeatureType>,
+}
+
+/// An instance of a parameter builder for Openstreetmap reverse geocoding
+pub struct OpenstreetmapReverseParams {
+    addressdetails: bool,
+    extratags: bool,
+    zoom: Option<u8>,
+    accept_language: Option<String>,
+    polygon_geojson: bool,
+    polygon_threshold: Option<f64>,
+}
+
+/// A kind of place Nominatim searches can be restricted to
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Layer {
+    /// Addresses, streets and administrative areas
+    Address,
+    /// Points of interest, such as shops or restaurants
+    Poi,
+    /// Railway features
+    Railway,
+    /// Natural features, such as rivers or mountains
+    Natural,
+    /// Man-made features, such as bridges or towers
+    Manmade,
+}
+
+impl Layer {
+    /// The value of the `layer` parameter
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Layer::Address => "address",
+            Layer::Poi => "poi",
+            Layer::Railway => "railway",
+            Layer::Natural => "natural",
+            Layer::Manmade => "manmade",
+        }
+    }
+}
+
+/// A level of the address hierarchy Nominatim searches can be restricted to
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FeatureType {
+    Country,
+    /// A state, province or other first-level administrative division
+    State,
+    /// A city, excluding towns and villages
+    City,
+    /// Any populated place, from a city to a hamlet
+    Settlement,
+}
+
+impl FeatureType {
+    /// The value of the `featureType` parameter
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FeatureType::Country => "country",
+            FeatureType::State => "state",
+            FeatureType::City => "city",
+            FeatureType::Settlement => "settlement",
+        }
+    }
+}
+
+/// How to order the results of a forward-geocoding request, instead of the server's default ordering
+///
+/// Sorting is stable: results comparing equal keep their server order.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SortBy {
+    /// Most important (highest `importance`) first; results without an importance come last
+    Importance,
+    /// Most specific (highest `place_rank`, e.g. 30 for a house) first; results without a rank come last
+    PlaceRank,
+    /// Closest to the center of the `viewbox` first; has no effect without a viewbox
+    DistanceToViewboxCenter,
+}
+
+// The properties of a result used to sort it
+pub(crate) trait Sortable<T>
+where
+    T: Float + Debug,
+{
+    fn importance(&self) -> Option<f64>;
+    fn place_rank(&self) -> Option<u64>;
+    fn position(&self) -> Point<T>;
 }
 
 impl<'a, T> OpenstreetmapParams<'a, T>
@@ -67,6 +219,20 @@ where
             query,
             addressdetails: false,
             viewbox: None,
+            strict_bounds: None,
+            sort_by: None,
+            accept_language: None,
+            countrycodes: None,
+            bounded: false,
+            exclude_place_ids: None,
+            dedupe: true,
+            limit: None,
+            extratags: false,
+            namedetails: false,
+            polygon_geojson: false,
+            polygon_threshold: None,
+            layers: None,
+            feature_type: None,
         }
     }
 
@@ -82,17 +248,368 @@ where
         self
     }
 
+    /// Check the results against the `viewbox`, which Nominatim only uses as a hint
+    pub fn with_strict_bounds(&mut self, strict_bounds: StrictBounds) -> &mut Self {
+        self.strict_bounds = Some(strict_bounds);
+        self
+    }
+
+    /// Sort the results client-side
+    pub fn with_sort_by(&mut self, sort_by: SortBy) -> &mut Self {
+        self.sort_by = Some(sort_by);
+        self
+    }
+
+    /// Set the `accept-language` property, a comma-separated list of the preferred languages
+    /// of the results (e.g. `de,en`)
+    pub fn with_accept_language(&mut self, accept_language: &'a str) -> &mut Self {
+        self.accept_language = Some(accept_language);
+        self
+    }
+
+    /// Set the `countrycodes` property, restricting results to countries given by their
+    /// ISO 3166-1 alpha-2 codes (e.g. `["de", "at"]`)
+    pub fn with_countrycodes(&mut self, countrycodes: &'a [&'a str]) -> &mut Self {
+        self.countrycodes = Some(countrycodes);
+        self
+    }
+
+    /// Set the `bounded` property, restricting results to the `viewbox` server-side rather than
+    /// merely preferring results within it
+    pub fn with_bounded(&mut self, bounded: bool) -> &mut Self {
+        self.bounded = bounded;
+        self
+    }
+
+    /// Set the `exclude_place_ids` property, skipping results by their `place_id`, e.g. to
+    /// fetch more results than those already seen
+    pub fn with_exclude_place_ids(&mut self, exclude_place_ids: &'a [u64]) -> &mut Self {
+        self.exclude_place_ids = Some(exclude_place_ids);
+        self
+    }
+
+    /// Set the `dedupe` property; when `false`, Nominatim returns every candidate instead of
+    /// merging duplicates, such as the segments of a street (default: `true`)
+    pub fn with_dedupe(&mut self, dedupe: bool) -> &mut Self {
+        self.dedupe = dedupe;
+        self
+    }
+
+    /// Set the `limit` property, the maximum number of results, from 1 to 40 (default: 10)
+    pub fn with_limit(&mut self, limit: u8) -> &mut Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Set the `extratags` property, adding the OSM tags of each result (e.g. `wikipedia` or
+    /// `opening_hours`) to its [`extratags`](struct.ResultProperties.html#structfield.extratags)
+    pub fn with_extratags(&mut self, extratags: bool) -> &mut Self {
+        self.extratags = extratags;
+        self
+    }
+
+    /// Set the `namedetails` property, adding the alternative and localized names of each
+    /// result (e.g. `name:en` or `old_name`) to its
+    /// [`namedetails`](struct.ResultProperties.html#structfield.namedetails)
+    pub fn with_namedetails(&mut self, namedetails: bool) -> &mut Self {
+        self.namedetails = namedetails;
+        self
+    }
+
+    /// Set the `polygon_geojson` property, returning the outline of each result, such as the
+    /// boundary of a city, as its [`geometry`](struct.OpenstreetmapResult.html#structfield.geometry)
+    pub fn with_polygon_geojson(&mut self, polygon_geojson: bool) -> &mut Self {
+        self.polygon_geojson = polygon_geojson;
+        self
+    }
+
+    /// Set the `polygon_threshold` property, simplifying outlines server-side to within this
+    /// tolerance, in degrees (e.g. `0.001`)
+    pub fn with_polygon_threshold(&mut self, polygon_threshold: f64) -> &mut Self {
+        self.polygon_threshold = Some(polygon_threshold);
+        self
+    }
+
+    /// Set the `layer` property, restricting results to some kinds of places
+    pub fn with_layers(&mut self, layers: &'a [Layer]) -> &mut Self {
+        self.layers = Some(layers);
+        self
+    }
+
+    /// Set the `featureType` property, restricting results to one level of the address
+    /// hierarchy, such as cities
+    pub fn with_feature_type(&mut self, feature_type: FeatureType) -> &mut Self {
+        self.feature_type = Some(feature_type);
+        self
+    }
+
     /// Build and return an instance of OpenstreetmapParams
     pub fn build(&self) -> OpenstreetmapParams<'a, T> {
         OpenstreetmapParams {
             query: self.query,
             addressdetails: self.addressdetails,
             viewbox: self.viewbox,
+            strict_bounds: self.strict_bounds,
+            sort_by: self.sort_by,
+            accept_language: self.accept_language,
+            countrycodes: self.countrycodes,
+            bounded: self.bounded,
+            exclude_place_ids: self.exclude_place_ids,
+            dedupe: self.dedupe,
+            limit: self.limit,
+            extratags: self.extratags,
+            namedetails: self.namedetails,
+            polygon_geojson: self.polygon_geojson,
+            polygon_threshold: self.polygon_threshold,
+            layers: self.layers,
+            feature_type: self.feature_type,
+        }
+    }
+
+    /// The search query parameters, shared with Nominatim-compatible providers
+    pub(crate) fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![
+            ("q", self.query.to_string()),
+            (
+                "addressdetails",
+                String::from(if self.addressdetails { "1" } else { "0" }),
+            ),
+        ];
+        if let Some(vb) = self.viewbox {
+            query.push(("viewbox", String::from(*vb)));
+        }
+        if let Some(accept_language) = self.accept_language {
+            query.push(("accept-language", accept_language.to_string()));
+        }
+        if let Some(countrycodes) = self.countrycodes {
+            query.push(("countrycodes", countrycodes.join(",")));
+        }
+        if self.bounded {
+            query.push(("bounded", String::from("1")));
+        }
+        if let Some(exclude_place_ids) = self.exclude_place_ids {
+            let ids: Vec<String> = exclude_place_ids.iter().map(u64::to_string).collect();
+            query.push(("exclude_place_ids", ids.join(",")));
+        }
+        if !self.dedupe {
+            query.push(("dedupe", String::from("0")));
+        }
+        if let Some(limit) = self.limit {
+            query.push(("limit", limit.to_string()));
+        }
+        if self.extratags {
+            query.push(("extratags", String::from("1")));
+        }
+        if self.namedetails {
+            query.push(("namedetails", String::from("1")));
+        }
+        if self.polygon_geojson {
+            query.push(("polygon_geojson", String::from("1")));
+        }
+        if let Some(polygon_threshold) = self.polygon_threshold {
+            query.push(("polygon_threshold", polygon_threshold.to_string()));
+        }
+        if let Some(layers) = self.layers {
+            let layers: Vec<&str> = layers.iter().map(|layer| layer.as_str()).collect();
+            query.push(("layer", layers.join(",")));
+        }
+        if let Some(feature_type) = self.feature_type {
+            query.push(("featureType", feature_type.as_str().to_string()));
+        }
+        query
+    }
+
+    /// Apply the `sort_by` order, then the `strict_bounds` mode, to a list of results
+    ///
+    /// Sorting first makes `StrictBounds::Error` check the result that ends up first.
+    pub(crate) fn order_results<R, F>(
+        &self,
+        mut results: Vec<R>,
+        point: F,
+    ) -> Result<Vec<R>, GeocodingError>
+    where
+        R: Sortable<T>,
+        F: Fn(&R) -> Point<T>,
+    {
+        self.sort(&mut results);
+        self.enforce_bounds(results, point)
+    }
+
+    /// Apply the `strict_bounds` mode, if any, to a list of results
+    fn enforce_bounds<R, F>(&self, results: Vec<R>, point: F) -> Result<Vec<R>, GeocodingError>
+    where
+        F: Fn(&R) -> Point<T>,
+    {
+        match (self.viewbox, self.strict_bounds) {
+            (Some(viewbox), Some(mode)) => viewbox.enforce(results, mode, point),
+            _ => Ok(results),
+        }
+    }
+
+    /// Apply the `sort_by` order, if any, to a list of results
+    fn sort<R>(&self, results: &mut [R])
+    where
+        R: Sortable<T>,
+    {
+        // `sort_by` is stable, and `None` keys compare as smallest
+        match self.sort_by {
+            Some(SortBy::Importance) => results.sort_by(|a, b| {
+                b.importance()
+                    .partial_cmp(&a.importance())
+                    .unwrap_or(Ordering::Equal)
+            }),
+            Some(SortBy::PlaceRank) => {
+                results.sort_by_key(|result| Descending(result.place_rank()))
+            }
+            Some(SortBy::DistanceToViewboxCenter) => {
+                if let Some(viewbox) = self.viewbox {
+                    let center = viewbox.center();
+                    results.sort_by(|a, b| {
+                        distance(&center, &a.position())
+                            .partial_cmp(&distance(&center, &b.position()))
+                            .unwrap_or(Ordering::Equal)
+                    })
+                }
+            }
+            None => (),
+        }
+    }
+}
+
+impl OpenstreetmapReverseParams {
+    /// Create a new OpenStreetMap reverse parameter builder, requesting address details
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::openstreetmap::OpenstreetmapReverseParams;
+    ///
+    /// let params = OpenstreetmapReverseParams::new()
+    ///     .with_extratags(true)
+    ///     .build();
+    /// ```
+    pub fn new() -> OpenstreetmapReverseParams {
+        OpenstreetmapReverseParams {
+            addressdetails: true,
+            extratags: false,
+            zoom: None,
+            accept_language: None,
+            polygon_geojson: false,
+            polygon_threshold: None,
+        }
+    }
+
+    /// Set the `addressdetails` property
+    pub fn with_addressdetails(&mut self, addressdetails: bool) -> &mut Self {
+        self.addressdetails = addressdetails;
+        self
+    }
+
+    /// Set the `extratags` property, adding the OSM tags of the result (e.g. `wikidata` or
+    /// `opening_hours`) to its properties
+    pub fn with_extratags(&mut self, extratags: bool) -> &mut Self {
+        self.extratags = extratags;
+        self
+    }
+
+    /// Set the `zoom` property, the level of detail of the result, from 3 (country) to
+    /// 18 (building, the default)
+    ///
+    /// Levels in between include 5 (state), 8 (county), 10 (city), 14 (suburb), 16 (major
+    /// streets) and 17 (major and minor streets).
+    pub fn with_zoom(&mut self, zoom: u8) -> &mut Self {
+        self.zoom = Some(zoom);
+        self
+    }
+
+    /// Set the `accept-language` property, a comma-separated list of the preferred languages
+    /// of the result (e.g. `de,en`)
+    pub fn with_accept_language(&mut self, accept_language: &str) -> &mut Self {
+        self.accept_language = Some(accept_language.to_string());
+        self
+    }
+
+    /// Set the `polygon_geojson` property, returning the outline of the result as its
+    /// [`geometry`](struct.OpenstreetmapResult.html#structfield.geometry)
+    pub fn with_polygon_geojson(&mut self, polygon_geojson: bool) -> &mut Self {
+        self.polygon_geojson = polygon_geojson;
+        self
+    }
+
+    /// Set the `polygon_threshold` property, simplifying the outline server-side to within this
+    /// tolerance, in degrees (e.g. `0.001`)
+    pub fn with_polygon_threshold(&mut self, polygon_threshold: f64) -> &mut Self {
+        self.polygon_threshold = Some(polygon_threshold);
+        self
+    }
+
+    /// Build and return an instance of OpenstreetmapReverseParams
+    pub fn build(&self) -> OpenstreetmapReverseParams {
+        OpenstreetmapReverseParams {
+            addressdetails: self.addressdetails,
+            extratags: self.extratags,
+            zoom: self.zoom,
+            accept_language: self.accept_language.clone(),
+            polygon_geojson: self.polygon_geojson,
+            polygon_threshold: self.polygon_threshold,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![
+            (
+                "addressdetails",
+                String::from(if self.addressdetails { "1" } else { "0" }),
+            ),
+            (
+                "extratags",
+                String::from(if self.extratags { "1" } else { "0" }),
+            ),
+        ];
+        if let Some(zoom) = self.zoom {
+            query.push(("zoom", zoom.to_string()));
         }
+        if let Some(accept_language) = &self.accept_language {
+            query.push(("accept-language", accept_language.clone()));
+        }
+        if self.polygon_geojson {
+            query.push(("polygon_geojson", String::from("1")));
+        }
+        if let Some(polygon_threshold) = self.polygon_threshold {
+            query.push(("polygon_threshold", polygon_threshold.to_string()));
+        }
+        query
+    }
+}
+
+impl Default for OpenstreetmapReverseParams {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
+// An equirectangular approximation of the distance between two points, in degrees,
+// which is enough to order results
+fn distance<T>(a: &Point<T>, b: &Point<T>) -> f64
+where
+    T: Float + Debug,
+{
+    let (ax, ay) = (a.x().to_f64().unwrap(), a.y().to_f64().unwrap());
+    let (bx, by) = (b.x().to_f64().unwrap(), b.y().to_f64().unwrap());
+    let dx = (bx - ax) * ((ay + by) / 2.0).to_radians().cos();
+    let dy = by - ay;
+    dx.hypot(dy)
+}
+
 impl Openstreetmap {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "Nominatim",
+        docs_url: "https://nominatim.org/release-docs/develop/api/Overview/",
+        terms_url: Some("https://operations.osmfoundation.org/policies/nominatim/"),
+        attribution: Some("© OpenStreetMap contributors"),
+        rate_limit: Some(RateLimit::per_second(1)),
+    };
+
     /// Create a new Openstreetmap geocoding instance using the default endpoint
     pub fn new() -> Self {
         Openstreetmap::new_with_endpoint("https://nominatim.openstreetmap.org/".to_string())
@@ -108,7 +625,98 @@ impl Openstreetmap {
             .default_headers(headers)
             .build()
             .expect("Couldn't build a client!");
-        Openstreetmap { client, endpoint }
+        Openstreetmap {
+            client,
+            endpoint,
+            email: None,
+            user_agent: None,
+            referer: None,
+            basic_auth: None,
+            headers: vec![],
+            last_request: None,
+            retry_rate_limited: false,
+        }
+    }
+
+    /// Create a new Openstreetmap geocoding instance using the default endpoint, identifying
+    /// your application with its own `User-Agent` header
+    ///
+    /// The [Nominatim Usage Policy](https://operations.osmfoundation.org/policies/nominatim/)
+    /// requires applications to identify themselves, rather than with this crate's default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Openstreetmap;
+    ///
+    /// let osm = Openstreetmap::new_with_user_agent("my-app/1.0 (contact@example.com)")
+    ///     .with_referer("https://example.com/");
+    /// ```
+    pub fn new_with_user_agent(user_agent: &str) -> Self {
+        let mut osm = Openstreetmap::new();
+        osm.user_agent = Some(user_agent.to_owned());
+        osm
+    }
+
+    /// Send a `Referer` header with every request, identifying the website using the service
+    pub fn with_referer(mut self, referer: &str) -> Self {
+        self.referer = Some(referer.to_owned());
+        self
+    }
+
+    /// Authenticate every request with HTTP basic auth, e.g. to a self-hosted instance behind a
+    /// reverse proxy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Openstreetmap;
+    ///
+    /// let osm = Openstreetmap::new_with_endpoint("https://nominatim.example.com/".to_string())
+    ///     .with_basic_auth("user", Some("password"))
+    ///     .with_header("X-Api-Key", "secret");
+    /// ```
+    pub fn with_basic_auth(mut self, username: &str, password: Option<&str>) -> Self {
+        self.basic_auth = Some((username.to_owned(), password.map(str::to_owned)));
+        self
+    }
+
+    /// Wait between requests so as not to exceed the rate limit of the public endpoint, one
+    /// request per second
+    ///
+    /// Requests made from several threads through the same instance are spaced out too. This
+    /// is opt-in, as self-hosted instances have no such limit.
+    pub fn with_throttling(mut self) -> Self {
+        self.last_request = Some(Mutex::new(None));
+        self
+    }
+
+    /// Retry a rate-limited request once, after the delay asked for by its `Retry-After`
+    /// header, if that delay is at most a minute
+    ///
+    /// Otherwise, rate-limited requests fail with
+    /// [`GeocodingError::RateLimited`](../enum.GeocodingError.html#variant.RateLimited).
+    pub fn with_retry_rate_limited(mut self) -> Self {
+        self.retry_rate_limited = true;
+        self
+    }
+
+    /// Send an additional header with every request, such as an API key expected by a
+    /// reverse proxy
+    ///
+    /// Invalid header names or values are reported as errors when making requests.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Send a contact email address with every request
+    ///
+    /// The [Nominatim Usage Policy](https://operations.osmfoundation.org/policies/nominatim/)
+    /// asks that large numbers of requests to the public endpoint identify their sender this way.
+    pub fn with_email(mut self, email: &str) -> Self {
+        self.email = Some(email.to_owned());
+        self
     }
 
     /// A forward-geocoding lookup of an address, returning a full detailed response
@@ -148,31 +756,221 @@ impl Openstreetmap {
         T: Float + Debug,
         for<'de> T: Deserialize<'de>,
     {
-        let format = String::from("geojson");
-        let addressdetails = String::from(if params.addressdetails { "1" } else { "0" });
-        // For lifetime issues
-        let viewbox;
+        let mut res: OpenstreetmapResponse<T> = self.get("search", params.as_query(), "geojson")?;
+        res.features = params.order_results(res.features, OpenstreetmapResult::point)?;
+        Ok(res)
+    }
 
-        let mut query = vec![
-            (&"q", params.query),
-            (&"format", &format),
-            (&"addressdetails", &addressdetails),
+    /// A reverse lookup of a point, returning a full detailed response
+    ///
+    /// Accepts an [`OpenstreetmapReverseParams`](struct.OpenstreetmapReverseParams.html) struct
+    /// for specifying options, including whether to include address details and extra tags in
+    /// the response. The response holds a single result, with its bounding box.
+    ///
+    /// Please see [the documentation](https://nominatim.org/release-docs/develop/api/Reverse/) for details.
+    ///
+    /// Address details are requested unless disabled with
+    /// [`with_addressdetails`](struct.OpenstreetmapReverseParams.html#method.with_addressdetails),
+    /// filling the [`address`](struct.ResultProperties.html#structfield.address) of the result.
+    ///
+    /// This method passes the `format` parameter to the API.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::{Openstreetmap, Point};
+    /// use geocoding::openstreetmap::{OpenstreetmapResponse, OpenstreetmapReverseParams};
+    ///
+    /// let osm = Openstreetmap::new();
+    /// let p = Point::new(2.12870, 41.40139);
+    /// let params = OpenstreetmapReverseParams::new().with_extratags(true).build();
+    /// let res: OpenstreetmapResponse<f64> = osm.reverse_full(&p, &params).unwrap();
+    /// let address = res.features[0].properties.address.as_ref().unwrap();
+    /// assert_eq!(address.road.as_deref(), Some("Carrer de Calatrava"));
+    /// ```
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+        params: &OpenstreetmapReverseParams,
+    ) -> Result<OpenstreetmapResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = params.as_query();
+        query.extend(vec![
+            ("lon", point.x().to_f64().unwrap().to_string()),
+            ("lat", point.y().to_f64().unwrap().to_string()),
+        ]);
+        self.get("reverse", query, "geojson")
+    }
+
+    /// Look up OSM elements by their ids, returning a full detailed response with address details
+    ///
+    /// Nominatim accepts up to 50 ids per request, and omits elements it hasn't indexed.
+    ///
+    /// Please see [the documentation](https://nominatim.org/release-docs/develop/api/Lookup/) for details.
+    ///
+    /// This method passes the `format` and `addressdetails` parameters to the API.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Openstreetmap;
+    /// use geocoding::openstreetmap::{OpenstreetmapResponse, OsmId, OsmType};
+    ///
+    /// let osm = Openstreetmap::new();
+    /// let ids = [OsmId::new(OsmType::Way, 355421084)];
+    /// let res: OpenstreetmapResponse<f64> = osm.lookup(&ids).unwrap();
+    /// let result = &res.features[0].properties;
+    /// assert!(result.display_name.contains("Carrer de Calatrava"));
+    /// ```
+    pub fn lookup<T>(&self, ids: &[OsmId]) -> Result<OpenstreetmapResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let osm_ids: Vec<String> = ids.iter().map(OsmId::to_string).collect();
+        let query = vec![
+            ("osm_ids", osm_ids.join(",")),
+            ("addressdetails", String::from("1")),
         ];
+        self.get("lookup", query, "geojson")
+    }
 
-        if let Some(vb) = params.viewbox {
-            viewbox = String::from(*vb);
-            query.push((&"viewbox", &viewbox));
-        }
+    /// A forward-geocoding lookup of an address in the `jsonv2` format, for instances not
+    /// serving GeoJSON
+    ///
+    /// Accepts the same [`OpenstreetmapParams`](struct.OpenstreetmapParams.html) as
+    /// [`forward_full`](#method.forward_full).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Openstreetmap;
+    /// use geocoding::openstreetmap::{NominatimPlace, OpenstreetmapParams};
+    ///
+    /// let osm = Openstreetmap::new();
+    /// let params = OpenstreetmapParams::new("Schwabing, München").build();
+    /// let res: Vec<NominatimPlace<f64>> = osm.forward_jsonv2(&params).unwrap();
+    /// assert_eq!(res[0].addresstype.as_deref(), Some("suburb"));
+    /// ```
+    pub fn forward_jsonv2<T>(
+        &self,
+        params: &OpenstreetmapParams<T>,
+    ) -> Result<Vec<NominatimPlace<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let res: Vec<NominatimPlace<T>> = self.get("search", params.as_query(), "jsonv2")?;
+        params.order_results(res, NominatimPlace::point)
+    }
+
+    /// A reverse lookup of a point in the `jsonv2` format, for instances not serving GeoJSON
+    pub fn reverse_jsonv2<T>(
+        &self,
+        point: &Point<T>,
+        params: &OpenstreetmapReverseParams,
+    ) -> Result<NominatimPlace<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = params.as_query();
+        query.extend(vec![
+            ("lon", point.x().to_f64().unwrap().to_string()),
+            ("lat", point.y().to_f64().unwrap().to_string()),
+        ]);
+        self.get("reverse", query, "jsonv2")
+    }
 
+    /// Check the health of the Nominatim instance, and the age of its data
+    ///
+    /// Please see [the documentation](https://nominatim.org/release-docs/develop/api/Status/) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Openstreetmap;
+    ///
+    /// let osm = Openstreetmap::new();
+    /// let status = osm.status().unwrap();
+    /// assert!(status.is_ok());
+    /// ```
+    pub fn status(&self) -> Result<NominatimStatus, GeocodingError> {
+        // an unhealthy instance answers with a 500 status and a JSON body describing the error
         let resp = self
-            .client
-            .get(&format!("{}search", self.endpoint))
-            .query(&query)
-            .send()?
-            .error_for_status()?;
-        let res: OpenstreetmapResponse<T> = resp.json()?;
+            .request("status.php")
+            .query(&[("format", "json")])
+            .send()?;
+        if let Some(err) = status_error(resp.status(), resp.headers()) {
+            return Err(err);
+        }
+        let res: NominatimStatus = resp.json()?;
+        Ok(res)
+    }
+
+    fn get<R>(
+        &self,
+        path: &str,
+        mut query: Vec<(&'static str, String)>,
+        format: &str,
+    ) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        query.push(("format", format.to_string()));
+        if let Some(email) = &self.email {
+            query.push(("email", email.clone()));
+        }
+        let mut resp = self.request(path).query(&query).send()?;
+        if resp.status() == StatusCode::TOO_MANY_REQUESTS && self.retry_rate_limited {
+            if let Some(delay) =
+                retry_after(resp.headers()).filter(|delay| *delay <= MAX_RETRY_AFTER)
+            {
+                thread::sleep(delay);
+                resp = self.request(path).query(&query).send()?;
+            }
+        }
+        if let Some(err) = status_error(resp.status(), resp.headers()) {
+            return Err(err);
+        }
+        let res: R = resp.error_for_status()?.json()?;
         Ok(res)
     }
+
+    fn request(&self, path: &str) -> RequestBuilder {
+        self.throttle();
+        let mut req = self.client.get(format!("{}{}", self.endpoint, path));
+        if let Some(user_agent) = &self.user_agent {
+            req = req.header(USER_AGENT, user_agent);
+        }
+        if let Some(referer) = &self.referer {
+            req = req.header(REFERER, referer);
+        }
+        if let Some((username, password)) = &self.basic_auth {
+            req = req.basic_auth(username, password.as_ref());
+        }
+        for (name, value) in &self.headers {
+            req = req.header(name.as_str(), value.as_str());
+        }
+        req
+    }
+
+    // The lock is held while waiting, so that concurrent requests are made one at a time
+    fn throttle(&self) {
+        if let (Some(last_request), Some(rate_limit)) = (&self.last_request, Self::INFO.rate_limit)
+        {
+            let mut last_request = last_request.lock().unwrap();
+            if let Some(elapsed) = last_request.map(|instant| instant.elapsed()) {
+                if elapsed < rate_limit.min_interval() {
+                    thread::sleep(rate_limit.min_interval() - elapsed);
+                }
+            }
+            *last_request = Some(Instant::now());
+        }
+    }
 }
 
 impl Default for Openstreetmap {
@@ -190,17 +988,12 @@ where
     ///
     /// This method passes the `format` parameter to the API.
     fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
-        let resp = self
-            .client
-            .get(&format!("{}search", self.endpoint))
-            .query(&[(&"q", place), (&"format", &String::from("geojson"))])
-            .send()?
-            .error_for_status()?;
-        let res: OpenstreetmapResponse<T> = resp.json()?;
+        let res: OpenstreetmapResponse<T> =
+            self.get("search", vec![("q", place.to_string())], "geojson")?;
         Ok(res
             .features
             .iter()
-            .map(|res| Point::new(res.geometry.coordinates.0, res.geometry.coordinates.1))
+            .map(OpenstreetmapResult::point)
             .collect())
     }
 }
@@ -213,19 +1006,12 @@ where
     /// A reverse lookup of a point. More detail on the format of the
     /// returned `String` can be found [here](https://nominatim.org/release-docs/develop/api/Reverse/)
     ///
-    /// This method passes the `format` parameter to the API.
+    /// Use [`reverse_full`](struct.Openstreetmap.html#method.reverse_full) for the structured
+    /// address of the result.
+    ///
+    /// This method passes the `format` and `addressdetails` parameters to the API.
     fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
-        let resp = self
-            .client
-            .get(&format!("{}reverse", self.endpoint))
-            .query(&[
-                (&"lon", &point.x().to_f64().unwrap().to_string()),
-                (&"lat", &point.y().to_f64().unwrap().to_string()),
-                (&"format", &String::from("geojson")),
-            ])
-            .send()?
-            .error_for_status()?;
-        let res: OpenstreetmapResponse<T> = resp.json()?;
+        let res = self.reverse_full(point, &OpenstreetmapReverseParams::new())?;
         let address = &res.features[0];
         Ok(Some(address.properties.display_name.to_string()))
     }
@@ -291,6 +1077,35 @@ where
     pub features: Vec<OpenstreetmapResult<T>>,
 }
 
+/// The status of a Nominatim instance
+///
+///```json
+///{
+///  "status": 0,
+///  "message": "OK",
+///  "data_updated": "2020-05-04T14:47:00+00:00",
+///  "software_version": "3.6.0-0",
+///  "database_version": "3.6.0-0"
+///}
+///```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NominatimStatus {
+    /// 0 if the instance is healthy, or an error code
+    pub status: u16,
+    pub message: String,
+    /// When the data was last updated, as an RFC 3339 timestamp
+    pub data_updated: Option<String>,
+    pub software_version: Option<String>,
+    pub database_version: Option<String>,
+}
+
+impl NominatimStatus {
+    /// Whether the instance is healthy
+    pub fn is_ok(&self) -> bool {
+        self.status == 0
+    }
+}
+
 /// A geocoding result
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OpenstreetmapResult<T>
@@ -303,18 +1118,322 @@ where
     pub geometry: ResultGeometry<T>,
 }
 
+impl<T> Sortable<T> for OpenstreetmapResult<T>
+where
+    T: Float + Debug,
+{
+    fn importance(&self) -> Option<f64> {
+        Some(self.properties.importance)
+    }
+
+    fn place_rank(&self) -> Option<u64> {
+        Some(self.properties.place_rank)
+    }
+
+    fn position(&self) -> Point<T> {
+        self.point()
+    }
+}
+
+impl<T> OpenstreetmapResult<T>
+where
+    T: Float + Debug,
+{
+    /// The point of the result, or the center of its bounding box if its geometry is an outline
+    pub fn point(&self) -> Point<T> {
+        self.geometry
+            .to_point()
+            .unwrap_or_else(|| self.bbox_rect().center().into())
+    }
+
+    /// The bounding box as a `Rect`
+    pub fn bbox_rect(&self) -> Rect<T> {
+        let (min_x, min_y, max_x, max_y) = self.bbox;
+        Rect::new((min_x, min_y), (max_x, max_y))
+    }
+}
+
+/// A place in the `jsonv2` format
+///
+/// See [the documentation](https://nominatim.org/release-docs/develop/api/Output/#jsonv2) for more details
+///
+///```json
+///{
+///  "place_id": 263681481,
+///  "licence": "Data © OpenStreetMap contributors, ODbL 1.0. https://osm.org/copyright",
+///  "osm_type": "way",
+///  "osm_id": 355421084,
+///  "lat": "41.40140675",
+///  "lon": "2.12872241167437",
+///  "category": "building",
+///  "type": "apartments",
+///  "place_rank": 30,
+///  "importance": 0.741,
+///  "addresstype": "building",
+///  "name": "",
+///  "display_name": "68, Carrer de Calatrava, les Tres Torres, Sarrià - Sant Gervasi, Barcelona, Catalonia, 08017, Spain",
+///  "boundingbox": ["41.401227", "41.4015815", "2.1284918", "2.128952"]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NominatimPlace<T>
+where
+    T: Float + Debug,
+{
+    pub place_id: u64,
+    pub licence: String,
+    pub osm_type: Option<OsmType>,
+    pub osm_id: Option<u64>,
+    #[serde(deserialize_with = "deserialize_float")]
+    pub lat: T,
+    #[serde(deserialize_with = "deserialize_float")]
+    pub lon: T,
+    pub category: Category,
+    pub r#type: PlaceType,
+    pub place_rank: u64,
+    pub importance: Option<f64>,
+    /// The kind of place in the address hierarchy, e.g. `city` or `road`
+    pub addresstype: Option<String>,
+    pub name: Option<String>,
+    pub display_name: String,
+    /// The bounding box, as `[min latitude, max latitude, min longitude, max longitude]`
+    #[serde(deserialize_with = "deserialize_float_vec")]
+    pub boundingbox: Vec<T>,
+    pub address: Option<AddressDetails>,
+    pub extratags: Option<HashMap<String, String>>,
+    pub namedetails: Option<HashMap<String, String>>,
+    /// The outline of the place, if `polygon_geojson` was requested
+    pub geojson: Option<ResultGeometry<T>>,
+}
+
+impl<T> NominatimPlace<T>
+where
+    T: Float + Debug,
+{
+    /// The point of the place
+    pub fn point(&self) -> Point<T> {
+        Point::new(self.lon, self.lat)
+    }
+}
+
+impl<T> Sortable<T> for NominatimPlace<T>
+where
+    T: Float + Debug,
+{
+    fn importance(&self) -> Option<f64> {
+        self.importance
+    }
+
+    fn place_rank(&self) -> Option<u64> {
+        Some(self.place_rank)
+    }
+
+    fn position(&self) -> Point<T> {
+        self.point()
+    }
+}
+
 /// Geocoding result properties
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ResultProperties {
     pub place_id: u64,
-    pub osm_type: String,
+    pub osm_type: OsmType,
     pub osm_id: u64,
     pub display_name: String,
     pub place_rank: u64,
-    pub category: String,
-    pub r#type: String,
+    pub category: Category,
+    pub r#type: PlaceType,
     pub importance: f64,
     pub address: Option<AddressDetails>,
+    /// Additional OSM tags of the result, if `extratags` was requested
+    pub extratags: Option<HashMap<String, String>>,
+    /// The names of the result, keyed by OSM tag (e.g. `name`, `name:en` or `ref`), if
+    /// `namedetails` was requested
+    pub namedetails: Option<HashMap<String, String>>,
+}
+
+impl ResultProperties {
+    /// The OSM element this result was derived from, e.g. to pass to
+    /// [`Openstreetmap::lookup`](struct.Openstreetmap.html#method.lookup)
+    pub fn to_osm_id(&self) -> OsmId {
+        OsmId::new(self.osm_type, self.osm_id)
+    }
+
+    /// How precisely this result matches an address
+    ///
+    /// Nominatim returns house numbers interpolated along address interpolation lines
+    /// (and from TIGER data in the US) as `place=house` results derived from a way; their
+    /// position may be tens of meters off. Telling exact house numbers apart requires
+    /// the `addressdetails` option.
+    pub fn match_quality(&self) -> MatchQuality {
+        let house_number = self
+            .address
+            .as_ref()
+            .and_then(|address| address.house_number.as_ref());
+        match (&self.category, &self.r#type) {
+            (Category::Place, PlaceType::House) if self.osm_type == OsmType::Way => {
+                MatchQuality::InterpolatedHouseNumber
+            }
+            (Category::Place, PlaceType::Postcode) => MatchQuality::Postcode,
+            _ if house_number.is_some() => MatchQuality::ExactHouseNumber,
+            (Category::Highway, _) => MatchQuality::Street,
+            _ => MatchQuality::Other,
+        }
+    }
+}
+
+// An enum of OSM tag keys or values, converted from and to strings, with a fallback for
+// those it doesn't list
+macro_rules! osm_tag_enum {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident => $value:literal,)* }) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        #[serde(from = "String", into = "String")]
+        pub enum $name {
+            $($variant,)*
+            /// Any other value
+            Other(String),
+        }
+
+        impl $name {
+            /// The value as returned by the API
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Other(value) => value,
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                match value.as_str() {
+                    $($value => $name::$variant,)*
+                    _ => $name::Other(value),
+                }
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                match value {
+                    $name::Other(value) => value,
+                    value => value.as_str().to_string(),
+                }
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+    };
+}
+
+osm_tag_enum! {
+    /// The main OSM tag of a result, e.g. `highway` or `building`
+    Category {
+        Amenity => "amenity",
+        Boundary => "boundary",
+        Building => "building",
+        Highway => "highway",
+        Historic => "historic",
+        Landuse => "landuse",
+        Leisure => "leisure",
+        ManMade => "man_made",
+        Natural => "natural",
+        Office => "office",
+        Place => "place",
+        Railway => "railway",
+        Shop => "shop",
+        Tourism => "tourism",
+        Waterway => "waterway",
+    }
+}
+
+osm_tag_enum! {
+    /// The value of the main OSM tag of a result, e.g. `city` for a `place`
+    PlaceType {
+        Administrative => "administrative",
+        Apartments => "apartments",
+        City => "city",
+        Country => "country",
+        County => "county",
+        Hamlet => "hamlet",
+        House => "house",
+        Neighbourhood => "neighbourhood",
+        Postcode => "postcode",
+        Residential => "residential",
+        State => "state",
+        Suburb => "suburb",
+        Town => "town",
+        Village => "village",
+    }
+}
+
+/// How precisely a geocoding result matches an address
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MatchQuality {
+    /// An exact house number, from an OSM element carrying address tags
+    ExactHouseNumber,
+    /// A house number whose position was interpolated along an address interpolation line
+    InterpolatedHouseNumber,
+    /// A street, without house number
+    Street,
+    /// A postcode centroid
+    Postcode,
+    /// Any other place, such as a POI, a locality or an administrative area
+    Other,
+}
+
+/// The kind of an OpenStreetMap element
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OsmType {
+    #[serde(alias = "N")]
+    Node,
+    #[serde(alias = "W")]
+    Way,
+    #[serde(alias = "R")]
+    Relation,
+}
+
+impl OsmType {
+    /// The single-letter prefix Nominatim uses for this element type (`N`, `W` or `R`)
+    pub fn prefix(self) -> char {
+        match self {
+            OsmType::Node => 'N',
+            OsmType::Way => 'W',
+            OsmType::Relation => 'R',
+        }
+    }
+}
+
+/// A reference to a single OpenStreetMap element, e.g. way 355421084
+///
+/// This type can be obtained from Nominatim results as well as from other providers
+/// exposing OSM references (see [`opencage::Osm`](../opencage/struct.Osm.html)), so that
+/// results from different providers can be cross-referenced.
+///
+/// Its `Display` implementation uses Nominatim's prefixed notation (`W355421084`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct OsmId {
+    pub osm_type: OsmType,
+    pub id: u64,
+}
+
+impl OsmId {
+    /// Create a new OSM element reference
+    pub fn new(osm_type: OsmType, id: u64) -> Self {
+        OsmId { osm_type, id }
+    }
+}
+
+impl Display for OsmId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.osm_type.prefix(), self.id)
+    }
 }
 
 /// Address details in the result object
@@ -334,28 +1453,163 @@ pub struct AddressDetails {
     pub suburb: Option<String>,
     pub road: Option<String>,
     pub village: Option<String>,
+    pub town: Option<String>,
+    pub municipality: Option<String>,
+    pub hamlet: Option<String>,
+    pub isolated_dwelling: Option<String>,
+    pub borough: Option<String>,
+    pub quarter: Option<String>,
+    pub county: Option<String>,
+    pub state_district: Option<String>,
+    pub region: Option<String>,
+    /// Any other address components, keyed as returned by the API (e.g. `ISO3166-2-lvl4`)
+    #[serde(flatten)]
+    pub extra: HashMap<String, String>,
 }
 
 /// A geocoding result geometry
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ResultGeometry<T>
+///
+/// This is the point of the result, unless its outline was requested with the
+/// `polygon_geojson` option.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "GeoJsonGeometry<T>", into = "GeoJsonGeometry<T>")]
+pub struct ResultGeometry<T>(pub Geometry<T>)
+where
+    T: Float + Debug;
+
+impl<T> ResultGeometry<T>
 where
     T: Float + Debug,
 {
-    pub r#type: String,
-    pub coordinates: (T, T),
+    /// The geometry, if it is a point
+    pub fn to_point(&self) -> Option<Point<T>> {
+        match self.0 {
+            Geometry::Point(point) => Some(point),
+            _ => None,
+        }
+    }
+}
+
+// The GeoJSON representation of a geometry
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum GeoJsonGeometry<T> {
+    Point { coordinates: (T, T) },
+    MultiPoint { coordinates: Vec<(T, T)> },
+    LineString { coordinates: Vec<(T, T)> },
+    MultiLineString { coordinates: Vec<Vec<(T, T)>> },
+    Polygon { coordinates: Vec<Vec<(T, T)>> },
+    MultiPolygon { coordinates: Vec<Vec<Vec<(T, T)>>> },
+    GeometryCollection { geometries: Vec<GeoJsonGeometry<T>> },
+}
+
+impl<T> From<GeoJsonGeometry<T>> for ResultGeometry<T>
+where
+    T: Float + Debug,
+{
+    fn from(geometry: GeoJsonGeometry<T>) -> Self {
+        ResultGeometry(geometry.into())
+    }
+}
+
+impl<T> From<ResultGeometry<T>> for GeoJsonGeometry<T>
+where
+    T: Float + Debug,
+{
+    fn from(geometry: ResultGeometry<T>) -> Self {
+        geometry.0.into()
+    }
+}
+
+impl<T> From<GeoJsonGeometry<T>> for Geometry<T>
+where
+    T: Float + Debug,
+{
+    fn from(geometry: GeoJsonGeometry<T>) -> Self {
+        // the first ring of a GeoJSON polygon is its exterior
+        let polygon = |rings: Vec<Vec<(T, T)>>| {
+            let mut rings = rings.into_iter().map(LineString::from);
+            let exterior = rings.next().unwrap_or_else(|| LineString::new(vec![]));
+            Polygon::new(exterior, rings.collect())
+        };
+        match geometry {
+            GeoJsonGeometry::Point { coordinates } => Geometry::Point(coordinates.into()),
+            GeoJsonGeometry::MultiPoint { coordinates } => Geometry::MultiPoint(coordinates.into()),
+            GeoJsonGeometry::LineString { coordinates } => Geometry::LineString(coordinates.into()),
+            GeoJsonGeometry::MultiLineString { coordinates } => Geometry::MultiLineString(
+                MultiLineString::new(coordinates.into_iter().map(LineString::from).collect()),
+            ),
+            GeoJsonGeometry::Polygon { coordinates } => Geometry::Polygon(polygon(coordinates)),
+            GeoJsonGeometry::MultiPolygon { coordinates } => Geometry::MultiPolygon(
+                MultiPolygon::new(coordinates.into_iter().map(polygon).collect()),
+            ),
+            GeoJsonGeometry::GeometryCollection { geometries } => Geometry::GeometryCollection(
+                GeometryCollection::new_from(geometries.into_iter().map(Geometry::from).collect()),
+            ),
+        }
+    }
+}
+
+impl<T> From<Geometry<T>> for GeoJsonGeometry<T>
+where
+    T: Float + Debug,
+{
+    fn from(geometry: Geometry<T>) -> Self {
+        fn coordinates<T: Float + Debug>(line: &LineString<T>) -> Vec<(T, T)> {
+            line.coords().map(|coord| coord.x_y()).collect()
+        }
+        fn rings<T: Float + Debug>(polygon: &Polygon<T>) -> Vec<Vec<(T, T)>> {
+            std::iter::once(polygon.exterior())
+                .chain(polygon.interiors())
+                .map(coordinates)
+                .collect()
+        }
+        match geometry {
+            Geometry::Point(point) => GeoJsonGeometry::Point {
+                coordinates: point.x_y(),
+            },
+            Geometry::MultiPoint(points) => GeoJsonGeometry::MultiPoint {
+                coordinates: points.iter().map(|point| point.x_y()).collect(),
+            },
+            Geometry::Line(line) => GeoJsonGeometry::LineString {
+                coordinates: vec![line.start.x_y(), line.end.x_y()],
+            },
+            Geometry::LineString(line) => GeoJsonGeometry::LineString {
+                coordinates: coordinates(&line),
+            },
+            Geometry::MultiLineString(lines) => GeoJsonGeometry::MultiLineString {
+                coordinates: lines.iter().map(coordinates).collect(),
+            },
+            Geometry::Polygon(polygon) => GeoJsonGeometry::Polygon {
+                coordinates: rings(&polygon),
+            },
+            Geometry::Rect(rect) => GeoJsonGeometry::Polygon {
+                coordinates: rings(&rect.to_polygon()),
+            },
+            Geometry::Triangle(triangle) => GeoJsonGeometry::Polygon {
+                coordinates: rings(&triangle.to_polygon()),
+            },
+            Geometry::MultiPolygon(polygons) => GeoJsonGeometry::MultiPolygon {
+                coordinates: polygons.iter().map(rings).collect(),
+            },
+            Geometry::GeometryCollection(geometries) => GeoJsonGeometry::GeometryCollection {
+                geometries: geometries.into_iter().map(GeoJsonGeometry::from).collect(),
+            },
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::time::UNIX_EPOCH;
 
     #[test]
     fn new_with_endpoint_forward_test() {
         let osm =
             Openstreetmap::new_with_endpoint("https://nominatim.openstreetmap.org/".to_string());
         let address = "Schwabing, München";
-        let res = osm.forward(&address);
+        let res = osm.forward(address);
         assert_eq!(res.unwrap(), vec![Point::new(11.5884858, 48.1700887)]);
     }
 
@@ -366,7 +1620,7 @@ mod test {
             (-0.13806939125061035, 51.51989264641164),
             (-0.13427138328552246, 51.52319711775629),
         );
-        let params = OpenstreetmapParams::new(&"UCL Centre for Advanced Spatial Analysis")
+        let params = OpenstreetmapParams::new("UCL Centre for Advanced Spatial Analysis")
             .with_addressdetails(true)
             .with_viewbox(&viewbox)
             .build();
@@ -380,10 +1634,403 @@ mod test {
     fn forward_test() {
         let osm = Openstreetmap::new();
         let address = "Schwabing, München";
-        let res = osm.forward(&address);
+        let res = osm.forward(address);
         assert_eq!(res.unwrap(), vec![Point::new(11.5884858, 48.1700887)]);
     }
 
+    #[test]
+    fn lookup_test() {
+        let osm = Openstreetmap::new();
+        let ids = [OsmId::new(OsmType::Way, 355421084)];
+        let res: OpenstreetmapResponse<f64> = osm.lookup(&ids).unwrap();
+        let result = &res.features[0].properties;
+        assert_eq!(result.to_osm_id(), ids[0]);
+        assert_eq!(
+            result.address.as_ref().unwrap().road.as_deref(),
+            Some("Carrer de Calatrava")
+        );
+    }
+
+    #[test]
+    fn params_test() {
+        let params = OpenstreetmapParams::<f64>::new("Köln")
+            .with_accept_language("de,en")
+            .with_countrycodes(&["de", "at"])
+            .with_bounded(true)
+            .with_exclude_place_ids(&[263681481, 330208127])
+            .with_dedupe(false)
+            .with_limit(20)
+            .with_extratags(true)
+            .with_namedetails(true)
+            .with_polygon_geojson(true)
+            .with_polygon_threshold(0.001)
+            .with_layers(&[Layer::Address, Layer::Poi])
+            .with_feature_type(FeatureType::City)
+            .build();
+        assert_eq!(
+            params.as_query(),
+            vec![
+                ("q", "Köln".to_string()),
+                ("addressdetails", "0".to_string()),
+                ("accept-language", "de,en".to_string()),
+                ("countrycodes", "de,at".to_string()),
+                ("bounded", "1".to_string()),
+                ("exclude_place_ids", "263681481,330208127".to_string()),
+                ("dedupe", "0".to_string()),
+                ("limit", "20".to_string()),
+                ("extratags", "1".to_string()),
+                ("namedetails", "1".to_string()),
+                ("polygon_geojson", "1".to_string()),
+                ("polygon_threshold", "0.001".to_string()),
+                ("layer", "address,poi".to_string()),
+                ("featureType", "city".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reverse_params_test() {
+        let params = OpenstreetmapReverseParams::new()
+            .with_extratags(true)
+            .with_zoom(10)
+            .with_accept_language("ca")
+            .build();
+        assert_eq!(
+            params.as_query(),
+            vec![
+                ("addressdetails", "1".to_string()),
+                ("extratags", "1".to_string()),
+                ("zoom", "10".to_string()),
+                ("accept-language", "ca".to_string()),
+            ]
+        );
+        let res: OpenstreetmapResponse<f64> = serde_json::from_str(
+            r#"{
+                "type": "FeatureCollection",
+                "licence": "Data © OpenStreetMap contributors, ODbL 1.0. https://osm.org/copyright",
+                "features": [{
+                    "type": "Feature",
+                    "properties": {
+                        "place_id": 263681481,
+                        "osm_type": "way",
+                        "osm_id": 355421084,
+                        "display_name": "68, Carrer de Calatrava, Barcelona, Spain",
+                        "place_rank": 30,
+                        "category": "building",
+                        "type": "apartments",
+                        "importance": 0.741,
+                        "address": { "house_number": "68", "road": "Carrer de Calatrava" },
+                        "extratags": { "building:levels": "6" },
+                        "namedetails": { "name": "Torre Calatrava", "name:en": "Calatrava Tower" }
+                    },
+                    "bbox": [2.1284918, 41.401227, 2.128952, 41.4015815],
+                    "geometry": { "type": "Point", "coordinates": [2.12872241167437, 41.40140675] }
+                }]
+            }"#,
+        )
+        .unwrap();
+        let properties = &res.features[0].properties;
+        assert_eq!(
+            properties.extratags.as_ref().unwrap()["building:levels"],
+            "6"
+        );
+        assert_eq!(
+            properties.namedetails.as_ref().unwrap()["name:en"],
+            "Calatrava Tower"
+        );
+        assert_eq!(res.features[0].bbox.0, 2.1284918);
+    }
+
+    #[test]
+    fn polygon_geojson_test() {
+        let result: OpenstreetmapResult<f64> = serde_json::from_str(
+            r#"{
+                "type": "Feature",
+                "properties": {
+                    "place_id": 1,
+                    "osm_type": "relation",
+                    "osm_id": 2,
+                    "display_name": "Square",
+                    "place_rank": 16,
+                    "category": "boundary",
+                    "type": "administrative",
+                    "importance": 0.5
+                },
+                "bbox": [0.0, 0.0, 2.0, 2.0],
+                "geometry": {
+                    "type": "Polygon",
+                    "coordinates": [
+                        [[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [0.0, 2.0], [0.0, 0.0]],
+                        [[0.5, 0.5], [1.0, 0.5], [1.0, 1.0], [0.5, 0.5]]
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+        match &result.geometry.0 {
+            Geometry::Polygon(polygon) => {
+                assert_eq!(polygon.exterior().0.len(), 5);
+                assert_eq!(polygon.interiors().len(), 1);
+            }
+            geometry => panic!("expected a polygon, got {:?}", geometry),
+        }
+        assert_eq!(result.geometry.to_point(), None);
+        assert_eq!(result.point(), Point::new(1.0, 1.0));
+        assert_eq!(result.bbox_rect().max().x_y(), (2.0, 2.0));
+        let json = serde_json::to_value(&result.geometry).unwrap();
+        assert_eq!(json["type"], "Polygon");
+        assert_eq!(json["coordinates"][1][2], serde_json::json!([1.0, 1.0]));
+    }
+
+    #[test]
+    fn status_test() {
+        let status: NominatimStatus = serde_json::from_str(
+            r#"{
+                "status": 0,
+                "message": "OK",
+                "data_updated": "2020-05-04T14:47:00+00:00",
+                "software_version": "3.6.0-0",
+                "database_version": "3.6.0-0"
+            }"#,
+        )
+        .unwrap();
+        assert!(status.is_ok());
+        assert_eq!(
+            status.data_updated.as_deref(),
+            Some("2020-05-04T14:47:00+00:00")
+        );
+        let status: NominatimStatus =
+            serde_json::from_str(r#"{"status": 700, "message": "Database connection failed"}"#)
+                .unwrap();
+        assert!(!status.is_ok());
+    }
+
+    #[test]
+    fn jsonv2_test() {
+        let places: Vec<NominatimPlace<f64>> = serde_json::from_str(
+            r#"[{
+                "place_id": 263681481,
+                "licence": "Data © OpenStreetMap contributors, ODbL 1.0. https://osm.org/copyright",
+                "osm_type": "way",
+                "osm_id": 355421084,
+                "lat": "41.40140675",
+                "lon": "2.12872241167437",
+                "category": "building",
+                "type": "apartments",
+                "place_rank": 30,
+                "importance": 0.741,
+                "addresstype": "building",
+                "name": "",
+                "display_name": "68, Carrer de Calatrava, Barcelona, Catalonia, 08017, Spain",
+                "boundingbox": ["41.401227", "41.4015815", "2.1284918", "2.128952"]
+            }]"#,
+        )
+        .unwrap();
+        assert_eq!(places[0].point(), Point::new(2.12872241167437, 41.40140675));
+        assert_eq!(places[0].addresstype.as_deref(), Some("building"));
+        assert_eq!(places[0].boundingbox[3], 2.128952);
+        assert!(places[0].geojson.is_none());
+    }
+
+    #[test]
+    fn user_agent_test() {
+        let osm =
+            Openstreetmap::new_with_user_agent("my-app/1.0").with_referer("https://example.com/");
+        let req = osm.request("search").build().unwrap();
+        assert_eq!(req.headers()[USER_AGENT], "my-app/1.0");
+        assert_eq!(req.headers()[REFERER], "https://example.com/");
+    }
+
+    #[test]
+    fn auth_test() {
+        let osm = Openstreetmap::new_with_endpoint("https://nominatim.example.com/".to_string())
+            .with_basic_auth("user", Some("pass"))
+            .with_header("X-Api-Key", "secret");
+        let req = osm.request("search").build().unwrap();
+        assert_eq!(req.headers()["authorization"], "Basic dXNlcjpwYXNz");
+        assert_eq!(req.headers()["x-api-key"], "secret");
+        let osm = Openstreetmap::new().with_header("Invalid Name", "value");
+        assert!(osm.request("search").build().is_err());
+    }
+
+    #[test]
+    fn throttling_test() {
+        let osm = Openstreetmap::new().with_throttling();
+        let start = Instant::now();
+        osm.throttle();
+        osm.throttle();
+        assert!(start.elapsed() >= Openstreetmap::INFO.rate_limit.unwrap().min_interval());
+    }
+
+    #[test]
+    fn status_error_test() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("30"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(30)));
+        let err = status_error(StatusCode::TOO_MANY_REQUESTS, &headers);
+        assert!(matches!(
+            err,
+            Some(GeocodingError::RateLimited { retry_after, .. })
+                if retry_after == Some(Duration::from_secs(30))
+        ));
+
+        // an HTTP date is relative to the current time, and may have passed already
+        let date = "Wed, 21 Oct 2015 07:28:00 GMT";
+        let now = UNIX_EPOCH + Duration::from_secs(1_445_412_450);
+        assert_eq!(parse_retry_after(date, now), Some(Duration::from_secs(30)));
+        assert_eq!(
+            parse_retry_after(date, SystemTime::now()),
+            Some(Duration::from_secs(0))
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+
+        headers.remove(RETRY_AFTER);
+        let err = status_error(StatusCode::TOO_MANY_REQUESTS, &headers);
+        assert!(matches!(
+            err,
+            Some(GeocodingError::RateLimited {
+                retry_after: None,
+                ..
+            })
+        ));
+        let err = status_error(StatusCode::FORBIDDEN, &headers);
+        assert!(matches!(err, Some(GeocodingError::Forbidden(_))));
+        assert!(status_error(StatusCode::OK, &headers).is_none());
+    }
+
+    #[test]
+    fn category_test() {
+        let category: Category = serde_json::from_str(r#""man_made""#).unwrap();
+        assert_eq!(category, Category::ManMade);
+        let place_type: PlaceType = serde_json::from_str(r#""bus_stop""#).unwrap();
+        assert_eq!(place_type, PlaceType::Other("bus_stop".to_string()));
+        assert_eq!(place_type.as_str(), "bus_stop");
+        assert_eq!(
+            serde_json::to_string(&PlaceType::Village).unwrap(),
+            r#""village""#
+        );
+    }
+
+    #[test]
+    fn osm_id_test() {
+        let osm_id = OsmId::new(OsmType::Way, 355421084);
+        assert_eq!(osm_id.to_string(), "W355421084");
+        let properties: ResultProperties = serde_json::from_str(
+            r#"{
+                "place_id": 263681481,
+                "osm_type": "way",
+                "osm_id": 355421084,
+                "display_name": "68, Carrer de Calatrava, Barcelona, Spain",
+                "place_rank": 30,
+                "category": "building",
+                "type": "apartments",
+                "importance": 0.741
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(properties.osm_type, OsmType::Way);
+        assert_eq!(properties.to_osm_id(), osm_id);
+        let osm_type: OsmType = serde_json::from_str(r#""R""#).unwrap();
+        assert_eq!(osm_type, OsmType::Relation);
+    }
+
+    #[test]
+    fn sort_by_test() {
+        let result = |id: u64, importance: f64, place_rank: u64, lon: f64, lat: f64| {
+            serde_json::from_value::<OpenstreetmapResult<f64>>(serde_json::json!({
+                "type": "Feature",
+                "properties": {
+                    "place_id": id,
+                    "osm_type": "node",
+                    "osm_id": id,
+                    "display_name": "",
+                    "place_rank": place_rank,
+                    "category": "place",
+                    "type": "city",
+                    "importance": importance
+                },
+                "bbox": [lon, lat, lon, lat],
+                "geometry": { "type": "Point", "coordinates": [lon, lat] }
+            }))
+            .unwrap()
+        };
+        let ids = |results: &[OpenstreetmapResult<f64>]| {
+            results
+                .iter()
+                .map(|result| result.properties.place_id)
+                .collect::<Vec<_>>()
+        };
+        let mut results = vec![
+            result(1, 0.5, 16, 2.0, 2.0),
+            result(2, 0.9, 30, 0.5, 0.5),
+            result(3, 0.5, 30, 1.2, 1.2),
+        ];
+        let viewbox = InputBounds::new((0.0, 0.0), (2.0, 2.0));
+        let mut params = OpenstreetmapParams::new("query");
+        params.with_viewbox(&viewbox);
+
+        params.with_sort_by(SortBy::Importance).sort(&mut results);
+        assert_eq!(ids(&results), vec![2, 1, 3]);
+        params.with_sort_by(SortBy::PlaceRank).sort(&mut results);
+        assert_eq!(ids(&results), vec![2, 3, 1]);
+        params
+            .with_sort_by(SortBy::DistanceToViewboxCenter)
+            .sort(&mut results);
+        assert_eq!(ids(&results), vec![3, 2, 1]);
+
+        // strict bounds apply to the result that is first once sorted
+        let results = vec![result(1, 0.5, 30, 1.0, 1.0), result(2, 0.9, 30, 5.0, 5.0)];
+        params.with_sort_by(SortBy::Importance);
+        params.with_strict_bounds(StrictBounds::Error);
+        assert!(matches!(
+            params.order_results(results, OpenstreetmapResult::point),
+            Err(GeocodingError::OutOfBounds)
+        ));
+        let results = vec![result(1, 0.9, 30, 1.0, 1.0), result(2, 0.5, 30, 5.0, 5.0)];
+        let ordered = params
+            .order_results(results, OpenstreetmapResult::point)
+            .unwrap();
+        assert_eq!(ids(&ordered), vec![1, 2]);
+    }
+
+    #[test]
+    fn match_quality_test() {
+        let interpolated: ResultProperties = serde_json::from_str(
+            r#"{
+                "place_id": 330208127,
+                "osm_type": "way",
+                "osm_id": 37893374,
+                "display_name": "25, Lindenstraße, Berlin, 10969, Deutschland",
+                "place_rank": 30,
+                "category": "place",
+                "type": "house",
+                "importance": 0.0,
+                "address": { "house_number": "25", "road": "Lindenstraße" }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            interpolated.match_quality(),
+            MatchQuality::InterpolatedHouseNumber
+        );
+
+        let exact: ResultProperties = serde_json::from_str(
+            r#"{
+                "place_id": 263681481,
+                "osm_type": "way",
+                "osm_id": 355421084,
+                "display_name": "68, Carrer de Calatrava, Barcelona, Spain",
+                "place_rank": 30,
+                "category": "building",
+                "type": "apartments",
+                "importance": 0.741,
+                "address": { "house_number": "68", "road": "Carrer de Calatrava" }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(exact.match_quality(), MatchQuality::ExactHouseNumber);
+    }
+
     #[test]
     fn reverse_test() {
         let osm = Openstreetmap::new();
@@ -0,0 +1,618 @@
+//! The Ordnance Survey [OS Places API](https://osdatahub.os.uk/docs/places/overview) provider.
+//!
+//! Geocoding methods are implemented on the [`OsPlaces`](struct.OsPlaces.html) struct. OS Places
+//! matches addresses in Great Britain against AddressBase, and every result carries its Unique
+//! Property Reference Number (UPRN). An OS Data Hub API key is required. Please see the
+//! [API documentation](https://osdatahub.os.uk/docs/places/technicalSpecification) for details.
+//!
+//! Results are records from one or both of the datasets served by the API: Royal Mail
+//! Delivery Point Addresses ([`Dpa`](struct.Dpa.html)), and Local Property Identifiers
+//! ([`Lpi`](struct.Lpi.html)) from local authority gazetteers. Coordinates are requested
+//! in WGS84, and the British National Grid easting and northing are also returned.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, OsPlaces, Point};
+//!
+//! let os = OsPlaces::new("your-api-key".to_string());
+//! let address = "Ordnance Survey, Adanac Drive, Southampton";
+//! let res: Result<Vec<Point<f64>>, _> = os.forward(address);
+//! ```
+use crate::info::{ProviderInfo, RateLimit};
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+use std::time::Duration;
+
+/// An instance of the OS Places geocoding service
+pub struct OsPlaces {
+    api_key: String,
+    client: Client,
+    endpoint: String,
+}
+
+/// The datasets which may be searched
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OsDataset {
+    /// Royal Mail Delivery Point Addresses, the default
+    Dpa,
+    /// Local Property Identifiers, from local authority gazetteers
+    Lpi,
+    /// Both datasets
+    Both,
+}
+
+impl OsDataset {
+    fn as_str(self) -> &'static str {
+        match self {
+            OsDataset::Dpa => "DPA",
+            OsDataset::Lpi => "LPI",
+            OsDataset::Both => "DPA,LPI",
+        }
+    }
+}
+
+/// An instance of a parameter builder for OS Places requests
+///
+/// The parameters are shared by every endpoint, except `minmatch` which only applies to
+/// [`find`](struct.OsPlaces.html#method.find).
+#[derive(Debug, Default)]
+pub struct OsPlacesParams {
+    dataset: Option<OsDataset>,
+    max_results: Option<u8>,
+    offset: Option<u32>,
+    min_match: Option<f64>,
+    welsh: Option<bool>,
+}
+
+impl OsPlacesParams {
+    /// Create a new OS Places parameter builder
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::osplaces::{OsDataset, OsPlacesParams};
+    ///
+    /// let params = OsPlacesParams::new()
+    ///     .with_dataset(OsDataset::Both)
+    ///     .with_max_results(10)
+    ///     .with_min_match(0.8)
+    ///     .build();
+    /// ```
+    pub fn new() -> OsPlacesParams {
+        OsPlacesParams::default()
+    }
+
+    /// Set the `dataset` property
+    pub fn with_dataset(&mut self, dataset: OsDataset) -> &mut Self {
+        self.dataset = Some(dataset);
+        self
+    }
+
+    /// Set the `maxresults` property, the number of results per page (100 by default, at most 100)
+    pub fn with_max_results(&mut self, max_results: u8) -> &mut Self {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    /// Set the `offset` property, the index of the first result to return
+    pub fn with_offset(&mut self, offset: u32) -> &mut Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Set the `minmatch` property, the minimum match score from 0.1 to 1
+    pub fn with_min_match(&mut self, min_match: f64) -> &mut Self {
+        self.min_match = Some(min_match);
+        self
+    }
+
+    /// Set the `lr` property, returning Welsh rather than English addresses where available
+    pub fn with_welsh(&mut self, welsh: bool) -> &mut Self {
+        self.welsh = Some(welsh);
+        self
+    }
+
+    /// Build and return an instance of OsPlacesParams
+    pub fn build(&self) -> OsPlacesParams {
+        OsPlacesParams {
+            dataset: self.dataset,
+            max_results: self.max_results,
+            offset: self.offset,
+            min_match: self.min_match,
+            welsh: self.welsh,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![];
+        if let Some(dataset) = self.dataset {
+            query.push(("dataset", dataset.as_str().to_string()));
+        }
+        if let Some(max_results) = self.max_results {
+            query.push(("maxresults", max_results.to_string()));
+        }
+        if let Some(offset) = self.offset {
+            query.push(("offset", offset.to_string()));
+        }
+        if let Some(welsh) = self.welsh {
+            query.push(("lr", String::from(if welsh { "CY" } else { "EN" })));
+        }
+        query
+    }
+}
+
+impl OsPlaces {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "OS Places API",
+        docs_url: "https://osdatahub.os.uk/docs/places/technicalSpecification",
+        terms_url: Some("https://osdatahub.os.uk/legal/termsOfUse"),
+        attribution: Some("Contains OS data © Crown copyright and database rights"),
+        rate_limit: Some(RateLimit {
+            requests: 600,
+            per: Duration::from_secs(60),
+        }),
+    };
+
+    /// Create a new OS Places geocoding instance using the default endpoint
+    pub fn new(api_key: String) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        OsPlaces {
+            api_key,
+            client,
+            endpoint: "https://api.os.uk/search/places/v1/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of an OS Places geocoding instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://api.os.uk/search/places/v1/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A free-text search for addresses
+    ///
+    /// Please see [the documentation](https://osdatahub.os.uk/docs/places/technicalSpecification) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::OsPlaces;
+    /// use geocoding::osplaces::{OsPlacesParams, OsPlacesResponse};
+    ///
+    /// let os = OsPlaces::new("your-api-key".to_string());
+    /// let params = OsPlacesParams::new().with_max_results(1).build();
+    /// let res: Result<OsPlacesResponse<f64>, _> =
+    ///     os.find("Ordnance Survey, Adanac Drive, SO16", &params);
+    /// ```
+    pub fn find<T>(
+        &self,
+        query: &str,
+        params: &OsPlacesParams,
+    ) -> Result<OsPlacesResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = vec![("query", query.to_string())];
+        query.extend(params.as_query());
+        if let Some(min_match) = params.min_match {
+            query.push(("minmatch", min_match.to_string()));
+        }
+        self.get("find", query)
+    }
+
+    /// Look up every address with a full or partial postcode
+    ///
+    /// Please see [the documentation](https://osdatahub.os.uk/docs/places/technicalSpecification) for details.
+    pub fn postcode<T>(
+        &self,
+        postcode: &str,
+        params: &OsPlacesParams,
+    ) -> Result<OsPlacesResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = vec![("postcode", postcode.to_string())];
+        query.extend(params.as_query());
+        self.get("postcode", query)
+    }
+
+    /// Look up the addresses of a Unique Property Reference Number
+    ///
+    /// Please see [the documentation](https://osdatahub.os.uk/docs/places/technicalSpecification) for details.
+    pub fn uprn<T>(
+        &self,
+        uprn: u64,
+        params: &OsPlacesParams,
+    ) -> Result<OsPlacesResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = vec![("uprn", uprn.to_string())];
+        query.extend(params.as_query());
+        self.get("uprn", query)
+    }
+
+    /// Look up the address closest to a point, within 1 km
+    ///
+    /// Please see [the documentation](https://osdatahub.os.uk/docs/places/technicalSpecification) for details.
+    pub fn nearest<T>(
+        &self,
+        point: &Point<T>,
+        params: &OsPlacesParams,
+    ) -> Result<OsPlacesResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        // WGS84 points are given in lat, lon order
+        let mut query = vec![
+            (
+                "point",
+                format!(
+                    "{},{}",
+                    point.y().to_f64().unwrap(),
+                    point.x().to_f64().unwrap()
+                ),
+            ),
+            ("srs", String::from("WGS84")),
+        ];
+        query.extend(params.as_query());
+        self.get("nearest", query)
+    }
+
+    fn get<R>(
+        &self,
+        path: &str,
+        mut query: Vec<(&'static str, String)>,
+    ) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        query.push(("output_srs", String::from("WGS84")));
+        query.push(("key", self.api_key.clone()));
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl<T> Forward<T> for OsPlaces
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A free-text search for addresses.
+    /// Please see [the documentation](https://osdatahub.os.uk/docs/places/technicalSpecification) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.find(place, &OsPlacesParams::new())?;
+        Ok(res.results.iter().map(OsPlacesResult::point).collect())
+    }
+}
+
+impl<T> Reverse<T> for OsPlaces
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the `ADDRESS` of the nearest record.
+    /// Please see [the documentation](https://osdatahub.os.uk/docs/places/technicalSpecification) for details.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.nearest(point, &OsPlacesParams::new())?;
+        Ok(res
+            .results
+            .into_iter()
+            .next()
+            .map(|result| result.address().to_string()))
+    }
+}
+
+/// The top-level full JSON response returned by an OS Places request
+///
+/// See [the documentation](https://osdatahub.os.uk/docs/places/technicalSpecification) for more details
+///
+///```json
+///{
+///  "header": {
+///    "uri": "https://api.os.uk/search/places/v1/uprn?uprn=200010019924&output_srs=WGS84",
+///    "query": "uprn=200010019924",
+///    "offset": 0,
+///    "totalresults": 1,
+///    "format": "JSON",
+///    "dataset": "DPA",
+///    "lr": "EN,CY",
+///    "maxresults": 100,
+///    "epoch": "104",
+///    "output_srs": "WGS84"
+///  },
+///  "results": [
+///    {
+///      "DPA": {
+///        "UPRN": "200010019924",
+///        "UDPRN": "52126563",
+///        "ADDRESS": "ORDNANCE SURVEY, 4, ADANAC DRIVE, NURSLING, SOUTHAMPTON, SO16 0AS",
+///        "ORGANISATION_NAME": "ORDNANCE SURVEY",
+///        "BUILDING_NUMBER": "4",
+///        "THOROUGHFARE_NAME": "ADANAC DRIVE",
+///        "DEPENDENT_LOCALITY": "NURSLING",
+///        "POST_TOWN": "SOUTHAMPTON",
+///        "POSTCODE": "SO16 0AS",
+///        "RPC": "2",
+///        "X_COORDINATE": 437292.43,
+///        "Y_COORDINATE": 115541.95,
+///        "LNG": -1.4729026,
+///        "LAT": 50.9378997,
+///        "STATUS": "APPROVED",
+///        "LOGICAL_STATUS_CODE": "1",
+///        "CLASSIFICATION_CODE": "CO01GV",
+///        "CLASSIFICATION_CODE_DESCRIPTION": "Central Government Service",
+///        "LOCAL_CUSTODIAN_CODE": 1760,
+///        "LOCAL_CUSTODIAN_CODE_DESCRIPTION": "TEST VALLEY",
+///        "COUNTRY_CODE": "E",
+///        "COUNTRY_CODE_DESCRIPTION": "This record is within England",
+///        "POSTAL_ADDRESS_CODE": "D",
+///        "POSTAL_ADDRESS_CODE_DESCRIPTION": "A record which is linked to PAF",
+///        "MATCH": 1.0,
+///        "MATCH_DESCRIPTION": "EXACT",
+///        "LANGUAGE": "EN"
+///      }
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OsPlacesResponse<T>
+where
+    T: Float + Debug,
+{
+    pub header: Header,
+    /// The results, absent from the JSON if there are none
+    #[serde(default = "Vec::new")]
+    pub results: Vec<OsPlacesResult<T>>,
+}
+
+/// Metadata about a request
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Header {
+    pub uri: String,
+    pub query: String,
+    pub offset: u32,
+    /// The total number of results, across all pages
+    #[serde(rename = "totalresults")]
+    pub total_results: u32,
+    pub dataset: String,
+    #[serde(rename = "maxresults")]
+    pub max_results: u32,
+    /// The AddressBase release the results come from
+    pub epoch: String,
+}
+
+/// An address record
+#[derive(Debug, Serialize, Deserialize)]
+pub enum OsPlacesResult<T>
+where
+    T: Float + Debug,
+{
+    #[serde(rename = "DPA")]
+    Dpa(Dpa<T>),
+    #[serde(rename = "LPI")]
+    Lpi(Lpi<T>),
+}
+
+impl<T> OsPlacesResult<T>
+where
+    T: Float + Debug,
+{
+    /// The Unique Property Reference Number of the record
+    pub fn uprn(&self) -> &str {
+        match self {
+            OsPlacesResult::Dpa(dpa) => &dpa.uprn,
+            OsPlacesResult::Lpi(lpi) => &lpi.uprn,
+        }
+    }
+
+    /// The single-line address of the record
+    pub fn address(&self) -> &str {
+        match self {
+            OsPlacesResult::Dpa(dpa) => &dpa.address,
+            OsPlacesResult::Lpi(lpi) => &lpi.address,
+        }
+    }
+
+    /// The WGS84 location of the record
+    pub fn point(&self) -> Point<T> {
+        match self {
+            OsPlacesResult::Dpa(dpa) => Point::new(dpa.lng, dpa.lat),
+            OsPlacesResult::Lpi(lpi) => Point::new(lpi.lng, lpi.lat),
+        }
+    }
+}
+
+/// A Royal Mail Delivery Point Address
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct Dpa<T>
+where
+    T: Float + Debug,
+{
+    pub uprn: String,
+    /// The Royal Mail Unique Delivery Point Reference Number
+    pub udprn: String,
+    pub address: String,
+    pub organisation_name: Option<String>,
+    pub department_name: Option<String>,
+    pub sub_building_name: Option<String>,
+    pub building_name: Option<String>,
+    pub building_number: Option<String>,
+    pub dependent_thoroughfare_name: Option<String>,
+    pub thoroughfare_name: Option<String>,
+    pub double_dependent_locality: Option<String>,
+    pub dependent_locality: Option<String>,
+    pub post_town: String,
+    pub postcode: String,
+    /// The Representative Point Code, from 1 (most accurate) to 9
+    pub rpc: String,
+    /// The British National Grid easting
+    pub x_coordinate: f64,
+    /// The British National Grid northing
+    pub y_coordinate: f64,
+    pub lng: T,
+    pub lat: T,
+    pub status: String,
+    pub logical_status_code: String,
+    pub classification_code: String,
+    pub classification_code_description: String,
+    pub local_custodian_code: u32,
+    pub local_custodian_code_description: String,
+    pub country_code: String,
+    pub postal_address_code: String,
+    /// The match score, from 0 to 1, for `find` requests
+    #[serde(rename = "MATCH")]
+    pub match_score: f64,
+    pub match_description: String,
+    pub language: String,
+}
+
+/// A Local Property Identifier
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct Lpi<T>
+where
+    T: Float + Debug,
+{
+    pub uprn: String,
+    pub address: String,
+    /// The Unique Street Reference Number
+    pub usrn: String,
+    pub lpi_key: String,
+    pub organisation: Option<String>,
+    pub sao_text: Option<String>,
+    pub pao_start_number: Option<String>,
+    pub pao_text: Option<String>,
+    pub street_description: String,
+    pub locality_name: Option<String>,
+    pub town_name: Option<String>,
+    pub administrative_area: String,
+    pub postcode_locator: Option<String>,
+    pub rpc: String,
+    /// The British National Grid easting
+    pub x_coordinate: f64,
+    /// The British National Grid northing
+    pub y_coordinate: f64,
+    pub lng: T,
+    pub lat: T,
+    pub status: String,
+    pub logical_status_code: String,
+    pub classification_code: String,
+    pub classification_code_description: String,
+    pub local_custodian_code: u32,
+    pub local_custodian_code_description: String,
+    pub country_code: String,
+    /// The match score, from 0 to 1, for `find` requests
+    #[serde(rename = "MATCH")]
+    pub match_score: f64,
+    pub match_description: String,
+    pub language: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn records_test() {
+        let res: OsPlacesResponse<f64> = serde_json::from_str(
+            r#"{
+                "header": {
+                    "uri": "https://api.os.uk/search/places/v1/uprn?uprn=200010019924&dataset=DPA%2CLPI",
+                    "query": "uprn=200010019924", "offset": 0, "totalresults": 2,
+                    "format": "JSON", "dataset": "DPA,LPI", "lr": "EN,CY",
+                    "maxresults": 100, "epoch": "104", "output_srs": "WGS84"
+                },
+                "results": [
+                    {
+                        "DPA": {
+                            "UPRN": "200010019924", "UDPRN": "52126563",
+                            "ADDRESS": "ORDNANCE SURVEY, 4, ADANAC DRIVE, NURSLING, SOUTHAMPTON, SO16 0AS",
+                            "ORGANISATION_NAME": "ORDNANCE SURVEY", "BUILDING_NUMBER": "4",
+                            "THOROUGHFARE_NAME": "ADANAC DRIVE", "DEPENDENT_LOCALITY": "NURSLING",
+                            "POST_TOWN": "SOUTHAMPTON", "POSTCODE": "SO16 0AS", "RPC": "2",
+                            "X_COORDINATE": 437292.43, "Y_COORDINATE": 115541.95,
+                            "LNG": -1.4729026, "LAT": 50.9378997,
+                            "STATUS": "APPROVED", "LOGICAL_STATUS_CODE": "1",
+                            "CLASSIFICATION_CODE": "CO01GV",
+                            "CLASSIFICATION_CODE_DESCRIPTION": "Central Government Service",
+                            "LOCAL_CUSTODIAN_CODE": 1760,
+                            "LOCAL_CUSTODIAN_CODE_DESCRIPTION": "TEST VALLEY",
+                            "COUNTRY_CODE": "E", "POSTAL_ADDRESS_CODE": "D",
+                            "MATCH": 1.0, "MATCH_DESCRIPTION": "EXACT", "LANGUAGE": "EN"
+                        }
+                    },
+                    {
+                        "LPI": {
+                            "UPRN": "200010019924",
+                            "ADDRESS": "ORDNANCE SURVEY, 4, ADANAC DRIVE, NURSLING, TEST VALLEY, SO16 0AS",
+                            "USRN": "20701419", "LPI_KEY": "1760L000183013",
+                            "ORGANISATION": "ORDNANCE SURVEY", "PAO_START_NUMBER": "4",
+                            "STREET_DESCRIPTION": "ADANAC DRIVE", "LOCALITY_NAME": "NURSLING",
+                            "TOWN_NAME": "ROWNHAMS", "ADMINISTRATIVE_AREA": "TEST VALLEY",
+                            "POSTCODE_LOCATOR": "SO16 0AS", "RPC": "2",
+                            "X_COORDINATE": 437292.43, "Y_COORDINATE": 115541.95,
+                            "LNG": -1.4729026, "LAT": 50.9378997,
+                            "STATUS": "APPROVED", "LOGICAL_STATUS_CODE": "1",
+                            "CLASSIFICATION_CODE": "CO01GV",
+                            "CLASSIFICATION_CODE_DESCRIPTION": "Central Government Service",
+                            "LOCAL_CUSTODIAN_CODE": 1760,
+                            "LOCAL_CUSTODIAN_CODE_DESCRIPTION": "TEST VALLEY",
+                            "COUNTRY_CODE": "E",
+                            "MATCH": 1.0, "MATCH_DESCRIPTION": "EXACT", "LANGUAGE": "EN"
+                        }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(res.header.total_results, 2);
+        assert!(matches!(res.results[0], OsPlacesResult::Dpa(_)));
+        assert!(matches!(res.results[1], OsPlacesResult::Lpi(_)));
+        assert_eq!(res.results[1].uprn(), "200010019924");
+        assert_eq!(res.results[0].point(), Point::new(-1.4729026, 50.9378997));
+    }
+
+    #[test]
+    fn no_results_test() {
+        let res: OsPlacesResponse<f64> = serde_json::from_str(
+            r#"{
+                "header": {
+                    "uri": "https://api.os.uk/search/places/v1/postcode?postcode=ZZ99%209ZZ",
+                    "query": "postcode=ZZ99 9ZZ", "offset": 0, "totalresults": 0,
+                    "format": "JSON", "dataset": "DPA", "lr": "EN,CY",
+                    "maxresults": 100, "epoch": "104", "output_srs": "WGS84"
+                }
+            }"#,
+        )
+        .unwrap();
+        assert!(res.results.is_empty());
+    }
+}
@@ -0,0 +1,456 @@
+//! The [Overpass API](https://wiki.openstreetmap.org/wiki/Overpass_API) provider.
+//!
+//! Geocoding methods are implemented on the [`Overpass`](struct.Overpass.html) struct, which
+//! finds points of interest by querying OpenStreetMap tags directly. Nominatim only indexes
+//! features it considers addressable, and often misses obscure POIs which a tag query finds.
+//! Queries are built from an [`OverpassParams`](struct.OverpassParams.html) name and tag
+//! filters, and written in [Overpass QL](https://wiki.openstreetmap.org/wiki/Overpass_API/Overpass_QL).
+//!
+//! The public instances are shared, and only allow a couple of concurrent queries per client.
+//! Name searches without a bounding box scan the whole planet, and are likely to time out.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, Overpass, Point};
+//!
+//! let overpass = Overpass::new();
+//! let res: Result<Vec<Point<f64>>, _> = overpass.forward("Café Sperl");
+//! ```
+use crate::info::ProviderInfo;
+use crate::Deserialize;
+use crate::Forward;
+use crate::GeocodingError;
+use crate::InputBounds;
+use crate::Point;
+use crate::Serialize;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use num_traits::Float;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// An instance of an Overpass API service
+pub struct Overpass {
+    client: Client,
+    endpoint: String,
+}
+
+/// An instance of a parameter builder for Overpass POI queries
+pub struct OverpassParams<'a, T>
+where
+    T: Float + Debug,
+{
+    name: &'a str,
+    exact: bool,
+    tags: Vec<(&'a str, &'a str)>,
+    bbox: Option<&'a InputBounds<T>>,
+    limit: Option<u16>,
+    timeout: Option<u16>,
+}
+
+impl<'a, T> OverpassParams<'a, T>
+where
+    T: Float + Debug,
+{
+    /// Create a new Overpass parameter builder, searching elements whose `name` contains `name`,
+    /// ignoring case
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::InputBounds;
+    /// use geocoding::overpass::OverpassParams;
+    ///
+    /// let bbox = InputBounds::new((16.35, 48.19), (16.37, 48.21));
+    /// let params = OverpassParams::new("Sperl")
+    ///     .with_tag("amenity", "cafe")
+    ///     .with_bbox(&bbox)
+    ///     .build();
+    /// assert_eq!(
+    ///     params.to_ql(),
+    ///     r#"[out:json][timeout:25];nwr["amenity"="cafe"]["name"~"Sperl",i](48.19,16.35,48.21,16.37);out center tags 10;"#
+    /// );
+    /// ```
+    pub fn new(name: &'a str) -> OverpassParams<'a, T> {
+        OverpassParams {
+            name,
+            exact: false,
+            tags: vec![],
+            bbox: None,
+            limit: None,
+            timeout: None,
+        }
+    }
+
+    /// Only match elements whose `name` is exactly `name`
+    ///
+    /// Exact matches use the tag index, and are much faster than the default substring search.
+    pub fn with_exact_name(&mut self) -> &mut Self {
+        self.exact = true;
+        self
+    }
+
+    /// Add a tag filter, such as `amenity=cafe`
+    pub fn with_tag(&mut self, key: &'a str, value: &'a str) -> &mut Self {
+        self.tags.push((key, value));
+        self
+    }
+
+    /// Restrict results to a bounding box
+    pub fn with_bbox(&mut self, bbox: &'a InputBounds<T>) -> &mut Self {
+        self.bbox = Some(bbox);
+        self
+    }
+
+    /// Set the maximum number of elements to return (default: 10)
+    pub fn with_limit(&mut self, limit: u16) -> &mut Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Set the query timeout, in seconds (default: 25)
+    pub fn with_timeout(&mut self, timeout: u16) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Build and return an instance of OverpassParams
+    pub fn build(&self) -> OverpassParams<'a, T> {
+        OverpassParams {
+            name: self.name,
+            exact: self.exact,
+            tags: self.tags.clone(),
+            bbox: self.bbox,
+            limit: self.limit,
+            timeout: self.timeout,
+        }
+    }
+
+    /// The Overpass QL query
+    pub fn to_ql(&self) -> String {
+        let mut filters: String = self
+            .tags
+            .iter()
+            .map(|(key, value)| format!(r#"["{}"="{}"]"#, escape(key), escape(value)))
+            .collect();
+        if self.exact {
+            filters.push_str(&format!(r#"["name"="{}"]"#, escape(self.name)));
+        } else {
+            filters.push_str(&format!(
+                r#"["name"~"{}",i]"#,
+                escape(&escape_regex(self.name))
+            ));
+        }
+        if let Some(bbox) = self.bbox {
+            filters.push_str(&format!(
+                "({},{},{},{})",
+                bbox.minimum_lonlat.y().to_f64().unwrap(),
+                bbox.minimum_lonlat.x().to_f64().unwrap(),
+                bbox.maximum_lonlat.y().to_f64().unwrap(),
+                bbox.maximum_lonlat.x().to_f64().unwrap()
+            ));
+        }
+        format!(
+            "[out:json][timeout:{}];nwr{};out center tags {};",
+            self.timeout.unwrap_or(25),
+            filters,
+            self.limit.unwrap_or(10)
+        )
+    }
+}
+
+// Escape a string literal of Overpass QL
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// Escape the metacharacters of a POSIX extended regular expression
+fn escape_regex(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if "\\^$.|?*+()[]{}".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+impl Overpass {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "Overpass API",
+        docs_url: "https://wiki.openstreetmap.org/wiki/Overpass_API",
+        terms_url: Some("https://dev.overpass-api.de/overpass-doc/en/preface/commons.html"),
+        attribution: Some("© OpenStreetMap contributors"),
+        rate_limit: None,
+    };
+
+    /// Create a new Overpass instance, using the main public instance
+    pub fn new() -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Overpass {
+            client,
+            endpoint: "https://overpass-api.de/api/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of an Overpass instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://overpass.kumi.systems/api/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A search of named POIs, returning a full detailed response
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::{InputBounds, Overpass};
+    /// use geocoding::overpass::{OverpassParams, OverpassResponse};
+    ///
+    /// let overpass = Overpass::new();
+    /// let bbox = InputBounds::new((16.35, 48.19), (16.37, 48.21));
+    /// let params = OverpassParams::new("Sperl")
+    ///     .with_tag("amenity", "cafe")
+    ///     .with_bbox(&bbox)
+    ///     .build();
+    /// let res: Result<OverpassResponse<f64>, _> = overpass.forward_full(&params);
+    /// ```
+    pub fn forward_full<T>(
+        &self,
+        params: &OverpassParams<T>,
+    ) -> Result<OverpassResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.query(&params.to_ql())
+    }
+
+    /// Run an Overpass QL query, which must request JSON output
+    pub fn query<T>(&self, ql: &str) -> Result<OverpassResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let resp = self
+            .client
+            .post(format!("{}interpreter", self.endpoint))
+            .form(&[("data", ql)])
+            .send()?
+            .error_for_status()?;
+        let res: OverpassResponse<T> = resp.json()?;
+        // Timeouts and memory exhaustion are reported with a 200 status
+        if res.is_error() {
+            return Err(GeocodingError::Forward);
+        }
+        Ok(res)
+    }
+}
+
+impl Default for Overpass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Forward<T> for Overpass
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A search of POIs named exactly `place`, anywhere on the planet.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(&OverpassParams::new(place).with_exact_name().build())?;
+        Ok(res
+            .elements
+            .iter()
+            .filter_map(OverpassElement::point)
+            .collect())
+    }
+}
+
+/// The top-level full JSON response returned by an Overpass query
+///
+///```json
+///{
+///  "version": 0.6,
+///  "generator": "Overpass API 0.7.62.1 084b4234",
+///  "osm3s": {
+///    "timestamp_osm_base": "2024-05-02T12:34:56Z",
+///    "copyright": "The data included in this document is from www.openstreetmap.org. The data is made available under ODbL."
+///  },
+///  "elements": [
+///    {
+///      "type": "node",
+///      "id": 1099424285,
+///      "lat": 48.1988543,
+///      "lon": 16.3618412,
+///      "tags": {
+///        "amenity": "cafe",
+///        "name": "Café Sperl"
+///      }
+///    },
+///    {
+///      "type": "way",
+///      "id": 26152052,
+///      "center": {
+///        "lat": 48.2081609,
+///        "lon": 16.3694127
+///      },
+///      "tags": {
+///        "amenity": "cafe",
+///        "name": "Café Central"
+///      }
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OverpassResponse<T>
+where
+    T: Float + Debug,
+{
+    #[serde(default = "Vec::new")]
+    pub elements: Vec<OverpassElement<T>>,
+    /// A message from the server, such as a runtime error
+    pub remark: Option<String>,
+}
+
+impl<T> OverpassResponse<T>
+where
+    T: Float + Debug,
+{
+    /// Whether the query failed while running, e.g. by timing out
+    pub fn is_error(&self) -> bool {
+        self.remark
+            .as_deref()
+            .map_or(false, |remark| remark.starts_with("runtime error"))
+    }
+}
+
+/// An OpenStreetMap element
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OverpassElement<T>
+where
+    T: Float + Debug,
+{
+    #[serde(rename = "type")]
+    pub element_type: ElementType,
+    pub id: u64,
+    /// The location of a node
+    pub lat: Option<T>,
+    pub lon: Option<T>,
+    /// The centre of the bounding box of a way or relation
+    pub center: Option<OverpassCenter<T>>,
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+}
+
+impl<T> OverpassElement<T>
+where
+    T: Float + Debug,
+{
+    /// The location of a node, or the centre of a way or relation
+    pub fn point(&self) -> Option<Point<T>> {
+        match (self.lon, self.lat, &self.center) {
+            (Some(lon), Some(lat), _) => Some(Point::new(lon, lat)),
+            (_, _, Some(center)) => Some(Point::new(center.lon, center.lat)),
+            _ => None,
+        }
+    }
+
+    /// The value of a tag
+    pub fn tag(&self, key: &str) -> Option<&str> {
+        self.tags.get(key).map(String::as_str)
+    }
+
+    /// The element's page on openstreetmap.org, e.g. `https://www.openstreetmap.org/node/1099424285`
+    pub fn url(&self) -> String {
+        let element_type = match self.element_type {
+            ElementType::Node => "node",
+            ElementType::Way => "way",
+            ElementType::Relation => "relation",
+        };
+        format!("https://www.openstreetmap.org/{}/{}", element_type, self.id)
+    }
+}
+
+/// The type of an OpenStreetMap element
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElementType {
+    Node,
+    Way,
+    Relation,
+}
+
+/// The centre of a way or relation
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OverpassCenter<T>
+where
+    T: Float + Debug,
+{
+    pub lat: T,
+    pub lon: T,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ql_test() {
+        let params = OverpassParams::<f64>::new(r#"Joe's "Bar" (Old) Town"#)
+            .with_limit(1)
+            .build();
+        assert_eq!(
+            params.to_ql(),
+            r#"[out:json][timeout:25];nwr["name"~"Joe's \"Bar\" \\(Old\\) Town",i];out center tags 1;"#
+        );
+        let params = OverpassParams::<f64>::new("Café Central")
+            .with_exact_name()
+            .with_tag("amenity", "cafe")
+            .with_timeout(60)
+            .build();
+        assert_eq!(
+            params.to_ql(),
+            r#"[out:json][timeout:60];nwr["amenity"="cafe"]["name"="Café Central"];out center tags 10;"#
+        );
+    }
+
+    #[test]
+    fn response_test() {
+        let res: OverpassResponse<f64> = serde_json::from_str(
+            r#"{
+                "version": 0.6,
+                "elements": [
+                    {"type": "node", "id": 1099424285, "lat": 48.1988543, "lon": 16.3618412,
+                     "tags": {"amenity": "cafe", "name": "Café Sperl"}},
+                    {"type": "way", "id": 26152052, "center": {"lat": 48.2081609, "lon": 16.3694127},
+                     "tags": {"name": "Café Central"}}
+                ],
+                "remark": "runtime error: Query timed out in \"query\" at line 1 after 26 seconds."
+            }"#,
+        )
+        .unwrap();
+        assert!(res.is_error());
+        assert_eq!(res.elements[0].tag("amenity"), Some("cafe"));
+        assert_eq!(
+            res.elements[1].point(),
+            Some(Point::new(16.3694127, 48.2081609))
+        );
+        assert_eq!(
+            res.elements[1].url(),
+            "https://www.openstreetmap.org/way/26152052"
+        );
+    }
+}
@@ -0,0 +1,468 @@
+//! The Dutch [PDOK Locatieserver](https://www.pdok.nl/introductie/-/article/pdok-locatieserver-1) provider.
+//!
+//! Geocoding methods are implemented on the [`Pdok`](struct.Pdok.html) struct. The Locatieserver
+//! searches the Dutch registers of addresses and buildings (BAG), and of administrative areas,
+//! roads and cadastral parcels. It is free and requires no key. Please see the
+//! [API documentation](https://github.com/PDOK/locatieserver/wiki/API-Locatieserver) for details.
+//!
+//! Four endpoints are available: `free` searches, `suggest` for type-ahead suggestions, `lookup`
+//! to fetch a full document by the ID of a suggestion, and `reverse`.
+//!
+//! ### A Note on Coordinate Systems
+//! Documents carry their location both in WGS84 and in the Dutch national grid, RD New
+//! ([EPSG:28992](https://epsg.io/28992)), as WKT points. The `Forward` and `Reverse`
+//! implementations use WGS84; RD New points are available through
+//! [`PdokDoc::rd_point`](struct.PdokDoc.html#method.rd_point), and reverse lookups may be made
+//! using RD New coordinates with [`reverse_rd`](struct.Pdok.html#method.reverse_rd).
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, Pdok, Point};
+//!
+//! let pdok = Pdok::new();
+//! let address = "Dam 1, Amsterdam";
+//! let res: Result<Vec<Point<f64>>, _> = pdok.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+
+/// An instance of the PDOK Locatieserver
+pub struct Pdok {
+    client: Client,
+    endpoint: String,
+}
+
+/// The kind of a Locatieserver document
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PdokType {
+    Provincie,
+    Gemeente,
+    Woonplaats,
+    Weg,
+    Postcode,
+    Adres,
+    Perceel,
+    Hectometerpaal,
+    Appartementsrecht,
+    /// A type added after this crate was released
+    #[serde(other)]
+    Other,
+}
+
+/// An instance of a parameter builder for Locatieserver searches
+pub struct PdokParams<'a, T>
+where
+    T: Float + Debug,
+{
+    query: &'a str,
+    rows: Option<u16>,
+    start: Option<u32>,
+    filters: &'a [&'a str],
+    bias: Option<&'a Point<T>>,
+}
+
+impl<'a, T> PdokParams<'a, T>
+where
+    T: Float + Debug,
+{
+    /// Create a new Locatieserver parameter builder
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::pdok::PdokParams;
+    ///
+    /// let params = PdokParams::<f64>::new(&"Dam 1, Amsterdam")
+    ///     .with_filters(&["type:adres"])
+    ///     .with_rows(5)
+    ///     .build();
+    /// ```
+    pub fn new(query: &'a str) -> PdokParams<'a, T> {
+        PdokParams {
+            query,
+            rows: None,
+            start: None,
+            filters: &[],
+            bias: None,
+        }
+    }
+
+    /// Set the `rows` property, the maximum number of results to return (10 by default, at most 100)
+    pub fn with_rows(&mut self, rows: u16) -> &mut Self {
+        self.rows = Some(rows);
+        self
+    }
+
+    /// Set the `start` property, the index of the first result to return
+    pub fn with_start(&mut self, start: u32) -> &mut Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Set the `fq` property, Solr filter queries such as `type:adres` or `gemeentenaam:Utrecht`
+    pub fn with_filters(&mut self, filters: &'a [&'a str]) -> &mut Self {
+        self.filters = filters;
+        self
+    }
+
+    /// Set the `lat` and `lon` properties, favouring results close to a point
+    pub fn with_bias(&mut self, bias: &'a Point<T>) -> &mut Self {
+        self.bias = Some(bias);
+        self
+    }
+
+    /// Build and return an instance of PdokParams
+    pub fn build(&self) -> PdokParams<'a, T> {
+        PdokParams {
+            query: self.query,
+            rows: self.rows,
+            start: self.start,
+            filters: self.filters,
+            bias: self.bias,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("q", self.query.to_string())];
+        if let Some(rows) = self.rows {
+            query.push(("rows", rows.to_string()));
+        }
+        if let Some(start) = self.start {
+            query.push(("start", start.to_string()));
+        }
+        for filter in self.filters {
+            query.push(("fq", filter.to_string()));
+        }
+        if let Some(bias) = self.bias {
+            query.push(("lat", bias.y().to_f64().unwrap().to_string()));
+            query.push(("lon", bias.x().to_f64().unwrap().to_string()));
+        }
+        query
+    }
+}
+
+impl Pdok {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "PDOK Locatieserver",
+        docs_url: "https://github.com/PDOK/locatieserver/wiki/API-Locatieserver",
+        terms_url: Some("https://www.pdok.nl/gebruiksvoorwaarden"),
+        attribution: Some("PDOK, Kadaster"),
+        rate_limit: None,
+    };
+
+    /// Create a new Locatieserver geocoding instance using the default endpoint
+    pub fn new() -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Pdok {
+            client,
+            endpoint: "https://api.pdok.nl/bzk/locatieserver/search/v3_1/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of a Locatieserver geocoding instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://api.pdok.nl/bzk/locatieserver/search/v3_1/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A free search of every document type, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://github.com/PDOK/locatieserver/wiki/API-Locatieserver#31free-service) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Pdok;
+    /// use geocoding::pdok::{PdokParams, PdokResponse};
+    ///
+    /// let pdok = Pdok::new();
+    /// let params = PdokParams::<f64>::new(&"Dam 1, Amsterdam").with_rows(1).build();
+    /// let res: Result<PdokResponse, _> = pdok.forward_full(&params);
+    /// ```
+    pub fn forward_full<T>(&self, params: &PdokParams<T>) -> Result<PdokResponse, GeocodingError>
+    where
+        T: Float + Debug,
+    {
+        self.get("free", params.as_query())
+    }
+
+    /// Type-ahead suggestions, whose documents only include their `id`, `type` and `weergavenaam`
+    ///
+    /// Please see [the documentation](https://github.com/PDOK/locatieserver/wiki/API-Locatieserver#32suggest-service) for details.
+    pub fn suggest<T>(&self, params: &PdokParams<T>) -> Result<PdokResponse, GeocodingError>
+    where
+        T: Float + Debug,
+    {
+        self.get("suggest", params.as_query())
+    }
+
+    /// Fetch the full document of a suggestion by its `id`
+    ///
+    /// Please see [the documentation](https://github.com/PDOK/locatieserver/wiki/API-Locatieserver#33lookup-service) for details.
+    pub fn lookup(&self, id: &str) -> Result<Option<PdokDoc>, GeocodingError> {
+        let res: PdokResponse = self.get("lookup", vec![("id", id.to_string())])?;
+        Ok(res.response.docs.into_iter().next())
+    }
+
+    /// A reverse lookup of a WGS84 point, returning the nearest documents
+    ///
+    /// Please see [the documentation](https://github.com/PDOK/locatieserver/wiki/API-Locatieserver#34reverse-geocoder) for details.
+    pub fn reverse_full<T>(&self, point: &Point<T>) -> Result<PdokResponse, GeocodingError>
+    where
+        T: Float + Debug,
+    {
+        let query = vec![
+            ("lat", point.y().to_f64().unwrap().to_string()),
+            ("lon", point.x().to_f64().unwrap().to_string()),
+        ];
+        self.get("reverse", query)
+    }
+
+    /// A reverse lookup of an RD New (EPSG:28992) point, returning the nearest documents
+    ///
+    /// Please see [the documentation](https://github.com/PDOK/locatieserver/wiki/API-Locatieserver#34reverse-geocoder) for details.
+    pub fn reverse_rd<T>(&self, point: &Point<T>) -> Result<PdokResponse, GeocodingError>
+    where
+        T: Float + Debug,
+    {
+        let query = vec![
+            ("X", point.x().to_f64().unwrap().to_string()),
+            ("Y", point.y().to_f64().unwrap().to_string()),
+        ];
+        self.get("reverse", query)
+    }
+
+    fn get<R>(&self, path: &str, query: Vec<(&'static str, String)>) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl Default for Pdok {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Forward<T> for Pdok
+where
+    T: Float + Debug,
+{
+    /// A forward-geocoding free search. Please see [the documentation](https://github.com/PDOK/locatieserver/wiki/API-Locatieserver#31free-service) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(&PdokParams::<T>::new(place))?;
+        Ok(res
+            .response
+            .docs
+            .iter()
+            .filter_map(PdokDoc::point)
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for Pdok
+where
+    T: Float + Debug,
+{
+    /// A reverse lookup of a point, returning the `weergavenaam` of the nearest address.
+    /// Please see [the documentation](https://github.com/PDOK/locatieserver/wiki/API-Locatieserver#34reverse-geocoder) for details.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point)?;
+        Ok(res
+            .response
+            .docs
+            .into_iter()
+            .next()
+            .map(|doc| doc.weergavenaam))
+    }
+}
+
+/// The top-level full JSON response returned by a Locatieserver request
+///
+/// See [the documentation](https://github.com/PDOK/locatieserver/wiki/API-Locatieserver) for more details
+///
+///```json
+///{
+///  "response": {
+///    "numFound": 1,
+///    "start": 0,
+///    "maxScore": 15.6,
+///    "docs": [
+///      {
+///        "id": "adr-e3bd7e4d1bac2bc07fcf4fb9a5a1b90a",
+///        "type": "adres",
+///        "weergavenaam": "Dam 1, 1012JS Amsterdam",
+///        "score": 15.6,
+///        "centroide_ll": "POINT(4.89218616 52.37317433)",
+///        "centroide_rd": "POINT(121341.05 487417.895)",
+///        "straatnaam": "Dam",
+///        "huisnummer": 1,
+///        "postcode": "1012JS",
+///        "woonplaatsnaam": "Amsterdam",
+///        "woonplaatscode": "3594",
+///        "gemeentenaam": "Amsterdam",
+///        "gemeentecode": "0363",
+///        "provincienaam": "Noord-Holland",
+///        "provinciecode": "PV27",
+///        "nummeraanduiding_id": "0363200000218908",
+///        "adresseerbaarobject_id": "0363010000959579",
+///        "openbareruimte_id": "0363300000002702"
+///      }
+///    ]
+///  }
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PdokResponse {
+    pub response: PdokDocs,
+}
+
+/// A page of documents
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PdokDocs {
+    /// The total number of matches, across all pages
+    pub num_found: u32,
+    pub start: u32,
+    pub max_score: Option<f64>,
+    pub docs: Vec<PdokDoc>,
+}
+
+/// A Locatieserver document
+///
+/// Which fields are present depends on the `type` of the document.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PdokDoc {
+    pub id: String,
+    pub r#type: PdokType,
+    /// The display name
+    pub weergavenaam: String,
+    pub score: Option<f64>,
+    /// The distance from the queried point in metres, for reverse lookups
+    pub afstand: Option<f64>,
+    /// The WGS84 centroid, as a WKT point
+    pub centroide_ll: Option<String>,
+    /// The RD New centroid, as a WKT point
+    pub centroide_rd: Option<String>,
+    pub straatnaam: Option<String>,
+    pub huisnummer: Option<u32>,
+    pub huisletter: Option<String>,
+    pub huisnummertoevoeging: Option<String>,
+    pub postcode: Option<String>,
+    pub woonplaatsnaam: Option<String>,
+    pub woonplaatscode: Option<String>,
+    pub gemeentenaam: Option<String>,
+    /// The four-digit CBS municipality code
+    pub gemeentecode: Option<String>,
+    pub provincienaam: Option<String>,
+    pub provinciecode: Option<String>,
+    /// The BAG identifier of the address
+    pub nummeraanduiding_id: Option<String>,
+    /// The BAG identifier of the addressed object, e.g. a dwelling
+    pub adresseerbaarobject_id: Option<String>,
+    /// The BAG identifier of the public space, e.g. the street
+    pub openbareruimte_id: Option<String>,
+}
+
+impl PdokDoc {
+    /// The WGS84 centroid of the document
+    pub fn point<T>(&self) -> Option<Point<T>>
+    where
+        T: Float + Debug,
+    {
+        self.centroide_ll.as_deref().and_then(parse_wkt_point)
+    }
+
+    /// The RD New (EPSG:28992) centroid of the document, as an easting and northing in metres
+    pub fn rd_point<T>(&self) -> Option<Point<T>>
+    where
+        T: Float + Debug,
+    {
+        self.centroide_rd.as_deref().and_then(parse_wkt_point)
+    }
+}
+
+// Parse a WKT point, e.g. "POINT(4.89218616 52.37317433)"
+fn parse_wkt_point<T>(wkt: &str) -> Option<Point<T>>
+where
+    T: Float + Debug,
+{
+    let coords = wkt.trim().strip_prefix("POINT")?.trim();
+    let coords = coords.strip_prefix('(')?.strip_suffix(')')?;
+    let mut coords = coords
+        .split_whitespace()
+        .map(|c| T::from(c.parse::<f64>().ok()?));
+    match (coords.next(), coords.next(), coords.next()) {
+        (Some(Some(x)), Some(Some(y)), None) => Some(Point::new(x, y)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn doc_test() {
+        let res: PdokResponse = serde_json::from_str(
+            r#"{
+                "response": {
+                    "numFound": 1, "start": 0, "maxScore": 15.6,
+                    "docs": [
+                        {
+                            "id": "adr-e3bd7e4d1bac2bc07fcf4fb9a5a1b90a",
+                            "type": "adres",
+                            "weergavenaam": "Dam 1, 1012JS Amsterdam",
+                            "score": 15.6,
+                            "centroide_ll": "POINT(4.89218616 52.37317433)",
+                            "centroide_rd": "POINT(121341.05 487417.895)",
+                            "straatnaam": "Dam", "huisnummer": 1, "postcode": "1012JS",
+                            "woonplaatsnaam": "Amsterdam", "gemeentecode": "0363",
+                            "nummeraanduiding_id": "0363200000218908"
+                        }
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+        let doc = &res.response.docs[0];
+        assert_eq!(doc.r#type, PdokType::Adres);
+        assert_eq!(doc.point(), Some(Point::new(4.89218616, 52.37317433)));
+        assert_eq!(doc.rd_point(), Some(Point::new(121341.05, 487417.895)));
+    }
+
+    #[test]
+    fn wkt_test() {
+        assert_eq!(
+            parse_wkt_point::<f64>("POINT (1.5 -2)"),
+            Some(Point::new(1.5, -2.0))
+        );
+        assert_eq!(parse_wkt_point::<f64>("POINT(1.5)"), None);
+        assert_eq!(parse_wkt_point::<f64>("LINESTRING(1 2, 3 4)"), None);
+    }
+}
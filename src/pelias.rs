@@ -0,0 +1,650 @@
+//! The [Pelias](https://pelias.io/) provider.
+//!
+//! Pelias is an open-source geocoder which can be self-hosted, and is also offered as a hosted
+//! service by e.g. [geocode.earth](https://geocode.earth). Geocoding methods are implemented on
+//! the [`Pelias`](struct.Pelias.html) struct, which must be pointed at a Pelias API endpoint.
+//! Please see the [API documentation](https://github.com/pelias/documentation) for details.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, Pelias, Point};
+//!
+//! let pelias = Pelias::new("https://api.geocode.earth/v1/").with_api_key("your-api-key");
+//! let address = "Schwabing, München";
+//! let res: Result<Vec<Point<f64>>, _> = pelias.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::Deserialize;
+use crate::GeocodingError;
+use crate::InputBounds;
+use crate::Point;
+use crate::Serialize;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+
+/// An instance of a Pelias geocoding service
+pub struct Pelias {
+    client: Client,
+    endpoint: String,
+    api_key: Option<String>,
+}
+
+/// An instance of a parameter builder for Pelias search and autocomplete queries
+pub struct PeliasParams<'a, T>
+where
+    T: Float + Debug,
+{
+    text: &'a str,
+    size: Option<u8>,
+    layers: Option<&'a str>,
+    sources: Option<&'a str>,
+    boundary_rect: Option<&'a InputBounds<T>>,
+    boundary_country: Option<&'a str>,
+    focus_point: Option<&'a Point<T>>,
+}
+
+impl<'a, T> PeliasParams<'a, T>
+where
+    T: Float + Debug,
+{
+    /// Create a new Pelias parameter builder
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::{InputBounds, Point};
+    /// use geocoding::pelias::PeliasParams;
+    ///
+    /// let bbox = InputBounds::new(
+    ///     (-0.13806939125061035, 51.51989264641164),
+    ///     (-0.13427138328552246, 51.52319711775629),
+    /// );
+    /// let params = PeliasParams::new(&"Tottenham Court Road")
+    ///     .with_layers("address,street")
+    ///     .with_boundary_rect(&bbox)
+    ///     .with_size(5)
+    ///     .build();
+    /// ```
+    pub fn new(text: &'a str) -> PeliasParams<'a, T> {
+        PeliasParams {
+            text,
+            size: None,
+            layers: None,
+            sources: None,
+            boundary_rect: None,
+            boundary_country: None,
+            focus_point: None,
+        }
+    }
+
+    /// Set the `size` property, the maximum number of results to return
+    pub fn with_size(&mut self, size: u8) -> &mut Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Set the `layers` property, a comma-separated list of layers (e.g. "venue,address")
+    pub fn with_layers(&mut self, layers: &'a str) -> &mut Self {
+        self.layers = Some(layers);
+        self
+    }
+
+    /// Set the `sources` property, a comma-separated list of sources (e.g. "osm,oa")
+    pub fn with_sources(&mut self, sources: &'a str) -> &mut Self {
+        self.sources = Some(sources);
+        self
+    }
+
+    /// Set the `boundary.rect.*` properties, restricting results to a bounding box
+    pub fn with_boundary_rect(&mut self, boundary_rect: &'a InputBounds<T>) -> &mut Self {
+        self.boundary_rect = Some(boundary_rect);
+        self
+    }
+
+    /// Set the `boundary.country` property, an ISO 3166-1 alpha-2 or alpha-3 country code
+    pub fn with_boundary_country(&mut self, boundary_country: &'a str) -> &mut Self {
+        self.boundary_country = Some(boundary_country);
+        self
+    }
+
+    /// Set the `focus.point.*` properties, biasing results towards a location
+    pub fn with_focus_point(&mut self, focus_point: &'a Point<T>) -> &mut Self {
+        self.focus_point = Some(focus_point);
+        self
+    }
+
+    /// Build and return an instance of PeliasParams
+    pub fn build(&self) -> PeliasParams<'a, T> {
+        PeliasParams {
+            text: self.text,
+            size: self.size,
+            layers: self.layers,
+            sources: self.sources,
+            boundary_rect: self.boundary_rect,
+            boundary_country: self.boundary_country,
+            focus_point: self.focus_point,
+        }
+    }
+
+    pub(crate) fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("text", self.text.to_string())];
+        if let Some(size) = self.size {
+            query.push(("size", size.to_string()));
+        }
+        if let Some(layers) = self.layers {
+            query.push(("layers", layers.to_string()));
+        }
+        if let Some(sources) = self.sources {
+            query.push(("sources", sources.to_string()));
+        }
+        if let Some(bounds) = self.boundary_rect {
+            query.extend([
+                ("boundary.rect.min_lon", to_param(bounds.minimum_lonlat.x())),
+                ("boundary.rect.min_lat", to_param(bounds.minimum_lonlat.y())),
+                ("boundary.rect.max_lon", to_param(bounds.maximum_lonlat.x())),
+                ("boundary.rect.max_lat", to_param(bounds.maximum_lonlat.y())),
+            ]);
+        }
+        if let Some(country) = self.boundary_country {
+            query.push(("boundary.country", country.to_string()));
+        }
+        if let Some(point) = self.focus_point {
+            query.push(("focus.point.lon", to_param(point.x())));
+            query.push(("focus.point.lat", to_param(point.y())));
+        }
+        query
+    }
+}
+
+/// An instance of a parameter builder for Pelias reverse queries
+pub struct PeliasReverseParams<'a> {
+    size: Option<u8>,
+    layers: Option<&'a str>,
+    sources: Option<&'a str>,
+    boundary_country: Option<&'a str>,
+    boundary_circle_radius: Option<f64>,
+}
+
+impl<'a> PeliasReverseParams<'a> {
+    /// Create a new Pelias reverse parameter builder
+    pub fn new() -> PeliasReverseParams<'a> {
+        PeliasReverseParams {
+            size: None,
+            layers: None,
+            sources: None,
+            boundary_country: None,
+            boundary_circle_radius: None,
+        }
+    }
+
+    /// Set the `size` property, the maximum number of results to return
+    pub fn with_size(&mut self, size: u8) -> &mut Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Set the `layers` property, a comma-separated list of layers (e.g. "venue,address")
+    pub fn with_layers(&mut self, layers: &'a str) -> &mut Self {
+        self.layers = Some(layers);
+        self
+    }
+
+    /// Set the `sources` property, a comma-separated list of sources (e.g. "osm,oa")
+    pub fn with_sources(&mut self, sources: &'a str) -> &mut Self {
+        self.sources = Some(sources);
+        self
+    }
+
+    /// Set the `boundary.country` property, an ISO 3166-1 alpha-2 or alpha-3 country code
+    pub fn with_boundary_country(&mut self, boundary_country: &'a str) -> &mut Self {
+        self.boundary_country = Some(boundary_country);
+        self
+    }
+
+    /// Set the `boundary.circle.radius` property, the search radius in kilometers
+    pub fn with_boundary_circle_radius(&mut self, radius: f64) -> &mut Self {
+        self.boundary_circle_radius = Some(radius);
+        self
+    }
+
+    /// Build and return an instance of PeliasReverseParams
+    pub fn build(&self) -> PeliasReverseParams<'a> {
+        PeliasReverseParams {
+            size: self.size,
+            layers: self.layers,
+            sources: self.sources,
+            boundary_country: self.boundary_country,
+            boundary_circle_radius: self.boundary_circle_radius,
+        }
+    }
+
+    pub(crate) fn as_query<T>(&self, point: &Point<T>) -> Vec<(&'static str, String)>
+    where
+        T: Float + Debug,
+    {
+        let mut query = vec![
+            ("point.lon", to_param(point.x())),
+            ("point.lat", to_param(point.y())),
+        ];
+        if let Some(size) = self.size {
+            query.push(("size", size.to_string()));
+        }
+        if let Some(layers) = self.layers {
+            query.push(("layers", layers.to_string()));
+        }
+        if let Some(sources) = self.sources {
+            query.push(("sources", sources.to_string()));
+        }
+        if let Some(country) = self.boundary_country {
+            query.push(("boundary.country", country.to_string()));
+        }
+        if let Some(radius) = self.boundary_circle_radius {
+            query.push(("boundary.circle.radius", radius.to_string()));
+        }
+        query
+    }
+}
+
+impl<'a> Default for PeliasReverseParams<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn to_param<T>(value: T) -> String
+where
+    T: Float,
+{
+    value.to_f64().unwrap().to_string()
+}
+
+impl Pelias {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "Pelias",
+        docs_url: "https://github.com/pelias/documentation",
+        terms_url: None,
+        attribution: None,
+        rate_limit: None,
+    };
+
+    /// Create a new Pelias geocoding instance for the given endpoint
+    ///
+    /// Endpoint should include the API version and a trailing slash (i.e. "http://localhost:4000/v1/")
+    pub fn new(endpoint: &str) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Pelias {
+            client,
+            endpoint: endpoint.to_owned(),
+            api_key: None,
+        }
+    }
+
+    /// Set an API key, passed as the `api_key` parameter on every request
+    ///
+    /// Self-hosted Pelias instances usually don't require a key, but hosted services do.
+    pub fn with_api_key(mut self, api_key: &str) -> Self {
+        self.api_key = Some(api_key.to_owned());
+        self
+    }
+
+    /// A forward-geocoding search of a location, returning a full detailed response
+    ///
+    /// Accepts a [`PeliasParams`](struct.PeliasParams.html) struct for specifying
+    /// options, including layers, sources and boundary filters.
+    ///
+    /// Please see [the documentation](https://github.com/pelias/documentation/blob/master/search.md) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::{Pelias, Point};
+    /// use geocoding::pelias::{PeliasParams, PeliasResponse};
+    ///
+    /// let pelias = Pelias::new("https://api.geocode.earth/v1/").with_api_key("your-api-key");
+    /// let focus = Point::new(-0.13, 51.52);
+    /// let params = PeliasParams::new(&"Tottenham Court Road")
+    ///     .with_focus_point(&focus)
+    ///     .build();
+    /// let res: Result<PeliasResponse<f64>, _> = pelias.forward_full(&params);
+    /// ```
+    pub fn forward_full<T>(
+        &self,
+        params: &PeliasParams<T>,
+    ) -> Result<PeliasResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get("search", params.as_query())
+    }
+
+    /// An autocomplete search for partially typed text, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://github.com/pelias/documentation/blob/master/autocomplete.md) for details.
+    pub fn autocomplete<T>(
+        &self,
+        params: &PeliasParams<T>,
+    ) -> Result<PeliasResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get("autocomplete", params.as_query())
+    }
+
+    /// A reverse lookup of a point, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://github.com/pelias/documentation/blob/master/reverse.md) for details.
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+        params: &PeliasReverseParams,
+    ) -> Result<PeliasResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get("reverse", params.as_query(point))
+    }
+
+    fn get<T>(
+        &self,
+        path: &str,
+        mut query: Vec<(&'static str, String)>,
+    ) -> Result<PeliasResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        if let Some(api_key) = &self.api_key {
+            query.push(("api_key", api_key.clone()));
+        }
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: PeliasResponse<T> = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl<T> Forward<T> for Pelias
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of an address. Please see [the documentation](https://github.com/pelias/documentation/blob/master/search.md) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(&PeliasParams::new(place))?;
+        Ok(res
+            .features
+            .iter()
+            .map(|feature| {
+                Point::new(
+                    feature.geometry.coordinates.0,
+                    feature.geometry.coordinates.1,
+                )
+            })
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for Pelias
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the `label` of the nearest result.
+    /// Please see [the documentation](https://github.com/pelias/documentation/blob/master/reverse.md) for details.
+    ///
+    /// This method passes the `size` parameter to the API.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point, PeliasReverseParams::new().with_size(1))?;
+        Ok(res
+            .features
+            .first()
+            .and_then(|feature| feature.properties.label.clone()))
+    }
+}
+
+/// The top-level full GeoJSON response returned by a Pelias request
+///
+/// See [the documentation](https://github.com/pelias/documentation/blob/master/response.md) for more details
+///
+///```json
+///{
+///  "geocoding": {
+///    "version": "0.2",
+///    "attribution": "https://geocode.earth/guidelines",
+///    "query": {
+///      "text": "Tottenham Court Road",
+///      "size": 10
+///    },
+///    "engine": {
+///      "name": "Pelias",
+///      "author": "Mapzen",
+///      "version": "1.0"
+///    },
+///    "timestamp": 1695885960345
+///  },
+///  "type": "FeatureCollection",
+///  "features": [
+///    {
+///      "type": "Feature",
+///      "geometry": {
+///        "type": "Point",
+///        "coordinates": [-0.134649, 51.520713]
+///      },
+///      "properties": {
+///        "id": "polyline:2212375",
+///        "gid": "openstreetmap:street:polyline:2212375",
+///        "layer": "street",
+///        "source": "openstreetmap",
+///        "source_id": "polyline:2212375",
+///        "name": "Tottenham Court Road",
+///        "street": "Tottenham Court Road",
+///        "confidence": 1,
+///        "match_type": "exact",
+///        "accuracy": "centroid",
+///        "country": "United Kingdom",
+///        "country_gid": "whosonfirst:country:85633159",
+///        "country_a": "GBR",
+///        "region": "Camden",
+///        "region_gid": "whosonfirst:region:1360698645",
+///        "locality": "London",
+///        "locality_gid": "whosonfirst:locality:101750367",
+///        "label": "Tottenham Court Road, London, England, United Kingdom"
+///      }
+///    }
+///  ],
+///  "bbox": [-0.134649, 51.520713, -0.134649, 51.520713]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeliasResponse<T>
+where
+    T: Float + Debug,
+{
+    pub geocoding: Option<PeliasMetadata>,
+    pub r#type: String,
+    pub features: Vec<PeliasFeature<T>>,
+    pub bbox: Option<(T, T, T, T)>,
+}
+
+/// Metadata about the request and the Pelias engine that answered it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeliasMetadata {
+    pub version: Option<String>,
+    pub attribution: Option<String>,
+    pub query: Option<serde_json::Value>,
+    pub engine: Option<PeliasEngine>,
+    pub timestamp: Option<u64>,
+}
+
+/// The Pelias engine version
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeliasEngine {
+    pub name: String,
+    pub author: String,
+    pub version: String,
+}
+
+/// A geocoding result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeliasFeature<T>
+where
+    T: Float + Debug,
+{
+    pub r#type: String,
+    pub geometry: PeliasGeometry<T>,
+    pub properties: PeliasProperties,
+    pub bbox: Option<(T, T, T, T)>,
+}
+
+/// A geocoding result geometry
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeliasGeometry<T>
+where
+    T: Float + Debug,
+{
+    pub r#type: String,
+    pub coordinates: (T, T),
+}
+
+/// Geocoding result properties
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PeliasProperties {
+    pub id: String,
+    pub gid: String,
+    pub layer: String,
+    pub source: String,
+    pub source_id: String,
+    pub name: Option<String>,
+    pub housenumber: Option<String>,
+    pub street: Option<String>,
+    pub postalcode: Option<String>,
+    pub confidence: Option<f64>,
+    pub match_type: Option<String>,
+    pub accuracy: Option<String>,
+    pub distance: Option<f64>,
+    pub country: Option<String>,
+    pub country_gid: Option<String>,
+    pub country_a: Option<String>,
+    pub macroregion: Option<String>,
+    pub region: Option<String>,
+    pub region_gid: Option<String>,
+    pub region_a: Option<String>,
+    pub macrocounty: Option<String>,
+    pub county: Option<String>,
+    pub county_gid: Option<String>,
+    pub localadmin: Option<String>,
+    pub locality: Option<String>,
+    pub locality_gid: Option<String>,
+    pub borough: Option<String>,
+    pub neighbourhood: Option<String>,
+    pub neighbourhood_gid: Option<String>,
+    pub continent: Option<String>,
+    pub label: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn params_query_test() {
+        let bbox = InputBounds::new((-0.5, 51.25), (0.25, 51.75));
+        let params = PeliasParams::new("Tottenham Court Road")
+            .with_size(5)
+            .with_layers("address,street")
+            .with_boundary_rect(&bbox)
+            .with_boundary_country("GB")
+            .build();
+        assert_eq!(
+            params.as_query(),
+            vec![
+                ("text", "Tottenham Court Road".to_string()),
+                ("size", "5".to_string()),
+                ("layers", "address,street".to_string()),
+                ("boundary.rect.min_lon", "-0.5".to_string()),
+                ("boundary.rect.min_lat", "51.25".to_string()),
+                ("boundary.rect.max_lon", "0.25".to_string()),
+                ("boundary.rect.max_lat", "51.75".to_string()),
+                ("boundary.country", "GB".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reverse_params_query_test() {
+        let params = PeliasReverseParams::new()
+            .with_size(1)
+            .with_sources("osm")
+            .build();
+        assert_eq!(
+            params.as_query(&Point::new(-0.134649, 51.520713)),
+            vec![
+                ("point.lon", "-0.134649".to_string()),
+                ("point.lat", "51.520713".to_string()),
+                ("size", "1".to_string()),
+                ("sources", "osm".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn response_test() {
+        let res: PeliasResponse<f64> = serde_json::from_str(
+            r#"{
+                "geocoding": {
+                    "version": "0.2",
+                    "attribution": "https://geocode.earth/guidelines",
+                    "query": { "text": "Tottenham Court Road", "size": 10 },
+                    "engine": { "name": "Pelias", "author": "Mapzen", "version": "1.0" },
+                    "timestamp": 1695885960345
+                },
+                "type": "FeatureCollection",
+                "features": [
+                    {
+                        "type": "Feature",
+                        "geometry": { "type": "Point", "coordinates": [-0.134649, 51.520713] },
+                        "properties": {
+                            "id": "polyline:2212375",
+                            "gid": "openstreetmap:street:polyline:2212375",
+                            "layer": "street",
+                            "source": "openstreetmap",
+                            "source_id": "polyline:2212375",
+                            "name": "Tottenham Court Road",
+                            "confidence": 1,
+                            "match_type": "exact",
+                            "country_a": "GBR",
+                            "locality": "London",
+                            "label": "Tottenham Court Road, London, England, United Kingdom"
+                        }
+                    }
+                ],
+                "bbox": [-0.134649, 51.520713, -0.134649, 51.520713]
+            }"#,
+        )
+        .unwrap();
+        let feature = &res.features[0];
+        assert_eq!(feature.geometry.coordinates, (-0.134649, 51.520713));
+        assert_eq!(feature.properties.layer, "street");
+        assert_eq!(feature.properties.confidence, Some(1.0));
+        assert_eq!(
+            feature.properties.label.as_deref(),
+            Some("Tottenham Court Road, London, England, United Kingdom")
+        );
+    }
+}
@@ -0,0 +1,442 @@
+//! An offline [Open Location Code](https://github.com/google/open-location-code) (Plus Codes) provider.
+//!
+//! Plus Codes encode a location as a short code, such as `8FVC9G8F+6X`, designating an area
+//! of about 14 by 14 metres; longer codes designate smaller areas. Since codes are computed
+//! from coordinates, the [`PlusCodes`](struct.PlusCodes.html) provider requires no network
+//! access or key, which makes it a useful fallback. Forward lookups decode a code to the center
+//! of its area, and reverse lookups encode a point.
+//!
+//! Short codes omit the leading digits of a full code, such as `9G8F+6X`, and are recovered or
+//! produced relative to a reference location, set using `with_reference`. Codes qualified by a
+//! locality, such as `9G8F+6X Zürich`, aren't supported, as resolving the locality requires a
+//! geocoder: forward-geocode the locality, and use it as the reference.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, PlusCodes, Point, Reverse};
+//!
+//! let plus_codes = PlusCodes::new();
+//! let res: Vec<Point<f64>> = plus_codes.forward("8FVC9G8F+6X").unwrap();
+//! let code: Option<String> = plus_codes.reverse(&res[0]).unwrap();
+//! assert_eq!(code.as_deref(), Some("8FVC9G8F+6X"));
+//! ```
+use crate::info::ProviderInfo;
+use crate::GeocodingError;
+use crate::Point;
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+
+const ALPHABET: &[u8; 20] = b"23456789CFGHJMPQRVWX";
+const SEPARATOR: char = '+';
+const SEPARATOR_POSITION: usize = 8;
+const PADDING: char = '0';
+const PAIR_CODE_LENGTH: usize = 10;
+const MAX_CODE_LENGTH: usize = 15;
+
+// Coordinates are encoded as integers, in units of the final grid cell
+const LAT_PRECISION: i64 = 25_000_000;
+const LNG_PRECISION: i64 = 8_192_000;
+
+/// An instance of the offline Plus Codes provider
+#[derive(Clone, Debug)]
+pub struct PlusCodes {
+    code_length: usize,
+    reference: Option<(f64, f64)>,
+}
+
+impl PlusCodes {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "Plus Codes",
+        docs_url: "https://github.com/google/open-location-code",
+        terms_url: None,
+        attribution: None,
+        rate_limit: None,
+    };
+
+    /// Create a new Plus Codes instance, encoding 10-digit codes
+    pub fn new() -> Self {
+        PlusCodes {
+            code_length: PAIR_CODE_LENGTH,
+            reference: None,
+        }
+    }
+
+    /// Set the number of digits of encoded codes
+    ///
+    /// # Panics
+    ///
+    /// Panics unless the length is 2, 4, 6, 8, or between 10 and 15.
+    pub fn with_code_length(mut self, code_length: usize) -> Self {
+        assert!(
+            is_valid_length(code_length),
+            "Invalid Plus Code length {}",
+            code_length
+        );
+        self.code_length = code_length;
+        self
+    }
+
+    /// Set a reference location, recovering short codes in forward lookups, and shortening
+    /// codes in reverse lookups
+    pub fn with_reference<T>(mut self, reference: &Point<T>) -> Self
+    where
+        T: Float + Debug,
+    {
+        self.reference = Some((
+            reference.y().to_f64().unwrap(),
+            reference.x().to_f64().unwrap(),
+        ));
+        self
+    }
+}
+
+impl Default for PlusCodes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Forward<T> for PlusCodes
+where
+    T: Float + Debug,
+{
+    /// Decode a full code, or a short code if a reference location is set, to the center of
+    /// its area. Anything else returns no results.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let code = place.trim();
+        let full = match self.reference {
+            _ if is_full(code) => Some(code.to_ascii_uppercase()),
+            Some((lat, lng)) => recover_nearest(code, lat, lng),
+            None => None,
+        };
+        Ok(full
+            .and_then(|code| decode(&code))
+            .map(|area| {
+                let (lat, lng) = area.center();
+                Point::new(T::from(lng).unwrap(), T::from(lat).unwrap())
+            })
+            .into_iter()
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for PlusCodes
+where
+    T: Float + Debug,
+{
+    /// Encode a point, shortening the code if a reference location is set and near enough.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let lat = point.y().to_f64().unwrap();
+        let lng = point.x().to_f64().unwrap();
+        let code = encode(lat, lng, self.code_length);
+        Ok(match self.reference {
+            Some((ref_lat, ref_lng)) => shorten(&code, ref_lat, ref_lng),
+            None => Some(code),
+        })
+    }
+}
+
+/// The area designated by a code, in degrees
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CodeArea {
+    pub south: f64,
+    pub west: f64,
+    pub north: f64,
+    pub east: f64,
+    /// The number of digits of the code
+    pub code_length: usize,
+}
+
+impl CodeArea {
+    /// The center of the area, as a latitude and longitude
+    pub fn center(&self) -> (f64, f64) {
+        let lat = (self.south + (self.north - self.south) / 2.0).min(90.0);
+        let lng = (self.west + (self.east - self.west) / 2.0).min(180.0);
+        (lat, lng)
+    }
+}
+
+fn is_valid_length(code_length: usize) -> bool {
+    match code_length {
+        2 | 4 | 6 | 8 => true,
+        _ => (PAIR_CODE_LENGTH..=MAX_CODE_LENGTH).contains(&code_length),
+    }
+}
+
+fn digit_value(digit: u8) -> Option<i64> {
+    ALPHABET
+        .iter()
+        .position(|&c| c == digit.to_ascii_uppercase())
+        .map(|value| value as i64)
+}
+
+/// Whether a string is a valid full or short code
+pub fn is_valid(code: &str) -> bool {
+    let separator = match code.find(SEPARATOR) {
+        Some(separator) => separator,
+        None => return false,
+    };
+    if code.rfind(SEPARATOR) != Some(separator)
+        || !(2..=SEPARATOR_POSITION).contains(&separator)
+        || separator % 2 == 1
+        || code.len() - separator - 1 == 1
+    {
+        return false;
+    }
+    if let Some(padding) = code.find(PADDING) {
+        // Padding fills a full code up to the separator, which ends it
+        if padding > separator {
+            return false;
+        }
+        let padded = &code[padding..separator];
+        if separator < SEPARATOR_POSITION
+            || padding == 0
+            || padding % 2 == 1
+            || !padded.chars().all(|c| c == PADDING)
+            || separator != code.len() - 1
+        {
+            return false;
+        }
+    }
+    code.bytes()
+        .all(|c| c == SEPARATOR as u8 || c == PADDING as u8 || digit_value(c).is_some())
+}
+
+/// Whether a string is a valid full code
+pub fn is_full(code: &str) -> bool {
+    if !is_valid(code) || is_short(code) {
+        return false;
+    }
+    let bytes = code.as_bytes();
+    // The first digits must lie within 180 degrees of latitude and 360 of longitude
+    let lat = digit_value(bytes[0]).unwrap_or(0) * 20;
+    let lng = bytes
+        .get(1)
+        .and_then(|&digit| digit_value(digit))
+        .unwrap_or(0)
+        * 20;
+    lat < 180 && lng < 360
+}
+
+/// Whether a string is a valid short code
+pub fn is_short(code: &str) -> bool {
+    is_valid(code) && code.find(SEPARATOR) < Some(SEPARATOR_POSITION)
+}
+
+/// Encode a location as a code of `code_length` digits
+///
+/// # Panics
+///
+/// Panics unless the length is 2, 4, 6, 8, or between 10 and 15.
+///
+/// # Examples
+///
+/// ```
+/// use geocoding::pluscodes::encode;
+///
+/// assert_eq!(encode(47.365562, 8.524969, 10), "8FVC9G8F+6X");
+/// assert_eq!(encode(47.365562, 8.524969, 4), "8FVC0000+");
+/// ```
+pub fn encode(lat: f64, lng: f64, code_length: usize) -> String {
+    assert!(
+        is_valid_length(code_length),
+        "Invalid Plus Code length {}",
+        code_length
+    );
+    let mut lat_units = to_units(lat.clamp(-90.0, 90.0) + 90.0, LAT_PRECISION);
+    lat_units = lat_units.min(180 * LAT_PRECISION - 1);
+    let lng_units = to_units(lng + 180.0, LNG_PRECISION).rem_euclid(360 * LNG_PRECISION);
+
+    let mut code = String::with_capacity(MAX_CODE_LENGTH + 1);
+    let mut lat_place = 400 * LAT_PRECISION;
+    let mut lng_place = 400 * LNG_PRECISION;
+    for _ in 0..PAIR_CODE_LENGTH / 2 {
+        lat_place /= 20;
+        lng_place /= 20;
+        code.push(ALPHABET[((lat_units / lat_place) % 20) as usize] as char);
+        code.push(ALPHABET[((lng_units / lng_place) % 20) as usize] as char);
+    }
+    for _ in PAIR_CODE_LENGTH..code_length {
+        lat_place /= 5;
+        lng_place /= 4;
+        let row = (lat_units / lat_place) % 5;
+        let col = (lng_units / lng_place) % 4;
+        code.push(ALPHABET[(row * 4 + col) as usize] as char);
+    }
+    code.truncate(code_length);
+    while code.len() < SEPARATOR_POSITION {
+        code.push(PADDING);
+    }
+    code.insert(SEPARATOR_POSITION, SEPARATOR);
+    code
+}
+
+// Rounding away floating point error before flooring, as the reference implementation does
+fn to_units(degrees: f64, precision: i64) -> i64 {
+    ((degrees * precision as f64 * 1e6).round() / 1e6).floor() as i64
+}
+
+/// Decode a full code to the area it designates
+///
+/// Returns `None` if the code isn't a valid full code. Digits beyond the fifteenth are ignored.
+pub fn decode(code: &str) -> Option<CodeArea> {
+    if !is_full(code) {
+        return None;
+    }
+    let digits: Vec<i64> = code
+        .bytes()
+        .filter_map(digit_value)
+        .take(MAX_CODE_LENGTH)
+        .collect();
+    let (mut lat, mut lng) = (0, 0);
+    let mut lat_place = 400 * LAT_PRECISION;
+    let mut lng_place = 400 * LNG_PRECISION;
+    for pair in digits.chunks(2).take(PAIR_CODE_LENGTH / 2) {
+        lat_place /= 20;
+        lng_place /= 20;
+        lat += pair[0] * lat_place;
+        lng += pair.get(1).copied().unwrap_or(0) * lng_place;
+    }
+    for digit in digits.iter().skip(PAIR_CODE_LENGTH) {
+        lat_place /= 5;
+        lng_place /= 4;
+        lat += (digit / 4) * lat_place;
+        lng += (digit % 4) * lng_place;
+    }
+    Some(CodeArea {
+        south: lat as f64 / LAT_PRECISION as f64 - 90.0,
+        west: lng as f64 / LNG_PRECISION as f64 - 180.0,
+        north: (lat + lat_place) as f64 / LAT_PRECISION as f64 - 90.0,
+        east: (lng + lng_place) as f64 / LNG_PRECISION as f64 - 180.0,
+        code_length: digits.len(),
+    })
+}
+
+/// Shorten a full code relative to a reference location, removing as many leading digits as
+/// the reference allows recovering
+///
+/// Returns `None` if the code isn't a valid, unpadded full code, and the code unchanged if the
+/// reference is too far away.
+///
+/// # Examples
+///
+/// ```
+/// use geocoding::pluscodes::shorten;
+///
+/// assert_eq!(shorten("8FVC9G8F+6X", 47.37, 8.54).as_deref(), Some("9G8F+6X"));
+/// ```
+pub fn shorten(code: &str, ref_lat: f64, ref_lng: f64) -> Option<String> {
+    if code.contains(PADDING) {
+        return None;
+    }
+    let area = decode(code)?;
+    let (lat, lng) = area.center();
+    let ref_lat = ref_lat.clamp(-90.0, 90.0);
+    let ref_lng = normalize_lng(ref_lng);
+    let range = (lat - ref_lat).abs().max((lng - ref_lng).abs());
+    let code = code.to_ascii_uppercase();
+    // Removing the first 8, 6 or 4 digits, if the reference lies well within the area of the
+    // remaining digits
+    for &(removed, resolution) in &[(8, 0.0025), (6, 0.05), (4, 1.0)] {
+        if range < resolution * 0.3 {
+            return Some(code[removed..].to_string());
+        }
+    }
+    Some(code)
+}
+
+/// Recover the full code nearest to a reference location from a short code
+///
+/// Returns `None` if the code isn't valid, and full codes unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use geocoding::pluscodes::recover_nearest;
+///
+/// assert_eq!(recover_nearest("9G8F+6X", 47.4, 8.6).as_deref(), Some("8FVC9G8F+6X"));
+/// ```
+pub fn recover_nearest(code: &str, ref_lat: f64, ref_lng: f64) -> Option<String> {
+    if is_full(code) {
+        return Some(code.to_ascii_uppercase());
+    }
+    if !is_short(code) {
+        return None;
+    }
+    let ref_lat = ref_lat.clamp(-90.0, 90.0);
+    let ref_lng = normalize_lng(ref_lng);
+    let missing = SEPARATOR_POSITION - code.find(SEPARATOR)?;
+    let resolution = 20f64.powi(2 - (missing / 2) as i32);
+    let half = resolution / 2.0;
+
+    let prefix = encode(ref_lat, ref_lng, PAIR_CODE_LENGTH);
+    let candidate = format!("{}{}", &prefix[..missing], code.to_ascii_uppercase());
+    let area = decode(&candidate)?;
+    let (mut lat, mut lng) = area.center();
+    // Move the candidate by a cell if its neighbour is nearer to the reference
+    if ref_lat + half < lat && lat - resolution >= -90.0 {
+        lat -= resolution;
+    } else if ref_lat - half > lat && lat + resolution <= 90.0 {
+        lat += resolution;
+    }
+    if ref_lng + half < lng {
+        lng -= resolution;
+    } else if ref_lng - half > lng {
+        lng += resolution;
+    }
+    Some(encode(lat, lng, area.code_length))
+}
+
+fn normalize_lng(lng: f64) -> f64 {
+    (lng + 180.0).rem_euclid(360.0) - 180.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encode_decode_test() {
+        assert_eq!(encode(20.375, 2.775, 6), "7FG49Q00+");
+        assert_eq!(encode(20.3700625, 2.7821875, 10), "7FG49QCJ+2V");
+        assert_eq!(encode(20.3701125, 2.782234375, 11), "7FG49QCJ+2VX");
+        assert_eq!(encode(47.0000625, 8.0000625, 10), "8FVC2222+22");
+        assert_eq!(encode(90.0, 1.0, 10), "CFX3X2X2+X2");
+        let area = decode("7FG49QCJ+2V").unwrap();
+        let (lat, lng) = area.center();
+        assert!((lat - 20.3700625).abs() < 1e-10);
+        assert!((lng - 2.7821875).abs() < 1e-10);
+        assert_eq!(area.code_length, 10);
+        assert!(decode("7FG49Q00+").is_some());
+        assert!(decode("9QCJ+2V").is_none());
+        assert!(!is_valid("7FG49QCJ2V"));
+        assert!(!is_valid("7FG49Q0+"));
+        assert!(!is_valid("7FG49QCJ+2"));
+        assert!(!is_valid("7FG49QCJ+20"));
+    }
+
+    #[test]
+    fn shorten_recover_test() {
+        let code = "9C3W9QCJ+2VX";
+        assert_eq!(
+            shorten(code, 51.3701125, -1.217765625).as_deref(),
+            Some("+2VX")
+        );
+        assert_eq!(
+            shorten(code, 51.3708675, -1.217765625).as_deref(),
+            Some("CJ+2VX")
+        );
+        assert_eq!(
+            recover_nearest("CJ+2VX", 51.3708675, -1.217765625).as_deref(),
+            Some(code)
+        );
+        let plus_codes = PlusCodes::new().with_reference(&Point::new(-1.2, 51.4));
+        let res: Vec<Point<f64>> = plus_codes.forward("9QCJ+2VX").unwrap();
+        assert_eq!(res.len(), 1);
+        let none: Vec<Point<f64>> = PlusCodes::new().forward("9QCJ+2VX").unwrap();
+        assert!(none.is_empty());
+    }
+}
@@ -0,0 +1,421 @@
+//! The [positionstack](https://positionstack.com/) provider.
+//!
+//! Geocoding methods are implemented on the [`PositionStack`](struct.PositionStack.html) struct.
+//! An access key is required. Please see the
+//! [API documentation](https://positionstack.com/documentation) for details.
+//!
+//! The free plan only allows plain HTTP requests; its users should set the
+//! `http://api.positionstack.com/v1/` endpoint using
+//! [`with_endpoint`](struct.PositionStack.html#method.with_endpoint).
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, Point, PositionStack};
+//!
+//! let positionstack = PositionStack::new("your-access-key".to_string());
+//! let address = "1600 Pennsylvania Ave NW, Washington DC";
+//! let res: Result<Vec<Point<f64>>, _> = positionstack.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+
+/// An instance of the positionstack geocoding service
+pub struct PositionStack {
+    access_key: String,
+    client: Client,
+    endpoint: String,
+}
+
+/// An instance of a parameter builder for positionstack requests
+pub struct PositionStackParams<'a> {
+    query: &'a str,
+    limit: Option<u8>,
+    countries: &'a [&'a str],
+    region: Option<&'a str>,
+    language: Option<&'a str>,
+    country_module: bool,
+}
+
+impl<'a> PositionStackParams<'a> {
+    /// Create a new positionstack parameter builder
+    ///
+    /// For reverse requests, the query is a `latitude,longitude` pair.
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::positionstack::PositionStackParams;
+    ///
+    /// let params = PositionStackParams::new(&"Copacabana")
+    ///     .with_countries(&["BR"])
+    ///     .with_country_module(true)
+    ///     .with_limit(5)
+    ///     .build();
+    /// ```
+    pub fn new(query: &'a str) -> PositionStackParams<'a> {
+        PositionStackParams {
+            query,
+            limit: None,
+            countries: &[],
+            region: None,
+            language: None,
+            country_module: false,
+        }
+    }
+
+    /// Set the `limit` property, the maximum number of results to return (10 by default, at most 80)
+    pub fn with_limit(&mut self, limit: u8) -> &mut Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Set the `country` property, restricting results to ISO 3166-1 alpha-2 or alpha-3 country codes
+    pub fn with_countries(&mut self, countries: &'a [&'a str]) -> &mut Self {
+        self.countries = countries;
+        self
+    }
+
+    /// Set the `region` property, restricting results to a region, e.g. a state or county
+    pub fn with_region(&mut self, region: &'a str) -> &mut Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// Set the `language` property, the language of results as a 2-letter code
+    pub fn with_language(&mut self, language: &'a str) -> &mut Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Set the `country_module` property, including country details in results
+    pub fn with_country_module(&mut self, country_module: bool) -> &mut Self {
+        self.country_module = country_module;
+        self
+    }
+
+    /// Build and return an instance of PositionStackParams
+    pub fn build(&self) -> PositionStackParams<'a> {
+        PositionStackParams {
+            query: self.query,
+            limit: self.limit,
+            countries: self.countries,
+            region: self.region,
+            language: self.language,
+            country_module: self.country_module,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("query", self.query.to_string())];
+        if let Some(limit) = self.limit {
+            query.push(("limit", limit.to_string()));
+        }
+        if !self.countries.is_empty() {
+            query.push(("country", self.countries.join(",")));
+        }
+        if let Some(region) = self.region {
+            query.push(("region", region.to_string()));
+        }
+        if let Some(language) = self.language {
+            query.push(("language", language.to_string()));
+        }
+        if self.country_module {
+            query.push(("country_module", String::from("1")));
+        }
+        query
+    }
+}
+
+impl PositionStack {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "positionstack",
+        docs_url: "https://positionstack.com/documentation",
+        terms_url: Some("https://positionstack.com/terms"),
+        attribution: None,
+        rate_limit: None,
+    };
+
+    /// Create a new positionstack geocoding instance using the default endpoint
+    pub fn new(access_key: String) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        PositionStack {
+            access_key,
+            client,
+            endpoint: "https://api.positionstack.com/v1/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of a positionstack geocoding instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://api.positionstack.com/v1/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A forward-geocoding lookup of an address, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://positionstack.com/documentation#forward_geocoding) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::PositionStack;
+    /// use geocoding::positionstack::{PositionStackParams, PositionStackResponse};
+    ///
+    /// let positionstack = PositionStack::new("your-access-key".to_string());
+    /// let params = PositionStackParams::new(&"Copacabana")
+    ///     .with_country_module(true)
+    ///     .build();
+    /// let res: Result<PositionStackResponse<f64>, _> = positionstack.forward_full(&params);
+    /// ```
+    pub fn forward_full<T>(
+        &self,
+        params: &PositionStackParams,
+    ) -> Result<PositionStackResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get("forward", params.as_query())
+    }
+
+    /// A reverse lookup of a point, returning a full detailed response
+    ///
+    /// Results are sorted by increasing `distance` from the point.
+    ///
+    /// Please see [the documentation](https://positionstack.com/documentation#reverse_geocoding) for details.
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+    ) -> Result<PositionStackResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        // positionstack expects lat, lon order
+        let query = format!(
+            "{},{}",
+            point.y().to_f64().unwrap(),
+            point.x().to_f64().unwrap()
+        );
+        self.get("reverse", PositionStackParams::new(&query).as_query())
+    }
+
+    fn get<R>(
+        &self,
+        path: &str,
+        mut query: Vec<(&'static str, String)>,
+    ) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        query.push(("access_key", self.access_key.clone()));
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl<T> Forward<T> for PositionStack
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of an address. Please see [the documentation](https://positionstack.com/documentation#forward_geocoding) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(&PositionStackParams::new(place))?;
+        Ok(res
+            .data
+            .iter()
+            .map(|result| Point::new(result.longitude, result.latitude))
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for PositionStack
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the `label` of the closest result.
+    /// Please see [the documentation](https://positionstack.com/documentation#reverse_geocoding) for details.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point)?;
+        Ok(res.data.into_iter().next().map(|result| result.label))
+    }
+}
+
+/// The top-level full JSON response returned by a forward or reverse request
+///
+/// See [the documentation](https://positionstack.com/documentation) for more details
+///
+///```json
+///{
+///  "data": [
+///    {
+///      "latitude": 38.897675,
+///      "longitude": -77.036547,
+///      "type": "address",
+///      "name": "1600 Pennsylvania Avenue NW",
+///      "number": "1600",
+///      "postal_code": "20500",
+///      "street": "Pennsylvania Avenue NW",
+///      "confidence": 1,
+///      "region": "District of Columbia",
+///      "region_code": "DC",
+///      "county": null,
+///      "locality": "Washington",
+///      "administrative_area": null,
+///      "neighbourhood": "White House Grounds",
+///      "country": "United States",
+///      "country_code": "USA",
+///      "continent": "North America",
+///      "label": "1600 Pennsylvania Avenue NW, Washington, DC, USA",
+///      "map_url": "http://map.positionstack.com/38.897675,-77.036547"
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PositionStackResponse<T>
+where
+    T: Float + Debug,
+{
+    pub data: Vec<PositionStackResult<T>>,
+}
+
+/// A forward or reverse geocoding result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PositionStackResult<T>
+where
+    T: Float + Debug,
+{
+    pub latitude: T,
+    pub longitude: T,
+    /// The kind of result, e.g. `address`, `street`, `venue` or `locality`
+    pub r#type: String,
+    pub name: Option<String>,
+    pub number: Option<String>,
+    pub postal_code: Option<String>,
+    pub street: Option<String>,
+    /// How well the result matches the query, from 0 to 1
+    pub confidence: f64,
+    /// The distance from the queried point in metres, for reverse requests
+    pub distance: Option<f64>,
+    pub region: Option<String>,
+    pub region_code: Option<String>,
+    pub county: Option<String>,
+    pub locality: Option<String>,
+    pub administrative_area: Option<String>,
+    pub neighbourhood: Option<String>,
+    pub country: Option<String>,
+    /// The ISO 3166-1 alpha-3 country code
+    pub country_code: Option<String>,
+    pub continent: Option<String>,
+    pub label: String,
+    /// A link to the result on the positionstack map
+    pub map_url: Option<String>,
+    /// Country details, if requested using the `country_module` parameter
+    pub country_module: Option<CountryModule>,
+}
+
+/// Details about the country of a result
+///
+/// Only the commonly used fields are typed; dialling codes, currencies and languages
+/// are available as raw JSON.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CountryModule {
+    pub common_name: String,
+    pub official_name: String,
+    pub capital: Option<String>,
+    /// The country's flag, as a Unicode emoji
+    pub flag: Option<String>,
+    /// The area in square kilometres
+    pub area: Option<f64>,
+    pub landlocked: bool,
+    pub independent: bool,
+    pub global: CountryCodes,
+    pub dial: Option<serde_json::Value>,
+    pub currencies: Option<serde_json::Value>,
+    pub languages: Option<serde_json::Value>,
+}
+
+/// Codes and regions of a country
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CountryCodes {
+    /// The ISO 3166-1 alpha-2 code
+    pub alpha2: String,
+    /// The ISO 3166-1 alpha-3 code
+    pub alpha3: String,
+    /// The ISO 3166-1 numeric code
+    pub numeric_code: Option<String>,
+    pub region: Option<String>,
+    pub subregion: Option<String>,
+    pub world_region: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn country_module_test() {
+        let res: PositionStackResponse<f64> = serde_json::from_str(
+            r#"{
+                "data": [
+                    {
+                        "latitude": -22.971964, "longitude": -43.184343,
+                        "type": "neighbourhood", "name": "Copacabana",
+                        "number": null, "postal_code": null, "street": null,
+                        "confidence": 1, "region": "Rio de Janeiro", "region_code": "RJ",
+                        "county": "Rio de Janeiro", "locality": "Rio de Janeiro",
+                        "administrative_area": null, "neighbourhood": "Copacabana",
+                        "country": "Brazil", "country_code": "BRA", "continent": "South America",
+                        "label": "Copacabana, Rio de Janeiro, Brazil",
+                        "map_url": "http://map.positionstack.com/-22.971964,-43.184343",
+                        "country_module": {
+                            "latitude": -10.3, "longitude": -53.2,
+                            "common_name": "Brazil",
+                            "official_name": "Federative Republic of Brazil",
+                            "capital": "Brasília", "flag": "🇧🇷", "area": 8515767,
+                            "landlocked": false, "independent": true,
+                            "global": {
+                                "alpha2": "BR", "alpha3": "BRA", "numeric_code": "076",
+                                "region": "Americas", "subregion": "Latin America and the Caribbean",
+                                "world_region": "AMER"
+                            },
+                            "dial": { "calling_code": "55" },
+                            "currencies": [{ "symbol": "R$", "code": "BRL", "name": "Brazilian real" }],
+                            "languages": { "por": "Portuguese" }
+                        }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let result = &res.data[0];
+        assert_eq!(result.confidence, 1.0);
+        let country = result.country_module.as_ref().unwrap();
+        assert_eq!(country.global.alpha2, "BR");
+        assert!(!country.landlocked);
+    }
+}
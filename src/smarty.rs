@@ -0,0 +1,519 @@
+//! The [Smarty](https://www.smarty.com/) (formerly SmartyStreets) provider.
+//!
+//! Geocoding methods are implemented on the [`Smarty`](struct.Smarty.html) struct, using the
+//! [US Street Address API](https://www.smarty.com/docs/cloud/us-street-api). Smarty verifies
+//! addresses against USPS data: each candidate is a standardised, deliverable address, with
+//! its coordinates and delivery point validation (DPV) results.
+//!
+//! Requests are authenticated with either a secret key pair (an auth ID and auth token), for
+//! server-side use, or an embedded key, which is tied to the `Referer` of the requests.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, Point, Smarty};
+//!
+//! let smarty = Smarty::new("your-auth-id".to_string(), "your-auth-token".to_string());
+//! let address = "1600 Amphitheatre Pkwy, Mountain View, CA";
+//! let res: Result<Vec<Point<f64>>, _> = smarty.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::Forward;
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, Serialize};
+use num_traits::Float;
+use reqwest::header::REFERER;
+use std::fmt::Debug;
+
+/// An instance of the Smarty US Street Address API
+pub struct Smarty {
+    credentials: Credentials,
+    license: Option<String>,
+    client: Client,
+    endpoint: String,
+}
+
+enum Credentials {
+    Secret { auth_id: String, auth_token: String },
+    Embedded { key: String, referer: String },
+}
+
+/// How strictly input addresses are matched
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MatchStrategy {
+    /// Only return valid, deliverable addresses; the default
+    Strict,
+    /// Also return a standardised candidate for addresses which can't be verified
+    Invalid,
+    /// Also return addresses which match non-postal data; requires a suitable license
+    Enhanced,
+}
+
+impl MatchStrategy {
+    fn as_str(self) -> &'static str {
+        match self {
+            MatchStrategy::Strict => "strict",
+            MatchStrategy::Invalid => "invalid",
+            MatchStrategy::Enhanced => "enhanced",
+        }
+    }
+}
+
+/// An instance of a parameter builder for Smarty address lookups
+///
+/// A complete address may be given as `street` alone; otherwise it should be combined with
+/// a `city` and `state`, or a `zipcode`.
+pub struct SmartyLookup<'a> {
+    street: &'a str,
+    secondary: Option<&'a str>,
+    city: Option<&'a str>,
+    state: Option<&'a str>,
+    zipcode: Option<&'a str>,
+    candidates: Option<u8>,
+    match_strategy: Option<MatchStrategy>,
+}
+
+impl<'a> SmartyLookup<'a> {
+    /// Create a new Smarty lookup builder
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::smarty::{MatchStrategy, SmartyLookup};
+    ///
+    /// let lookup = SmartyLookup::new(&"1600 Amphitheatre Pkwy")
+    ///     .with_city(&"Mountain View")
+    ///     .with_state(&"CA")
+    ///     .with_match_strategy(MatchStrategy::Invalid)
+    ///     .build();
+    /// ```
+    pub fn new(street: &'a str) -> SmartyLookup<'a> {
+        SmartyLookup {
+            street,
+            secondary: None,
+            city: None,
+            state: None,
+            zipcode: None,
+            candidates: None,
+            match_strategy: None,
+        }
+    }
+
+    /// Set the `secondary` property, e.g. an apartment or suite number
+    pub fn with_secondary(&mut self, secondary: &'a str) -> &mut Self {
+        self.secondary = Some(secondary);
+        self
+    }
+
+    /// Set the `city` property
+    pub fn with_city(&mut self, city: &'a str) -> &mut Self {
+        self.city = Some(city);
+        self
+    }
+
+    /// Set the `state` property, as a name or abbreviation
+    pub fn with_state(&mut self, state: &'a str) -> &mut Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Set the `zipcode` property, as a 5-digit ZIP Code or ZIP+4
+    pub fn with_zipcode(&mut self, zipcode: &'a str) -> &mut Self {
+        self.zipcode = Some(zipcode);
+        self
+    }
+
+    /// Set the `candidates` property, the maximum number of candidates to return (1 by default, at most 10)
+    pub fn with_candidates(&mut self, candidates: u8) -> &mut Self {
+        self.candidates = Some(candidates);
+        self
+    }
+
+    /// Set the `match` property
+    pub fn with_match_strategy(&mut self, match_strategy: MatchStrategy) -> &mut Self {
+        self.match_strategy = Some(match_strategy);
+        self
+    }
+
+    /// Build and return an instance of SmartyLookup
+    pub fn build(&self) -> SmartyLookup<'a> {
+        SmartyLookup {
+            street: self.street,
+            secondary: self.secondary,
+            city: self.city,
+            state: self.state,
+            zipcode: self.zipcode,
+            candidates: self.candidates,
+            match_strategy: self.match_strategy,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("street", self.street.to_string())];
+        if let Some(secondary) = self.secondary {
+            query.push(("secondary", secondary.to_string()));
+        }
+        if let Some(city) = self.city {
+            query.push(("city", city.to_string()));
+        }
+        if let Some(state) = self.state {
+            query.push(("state", state.to_string()));
+        }
+        if let Some(zipcode) = self.zipcode {
+            query.push(("zipcode", zipcode.to_string()));
+        }
+        if let Some(candidates) = self.candidates {
+            query.push(("candidates", candidates.to_string()));
+        }
+        if let Some(match_strategy) = self.match_strategy {
+            query.push(("match", match_strategy.as_str().to_string()));
+        }
+        query
+    }
+}
+
+impl Smarty {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "Smarty",
+        docs_url: "https://www.smarty.com/docs/cloud/us-street-api",
+        terms_url: Some("https://www.smarty.com/legal/terms-of-service"),
+        attribution: None,
+        rate_limit: None,
+    };
+
+    /// Create a new Smarty geocoding instance using a secret key pair
+    pub fn new(auth_id: String, auth_token: String) -> Self {
+        Smarty::from_credentials(Credentials::Secret {
+            auth_id,
+            auth_token,
+        })
+    }
+
+    /// Create a new Smarty geocoding instance using an embedded key
+    ///
+    /// Embedded keys are only accepted from the hosts they were created for, which are
+    /// matched against the `Referer` header, e.g. `https://example.com`.
+    pub fn new_embedded(key: String, referer: String) -> Self {
+        Smarty::from_credentials(Credentials::Embedded { key, referer })
+    }
+
+    fn from_credentials(credentials: Credentials) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Smarty {
+            credentials,
+            license: None,
+            client,
+            endpoint: "https://us-street.api.smarty.com/".to_string(),
+        }
+    }
+
+    /// Set the license to use, e.g. `us-rooftop-geocoding-cloud` for rooftop-level coordinates
+    pub fn with_license(mut self, license: &str) -> Self {
+        self.license = Some(license.to_owned());
+        self
+    }
+
+    /// Set a custom endpoint of a Smarty geocoding instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://us-street.api.smarty.com/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// Verify an address, returning its candidates
+    ///
+    /// An empty list means the address couldn't be verified.
+    ///
+    /// Please see [the documentation](https://www.smarty.com/docs/cloud/us-street-api) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Smarty;
+    /// use geocoding::smarty::{Candidate, SmartyLookup};
+    ///
+    /// let smarty = Smarty::new("your-auth-id".to_string(), "your-auth-token".to_string());
+    /// let lookup = SmartyLookup::new(&"1600 Amphitheatre Pkwy")
+    ///     .with_zipcode(&"94043")
+    ///     .build();
+    /// let res: Result<Vec<Candidate<f64>>, _> = smarty.forward_full(&lookup);
+    /// ```
+    pub fn forward_full<T>(
+        &self,
+        lookup: &SmartyLookup,
+    ) -> Result<Vec<Candidate<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = lookup.as_query();
+        if let Some(license) = &self.license {
+            query.push(("license", license.clone()));
+        }
+        let mut req = self.client.get(format!("{}street-address", self.endpoint));
+        match &self.credentials {
+            Credentials::Secret {
+                auth_id,
+                auth_token,
+            } => {
+                query.push(("auth-id", auth_id.clone()));
+                query.push(("auth-token", auth_token.clone()));
+            }
+            Credentials::Embedded { key, referer } => {
+                query.push(("key", key.clone()));
+                req = req.header(REFERER, referer);
+            }
+        }
+        let resp = req.query(&query).send()?.error_for_status()?;
+        let res: Vec<Candidate<T>> = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl<T> Forward<T> for Smarty
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of a complete address, returning the location of its
+    /// verified candidate, if any.
+    /// Please see [the documentation](https://www.smarty.com/docs/cloud/us-street-api) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(&SmartyLookup::new(place))?;
+        Ok(res
+            .iter()
+            .filter_map(|candidate| candidate.metadata.point())
+            .collect())
+    }
+}
+
+/// A verified address
+///
+/// See [the documentation](https://www.smarty.com/docs/cloud/us-street-api#http-response-output) for more details
+///
+///```json
+///{
+///  "input_index": 0,
+///  "candidate_index": 0,
+///  "delivery_line_1": "1600 Amphitheatre Pkwy",
+///  "last_line": "Mountain View CA 94043-1351",
+///  "delivery_point_barcode": "940431351000",
+///  "components": {
+///    "primary_number": "1600",
+///    "street_name": "Amphitheatre",
+///    "street_suffix": "Pkwy",
+///    "city_name": "Mountain View",
+///    "default_city_name": "Mountain View",
+///    "state_abbreviation": "CA",
+///    "zipcode": "94043",
+///    "plus4_code": "1351",
+///    "delivery_point": "00",
+///    "delivery_point_check_digit": "0"
+///  },
+///  "metadata": {
+///    "record_type": "S",
+///    "zip_type": "Standard",
+///    "county_fips": "06085",
+///    "county_name": "Santa Clara",
+///    "carrier_route": "C909",
+///    "congressional_district": "18",
+///    "rdi": "Commercial",
+///    "latitude": 37.42357,
+///    "longitude": -122.08661,
+///    "precision": "Zip9",
+///    "time_zone": "Pacific",
+///    "utc_offset": -8,
+///    "dst": true
+///  },
+///  "analysis": {
+///    "dpv_match_code": "Y",
+///    "dpv_footnotes": "AABB",
+///    "dpv_cmra": "N",
+///    "dpv_vacant": "N",
+///    "dpv_no_stat": "N",
+///    "active": "Y"
+///  }
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Candidate<T>
+where
+    T: Float + Debug,
+{
+    pub input_index: usize,
+    pub candidate_index: usize,
+    pub addressee: Option<String>,
+    pub delivery_line_1: String,
+    pub delivery_line_2: Option<String>,
+    pub last_line: String,
+    pub delivery_point_barcode: String,
+    pub components: Components,
+    pub metadata: Metadata<T>,
+    pub analysis: Analysis,
+}
+
+/// The components of a verified address
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Components {
+    pub urbanization: Option<String>,
+    pub primary_number: Option<String>,
+    pub street_name: Option<String>,
+    pub street_predirection: Option<String>,
+    pub street_postdirection: Option<String>,
+    pub street_suffix: Option<String>,
+    pub secondary_number: Option<String>,
+    pub secondary_designator: Option<String>,
+    pub pmb_designator: Option<String>,
+    pub pmb_number: Option<String>,
+    pub city_name: Option<String>,
+    /// The USPS preferred city name
+    pub default_city_name: Option<String>,
+    pub state_abbreviation: Option<String>,
+    pub zipcode: Option<String>,
+    pub plus4_code: Option<String>,
+    pub delivery_point: Option<String>,
+    pub delivery_point_check_digit: Option<String>,
+}
+
+/// Metadata about a verified address, including its location
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Metadata<T>
+where
+    T: Float + Debug,
+{
+    /// The USPS record type, e.g. `S` for street or `H` for highrise
+    pub record_type: Option<String>,
+    pub zip_type: Option<String>,
+    /// The 5-digit county FIPS code
+    pub county_fips: Option<String>,
+    pub county_name: Option<String>,
+    pub carrier_route: Option<String>,
+    pub congressional_district: Option<String>,
+    /// The Residential Delivery Indicator, `Residential` or `Commercial`
+    pub rdi: Option<String>,
+    pub latitude: Option<T>,
+    pub longitude: Option<T>,
+    pub precision: Option<Precision>,
+    pub time_zone: Option<String>,
+    pub utc_offset: Option<f64>,
+    pub dst: Option<bool>,
+}
+
+impl<T> Metadata<T>
+where
+    T: Float + Debug,
+{
+    /// The location of the address, if known
+    pub fn point(&self) -> Option<Point<T>> {
+        match (self.longitude, self.latitude, self.precision) {
+            (_, _, Some(Precision::Unknown)) => None,
+            (Some(longitude), Some(latitude), _) => Some(Point::new(longitude, latitude)),
+            _ => None,
+        }
+    }
+}
+
+/// The precision of a location, from least to most precise
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Precision {
+    Unknown,
+    /// The centroid of the 5-digit ZIP Code
+    Zip5,
+    /// The centroid of the ZIP+2 area
+    Zip6,
+    /// The centroid of the ZIP+3 area
+    Zip7,
+    /// The centroid of the ZIP+4 area
+    Zip8,
+    /// The centroid of the ZIP+4 delivery area
+    Zip9,
+    /// Interpolated along the street segment
+    Street,
+    /// The parcel centroid
+    Parcel,
+    /// The rooftop, with the rooftop license
+    Rooftop,
+}
+
+/// The delivery point validation results of a verified address
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Analysis {
+    /// Whether the address is deliverable: `Y` (confirmed), `S` (confirmed by dropping the
+    /// secondary number), `D` (confirmed, but missing a secondary number), or `N`
+    pub dpv_match_code: Option<String>,
+    /// The DPV footnotes, as consecutive two-character codes
+    pub dpv_footnotes: Option<String>,
+    /// Whether the address is a Commercial Mail Receiving Agency
+    pub dpv_cmra: Option<String>,
+    pub dpv_vacant: Option<String>,
+    /// Whether the address doesn't receive mail
+    pub dpv_no_stat: Option<String>,
+    /// Whether the address is active
+    pub active: Option<String>,
+    pub footnotes: Option<String>,
+    pub lacslink_code: Option<String>,
+    pub suitelink_match: Option<bool>,
+    pub enhanced_match: Option<String>,
+}
+
+impl Analysis {
+    /// Whether the address was confirmed as deliverable
+    pub fn is_deliverable(&self) -> bool {
+        matches!(self.dpv_match_code.as_deref(), Some("Y" | "S" | "D"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn candidate_test() {
+        let res: Vec<Candidate<f64>> = serde_json::from_str(
+            r#"[
+                {
+                    "input_index": 0, "candidate_index": 0,
+                    "delivery_line_1": "1600 Amphitheatre Pkwy",
+                    "last_line": "Mountain View CA 94043-1351",
+                    "delivery_point_barcode": "940431351000",
+                    "components": {
+                        "primary_number": "1600", "street_name": "Amphitheatre",
+                        "street_suffix": "Pkwy", "city_name": "Mountain View",
+                        "default_city_name": "Mountain View", "state_abbreviation": "CA",
+                        "zipcode": "94043", "plus4_code": "1351",
+                        "delivery_point": "00", "delivery_point_check_digit": "0"
+                    },
+                    "metadata": {
+                        "record_type": "S", "zip_type": "Standard", "county_fips": "06085",
+                        "county_name": "Santa Clara", "carrier_route": "C909",
+                        "congressional_district": "18", "rdi": "Commercial",
+                        "elot_sequence": "0107", "elot_sort": "A",
+                        "latitude": 37.42357, "longitude": -122.08661, "precision": "Zip9",
+                        "time_zone": "Pacific", "utc_offset": -8, "dst": true
+                    },
+                    "analysis": {
+                        "dpv_match_code": "Y", "dpv_footnotes": "AABB", "dpv_cmra": "N",
+                        "dpv_vacant": "N", "dpv_no_stat": "N", "active": "Y"
+                    }
+                }
+            ]"#,
+        )
+        .unwrap();
+        let candidate = &res[0];
+        assert!(candidate.analysis.is_deliverable());
+        assert_eq!(candidate.metadata.precision, Some(Precision::Zip9));
+        assert_eq!(
+            candidate.metadata.point(),
+            Some(Point::new(-122.08661, 37.42357))
+        );
+    }
+}
@@ -0,0 +1,344 @@
+//! The [Tencent Maps](https://lbs.qq.com/) provider.
+//!
+//! Geocoding methods are implemented on the [`Tencent`](struct.Tencent.html) struct, using the
+//! [WebService API](https://lbs.qq.com/service/webService/webServiceGuide/webServiceOverview)
+//! geocoder. A key is required.
+//!
+//! ### A Note on Coordinate Systems
+//! Tencent uses the GCJ-02 coordinate system. The `Forward` and `Reverse` implementations convert
+//! to and from WGS84, like every other provider; the full responses returned by
+//! [`forward_full`](struct.Tencent.html#method.forward_full) and
+//! [`reverse_full`](struct.Tencent.html#method.reverse_full) are left in GCJ-02, and may be
+//! converted using the [`transform`](../transform/index.html) helpers.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, Point, Tencent};
+//!
+//! let tencent = Tencent::new("your-key".to_string());
+//! let address = "北京市海淀区彩和坊路海淀西大街74号";
+//! let res: Result<Vec<Point<f64>>, _> = tencent.forward(address);
+//! ```
+use crate::info::{ProviderInfo, RateLimit};
+use crate::transform::{gcj02_to_wgs84, wgs84_to_gcj02};
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use std::fmt::Debug;
+
+/// An instance of the Tencent Maps geocoding service
+pub struct Tencent {
+    api_key: String,
+    client: Client,
+    endpoint: String,
+}
+
+impl Tencent {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "Tencent Maps",
+        docs_url: "https://lbs.qq.com/service/webService/webServiceGuide/webServiceGeocoder",
+        terms_url: Some("https://lbs.qq.com/userAgreements/agreements/terms"),
+        attribution: Some("© Tencent"),
+        rate_limit: Some(RateLimit::per_second(5)),
+    };
+
+    /// Create a new Tencent geocoding instance using the default endpoint
+    pub fn new(api_key: String) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Tencent {
+            api_key,
+            client,
+            endpoint: "https://apis.map.qq.com/ws/geocoder/v1/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of a Tencent geocoding instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://apis.map.qq.com/ws/geocoder/v1/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A forward-geocoding lookup of an address, returning a full detailed response
+    ///
+    /// Coordinates in the response are in GCJ-02. Returns `None` if the address couldn't be found.
+    ///
+    /// Please see [the documentation](https://lbs.qq.com/service/webService/webServiceGuide/webServiceGeocoder) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Tencent;
+    /// use geocoding::tencent::TencentResponse;
+    ///
+    /// let tencent = Tencent::new("your-key".to_string());
+    /// let res: Result<Option<TencentResponse<f64>>, _> = tencent.forward_full("海淀西大街74号");
+    /// ```
+    pub fn forward_full<T>(
+        &self,
+        address: &str,
+    ) -> Result<Option<TencentResponse<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let query = vec![("address", address.to_string())];
+        let res: TencentReply<TencentResponse<T>> = self.get(query)?;
+        match res.status {
+            0 => Ok(res.result),
+            NO_RESULT => Ok(None),
+            _ => Err(GeocodingError::Forward),
+        }
+    }
+
+    /// A reverse lookup of a GCJ-02 point, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://lbs.qq.com/service/webService/webServiceGuide/webServiceGcoder) for details.
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+    ) -> Result<Option<TencentReverseResponse<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        // Tencent expects lat, lng order
+        let query = vec![(
+            "location",
+            format!(
+                "{},{}",
+                point.y().to_f64().unwrap(),
+                point.x().to_f64().unwrap()
+            ),
+        )];
+        let res: TencentReply<TencentReverseResponse<T>> = self.get(query)?;
+        match res.status {
+            0 => Ok(res.result),
+            NO_RESULT => Ok(None),
+            _ => Err(GeocodingError::Reverse),
+        }
+    }
+
+    // Tencent reports errors with a 200 status and a non-zero `status`
+    fn get<R>(&self, mut query: Vec<(&'static str, String)>) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        query.push(("key", self.api_key.clone()));
+        let resp = self
+            .client
+            .get(&self.endpoint)
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+}
+
+// The status of requests which are valid but match nothing
+const NO_RESULT: i32 = 347;
+
+impl<T> Forward<T> for Tencent
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of an address, returning a WGS84 point.
+    /// Please see [the documentation](https://lbs.qq.com/service/webService/webServiceGuide/webServiceGeocoder) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(place)?;
+        Ok(res
+            .into_iter()
+            .map(|result| gcj02_to_wgs84(&Point::new(result.location.lng, result.location.lat)))
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for Tencent
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a WGS84 point, returning its `address`.
+    /// Please see [the documentation](https://lbs.qq.com/service/webService/webServiceGuide/webServiceGcoder) for details.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(&wgs84_to_gcj02(point))?;
+        Ok(res.map(|result| result.address))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TencentReply<R> {
+    status: i32,
+    result: Option<R>,
+}
+
+/// The `result` of a geocoding request
+///
+/// See [the documentation](https://lbs.qq.com/service/webService/webServiceGuide/webServiceGeocoder) for more details
+///
+///```json
+///{
+///  "title": "海淀西大街74号",
+///  "location": { "lng": 116.307015, "lat": 39.982915 },
+///  "ad_info": { "adcode": "110108" },
+///  "address_components": {
+///    "province": "北京市",
+///    "city": "北京市",
+///    "district": "海淀区",
+///    "street": "海淀西大街",
+///    "street_number": "海淀西大街74号"
+///  },
+///  "similarity": 0.8,
+///  "deviation": 1000,
+///  "reliability": 7,
+///  "level": 9
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TencentResponse<T>
+where
+    T: Float + Debug,
+{
+    pub title: String,
+    /// The location, in GCJ-02
+    pub location: LngLat<T>,
+    pub ad_info: AdInfo,
+    pub address_components: AddressComponents,
+    pub similarity: Option<f64>,
+    /// The estimated error, in meters
+    pub deviation: Option<i32>,
+    /// How reliable the result is, from 1 to 10; 7 or more is considered accurate
+    pub reliability: u8,
+    /// The precision of the result, from 1 (country) to 11 (house number)
+    pub level: Option<u8>,
+}
+
+/// A GCJ-02 coordinate pair
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LngLat<T>
+where
+    T: Float + Debug,
+{
+    pub lng: T,
+    pub lat: T,
+}
+
+/// Administrative division information
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdInfo {
+    /// The administrative division code
+    pub adcode: String,
+    pub nation_code: Option<String>,
+    pub city_code: Option<String>,
+    /// The administrative divisions, comma-separated
+    pub name: Option<String>,
+}
+
+/// The structured address of a result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddressComponents {
+    pub nation: Option<String>,
+    pub province: String,
+    pub city: String,
+    pub district: String,
+    pub street: String,
+    pub street_number: String,
+}
+
+/// The `result` of a reverse geocoding request
+///
+/// See [the documentation](https://lbs.qq.com/service/webService/webServiceGuide/webServiceGcoder) for more details
+///
+///```json
+///{
+///  "location": { "lat": 39.984154, "lng": 116.30749 },
+///  "address": "北京市海淀区北四环西路66号",
+///  "formatted_addresses": { "recommend": "海淀区中关村中国技术交易大厦", "rough": "海淀区中关村中国技术交易大厦" },
+///  "address_component": {
+///    "nation": "中国",
+///    "province": "北京市",
+///    "city": "北京市",
+///    "district": "海淀区",
+///    "street": "北四环西路",
+///    "street_number": "北四环西路66号"
+///  },
+///  "ad_info": { "nation_code": "156", "adcode": "110108", "city_code": "156110000", "name": "中国,北京市,北京市,海淀区" }
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TencentReverseResponse<T>
+where
+    T: Float + Debug,
+{
+    /// The queried location, in GCJ-02
+    pub location: LngLat<T>,
+    pub address: String,
+    pub formatted_addresses: Option<FormattedAddresses>,
+    pub address_component: AddressComponents,
+    pub ad_info: AdInfo,
+}
+
+/// Alternative descriptions of a reverse geocoding result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FormattedAddresses {
+    /// A description relative to a nearby landmark
+    pub recommend: String,
+    pub rough: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn forward_response_test() {
+        let res: TencentReply<TencentResponse<f64>> = serde_json::from_str(
+            r#"{
+                "status": 0,
+                "message": "query ok",
+                "result": {
+                    "title": "海淀西大街74号",
+                    "location": { "lng": 116.307015, "lat": 39.982915 },
+                    "ad_info": { "adcode": "110108" },
+                    "address_components": {
+                        "province": "北京市",
+                        "city": "北京市",
+                        "district": "海淀区",
+                        "street": "海淀西大街",
+                        "street_number": "海淀西大街74号"
+                    },
+                    "similarity": 0.8,
+                    "deviation": 1000,
+                    "reliability": 7,
+                    "level": 9
+                }
+            }"#,
+        )
+        .unwrap();
+        let result = res.result.unwrap();
+        assert_eq!(result.reliability, 7);
+        assert_eq!(result.ad_info.adcode, "110108");
+    }
+
+    #[test]
+    fn no_result_test() {
+        let res: TencentReply<TencentResponse<f64>> =
+            serde_json::from_str(r#"{ "status": 347, "message": "查询无结果" }"#).unwrap();
+        assert_eq!(res.status, NO_RESULT);
+        assert!(res.result.is_none());
+    }
+}
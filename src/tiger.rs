@@ -0,0 +1,589 @@
+//! An offline US street address geocoder, interpolating Census TIGER/Line address ranges.
+//!
+//! The [`Tiger`](struct.Tiger.html) provider indexes the streets of
+//! [TIGER/Line](https://www.census.gov/geographies/mapping-files/time-series/geo/tiger-line-file.html)
+//! `EDGES` shapefiles, published by the US Census Bureau for each county (e.g.
+//! `tl_2023_11001_edges.zip` for the District of Columbia). Each street edge carries the ranges
+//! of house numbers on its left and right sides; a house number is located by interpolating its
+//! position within the range along the edge. Results lie on the street centreline, and are as
+//! accurate as the ranges' assumption of evenly spaced numbers, which suits bulk geocoding
+//! without per-request costs.
+//!
+//! The files are in the public domain, and must be downloaded and unzipped: the `.shp` and
+//! `.dbf` files of each county are read. Addresses are given as `number street[, city, state zip]`;
+//! street names are matched after abbreviating common street types and directions, as in
+//! TIGER/Line (e.g. `Pennsylvania Avenue Northwest` matches `Pennsylvania Ave NW`).
+//!
+//! This module is only available with the `tiger` feature.
+//!
+//! ### Example
+//!
+//! ```no_run
+//! use geocoding::{Forward, Point, Tiger};
+//!
+//! let tiger = Tiger::from_path("tl_2023_11001_edges.shp").unwrap();
+//! let res: Vec<Point<f64>> = tiger
+//!     .forward("1600 Pennsylvania Ave NW, Washington, DC 20500")
+//!     .unwrap();
+//! ```
+use crate::info::ProviderInfo;
+use crate::Coord;
+use crate::Forward;
+use crate::GeocodingError;
+use crate::Point;
+use num_traits::Float;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// An instance of the offline TIGER/Line geocoder
+#[derive(Default)]
+pub struct Tiger {
+    edges: Vec<Edge>,
+    index: HashMap<String, Vec<usize>>,
+}
+
+/// A street edge, between two intersections
+#[derive(Clone, Debug, PartialEq)]
+pub struct Edge {
+    /// The TIGER/Line identifier
+    pub tlid: u64,
+    /// The full street name, e.g. `Pennsylvania Ave NW`
+    pub name: String,
+    pub left: Option<AddressRange>,
+    pub right: Option<AddressRange>,
+    pub zip_left: String,
+    pub zip_right: String,
+    /// The street centreline, from the `from` end of the ranges to the `to` end
+    pub line: Vec<Coord<f64>>,
+}
+
+/// The house numbers on one side of an edge
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AddressRange {
+    /// The house number at the start of the edge
+    pub from: u32,
+    /// The house number at the end of the edge
+    pub to: u32,
+}
+
+impl AddressRange {
+    /// Whether the range includes a house number
+    ///
+    /// Ranges hold either even or odd numbers, so the number must also share their parity.
+    pub fn contains(&self, number: u32) -> bool {
+        let (low, high) = (self.from.min(self.to), self.from.max(self.to));
+        (low..=high).contains(&number)
+            && (self.from % 2 != self.to % 2 || number % 2 == self.from % 2)
+    }
+
+    // The position of a house number along the range, between 0 and 1
+    fn fraction(&self, number: u32) -> f64 {
+        if self.from == self.to {
+            return 0.5;
+        }
+        (f64::from(number) - f64::from(self.from)) / (f64::from(self.to) - f64::from(self.from))
+    }
+}
+
+/// The side of an edge, looking from its start to its end
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// A house number located on an edge
+#[derive(Debug)]
+pub struct TigerMatch<'a, T>
+where
+    T: Float + Debug,
+{
+    pub edge: &'a Edge,
+    pub side: Side,
+    /// The interpolated location, on the street centreline
+    pub point: Point<T>,
+}
+
+impl Tiger {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "TIGER/Line (offline)",
+        docs_url: "https://www.census.gov/programs-surveys/geography/technical-documentation/complete-technical-documentation/tiger-geo-line.html",
+        terms_url: None,
+        attribution: None,
+        rate_limit: None,
+    };
+
+    /// Create an empty geocoder, to which counties are added
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a county's `EDGES` shapefile, given the path of its `.shp` file
+    pub fn from_path<P>(path: P) -> Result<Self, GeocodingError>
+    where
+        P: AsRef<Path>,
+    {
+        let mut tiger = Self::new();
+        tiger.add_path(path)?;
+        Ok(tiger)
+    }
+
+    /// Add a county's `EDGES` shapefile, given the path of its `.shp` file
+    ///
+    /// The `.dbf` file is expected next to it.
+    pub fn add_path<P>(&mut self, path: P) -> Result<(), GeocodingError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let shp = File::open(path)?;
+        let dbf = File::open(path.with_extension("dbf"))?;
+        self.add_reader(shp, dbf)
+    }
+
+    /// Add a county's `EDGES` shapefile, from readers of its `.shp` and `.dbf` files
+    pub fn add_reader<S, D>(&mut self, mut shp: S, mut dbf: D) -> Result<(), GeocodingError>
+    where
+        S: Read,
+        D: Read,
+    {
+        let mut shp_bytes = vec![];
+        shp.read_to_end(&mut shp_bytes)?;
+        let mut dbf_bytes = vec![];
+        dbf.read_to_end(&mut dbf_bytes)?;
+        let lines = read_shp(&shp_bytes)?;
+        let records = read_dbf(&dbf_bytes)?;
+        if lines.len() != records.len() {
+            return Err(invalid_data("shapes and records don't match").into());
+        }
+        for (line, record) in lines.into_iter().zip(records) {
+            let (line, record) = match (line, record) {
+                (Some(line), Some(record)) => (line, record),
+                _ => continue,
+            };
+            let field = |name: &str| record.get(name).cloned().unwrap_or_default();
+            let range = |from: &str, to: &str| {
+                Some(AddressRange {
+                    from: field(from).parse().ok()?,
+                    to: field(to).parse().ok()?,
+                })
+            };
+            let edge = Edge {
+                tlid: field("TLID").parse().unwrap_or_default(),
+                name: field("FULLNAME"),
+                left: range("LFROMADD", "LTOADD"),
+                right: range("RFROMADD", "RTOADD"),
+                zip_left: field("ZIPL"),
+                zip_right: field("ZIPR"),
+                line,
+            };
+            if edge.name.is_empty() || (edge.left.is_none() && edge.right.is_none()) {
+                continue;
+            }
+            self.index
+                .entry(normalize_street(&edge.name))
+                .or_default()
+                .push(self.edges.len());
+            self.edges.push(edge);
+        }
+        Ok(())
+    }
+
+    /// The number of addressed edges loaded
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Whether no addressed edges were loaded
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// Locate a house number on a street, optionally within a ZIP code
+    pub fn lookup<T>(&self, number: u32, street: &str, zip: Option<&str>) -> Vec<TigerMatch<'_, T>>
+    where
+        T: Float + Debug,
+    {
+        let edges = match self.index.get(&normalize_street(street)) {
+            Some(edges) => edges,
+            None => return vec![],
+        };
+        let mut matches = vec![];
+        for edge in edges.iter().map(|&i| &self.edges[i]) {
+            let sides = [
+                (Side::Left, edge.left, &edge.zip_left),
+                (Side::Right, edge.right, &edge.zip_right),
+            ];
+            for (side, range, edge_zip) in sides {
+                let range = match range {
+                    Some(range) if range.contains(number) => range,
+                    _ => continue,
+                };
+                if zip.map_or(false, |zip| !edge_zip.is_empty() && edge_zip != zip) {
+                    continue;
+                }
+                if let Some(coord) = along(&edge.line, range.fraction(number)) {
+                    matches.push(TigerMatch {
+                        edge,
+                        side,
+                        point: Point::new(T::from(coord.x).unwrap(), T::from(coord.y).unwrap()),
+                    });
+                }
+            }
+        }
+        matches
+    }
+
+    /// Locate an address given as `number street[, city, state zip]`
+    pub fn forward_full<T>(&self, address: &str) -> Vec<TigerMatch<'_, T>>
+    where
+        T: Float + Debug,
+    {
+        match parse_address(address) {
+            Some((number, street, zip)) => self.lookup(number, street, zip),
+            None => vec![],
+        }
+    }
+}
+
+impl<T> Forward<T> for Tiger
+where
+    T: Float + Debug,
+{
+    /// A forward-geocoding lookup of a US street address, returning the interpolated location on
+    /// each matching edge.
+    fn forward(&self, address: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        Ok(self
+            .forward_full(address)
+            .into_iter()
+            .map(|found| found.point)
+            .collect())
+    }
+}
+
+// Split an address into its house number, street and ZIP code
+fn parse_address(address: &str) -> Option<(u32, &str, Option<&str>)> {
+    let (street, rest) = match address.find(',') {
+        Some(comma) => (&address[..comma], &address[comma + 1..]),
+        None => (address, ""),
+    };
+    let street = street.trim();
+    let split = street.find(char::is_whitespace)?;
+    let number: String = street[..split]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    let zip = rest.split_whitespace().rev().find_map(|token| {
+        let zip = token.get(..5)?;
+        let plus4 = &token[5..];
+        let is_zip =
+            zip.chars().all(|c| c.is_ascii_digit()) && (plus4.is_empty() || plus4.starts_with('-'));
+        if is_zip {
+            Some(zip)
+        } else {
+            None
+        }
+    });
+    Some((number.parse().ok()?, street[split..].trim(), zip))
+}
+
+// Uppercase a street name, abbreviating its type and direction as in TIGER/Line
+fn normalize_street(street: &str) -> String {
+    street
+        .split(|c: char| c.is_whitespace() || c == '.' || c == ',')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let word = word.to_uppercase();
+            ABBREVIATIONS
+                .iter()
+                .find(|(full, _)| *full == word)
+                .map_or(word, |(_, abbreviation)| abbreviation.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+static ABBREVIATIONS: &[(&str, &str)] = &[
+    ("ALLEY", "ALY"),
+    ("AVENUE", "AVE"),
+    ("BOULEVARD", "BLVD"),
+    ("CIRCLE", "CIR"),
+    ("COURT", "CT"),
+    ("DRIVE", "DR"),
+    ("EXPRESSWAY", "EXPY"),
+    ("FREEWAY", "FWY"),
+    ("HIGHWAY", "HWY"),
+    ("LANE", "LN"),
+    ("PARKWAY", "PKWY"),
+    ("PLACE", "PL"),
+    ("ROAD", "RD"),
+    ("SQUARE", "SQ"),
+    ("STREET", "ST"),
+    ("TERRACE", "TER"),
+    ("TRAIL", "TRL"),
+    ("NORTH", "N"),
+    ("SOUTH", "S"),
+    ("EAST", "E"),
+    ("WEST", "W"),
+    ("NORTHEAST", "NE"),
+    ("NORTHWEST", "NW"),
+    ("SOUTHEAST", "SE"),
+    ("SOUTHWEST", "SW"),
+];
+
+// The point at a fraction of a line's length, measuring longitudes as shorter away from the
+// equator
+fn along(line: &[Coord<f64>], fraction: f64) -> Option<Coord<f64>> {
+    let first = line.first()?;
+    let scale = first.y.to_radians().cos();
+    let length = |a: &Coord<f64>, b: &Coord<f64>| ((b.x - a.x) * scale).hypot(b.y - a.y);
+    let total: f64 = line.windows(2).map(|pair| length(&pair[0], &pair[1])).sum();
+    let mut remaining = total * fraction.clamp(0.0, 1.0);
+    for pair in line.windows(2) {
+        let segment = length(&pair[0], &pair[1]);
+        if segment > 0.0 && remaining <= segment {
+            let t = remaining / segment;
+            return Some(Coord {
+                x: pair[0].x + (pair[1].x - pair[0].x) * t,
+                y: pair[0].y + (pair[1].y - pair[0].y) * t,
+            });
+        }
+        remaining -= segment;
+    }
+    line.last().copied()
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn slice(bytes: &[u8], offset: usize, len: usize) -> io::Result<&[u8]> {
+    offset
+        .checked_add(len)
+        .and_then(|end| bytes.get(offset..end))
+        .ok_or_else(|| invalid_data("unexpected end of file"))
+}
+
+// A length or count read from a file, which a corrupt file may give as negative
+fn to_usize(value: i32) -> io::Result<usize> {
+    usize::try_from(value).map_err(|_| invalid_data("negative length"))
+}
+
+fn i32_be(bytes: &[u8], offset: usize) -> io::Result<i32> {
+    let mut buf = [0; 4];
+    buf.copy_from_slice(slice(bytes, offset, 4)?);
+    Ok(i32::from_be_bytes(buf))
+}
+
+fn u16_le(bytes: &[u8], offset: usize) -> io::Result<u16> {
+    let mut buf = [0; 2];
+    buf.copy_from_slice(slice(bytes, offset, 2)?);
+    Ok(u16::from_le_bytes(buf))
+}
+
+fn i32_le(bytes: &[u8], offset: usize) -> io::Result<i32> {
+    let mut buf = [0; 4];
+    buf.copy_from_slice(slice(bytes, offset, 4)?);
+    Ok(i32::from_le_bytes(buf))
+}
+
+fn f64_le(bytes: &[u8], offset: usize) -> io::Result<f64> {
+    let mut buf = [0; 8];
+    buf.copy_from_slice(slice(bytes, offset, 8)?);
+    Ok(f64::from_le_bytes(buf))
+}
+
+// Read the polylines of a shapefile, with `None` for null shapes
+fn read_shp(bytes: &[u8]) -> io::Result<Vec<Option<Vec<Coord<f64>>>>> {
+    if i32_be(bytes, 0)? != 9994 {
+        return Err(invalid_data("not a shapefile"));
+    }
+    let mut lines = vec![];
+    let mut offset = 100;
+    while offset < bytes.len() {
+        // record lengths are in 16-bit words
+        let len = to_usize(i32_be(bytes, offset + 4)?)?
+            .checked_mul(2)
+            .ok_or_else(|| invalid_data("record too long"))?;
+        let content = slice(bytes, offset + 8, len)?;
+        offset += 8 + len;
+        match i32_le(content, 0)? {
+            0 => lines.push(None),
+            // PolyLine, PolyLineZ and PolyLineM shapes start with the same fields
+            3 | 13 | 23 => {
+                let num_parts = to_usize(i32_le(content, 36)?)?;
+                let num_points = to_usize(i32_le(content, 40)?)?;
+                let points = num_parts
+                    .checked_mul(4)
+                    .and_then(|parts| parts.checked_add(44))
+                    .ok_or_else(|| invalid_data("too many parts"))?;
+                let line = (0..num_points)
+                    .map(|i| {
+                        Ok(Coord {
+                            x: f64_le(content, points + 16 * i)?,
+                            y: f64_le(content, points + 16 * i + 8)?,
+                        })
+                    })
+                    .collect::<io::Result<_>>()?;
+                lines.push(Some(line));
+            }
+            _ => return Err(invalid_data("not a polyline shapefile")),
+        }
+    }
+    Ok(lines)
+}
+
+// Read the records of a dBase file, with `None` for deleted records
+fn read_dbf(bytes: &[u8]) -> io::Result<Vec<Option<HashMap<String, String>>>> {
+    let num_records = to_usize(i32_le(bytes, 4)?)?;
+    let header_len = u16_le(bytes, 8)? as usize;
+    let record_len = u16_le(bytes, 10)? as usize;
+    let mut fields = vec![];
+    let mut offset = 32;
+    while slice(bytes, offset, 1)?[0] != 0x0D {
+        let descriptor = slice(bytes, offset, 32)?;
+        let name = String::from_utf8_lossy(&descriptor[..11])
+            .trim_end_matches('\0')
+            .to_string();
+        fields.push((name, descriptor[16] as usize));
+        offset += 32;
+    }
+    (0..num_records)
+        .map(|i| {
+            let record = slice(bytes, header_len + i * record_len, record_len)?;
+            if record[0] == b'*' {
+                return Ok(None);
+            }
+            let mut values = HashMap::new();
+            let mut offset = 1;
+            for (name, len) in &fields {
+                let value = slice(record, offset, *len)?;
+                values.insert(
+                    name.clone(),
+                    String::from_utf8_lossy(value).trim().to_string(),
+                );
+                offset += len;
+            }
+            Ok(Some(values))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // A shapefile of one two-segment polyline
+    fn shp() -> Vec<u8> {
+        let points = [(-77.04, 38.90), (-77.035, 38.90), (-77.03, 38.90)];
+        let mut content = vec![];
+        content.extend_from_slice(&3i32.to_le_bytes());
+        content.extend_from_slice(&[0; 32]);
+        content.extend_from_slice(&1i32.to_le_bytes());
+        content.extend_from_slice(&(points.len() as i32).to_le_bytes());
+        content.extend_from_slice(&0i32.to_le_bytes());
+        for (x, y) in points {
+            content.extend_from_slice(&f64::to_le_bytes(x));
+            content.extend_from_slice(&f64::to_le_bytes(y));
+        }
+        let mut bytes = vec![0; 100];
+        bytes[..4].copy_from_slice(&9994i32.to_be_bytes());
+        bytes.extend_from_slice(&1i32.to_be_bytes());
+        bytes.extend_from_slice(&(content.len() as i32 / 2).to_be_bytes());
+        bytes.extend_from_slice(&content);
+        bytes
+    }
+
+    // A dBase file of one edge's attributes
+    fn dbf() -> Vec<u8> {
+        let fields = [
+            ("TLID", "76225813"),
+            ("FULLNAME", "Pennsylvania Ave NW"),
+            ("LFROMADD", "1501"),
+            ("LTOADD", "1599"),
+            ("RFROMADD", "1500"),
+            ("RTOADD", "1700"),
+            ("ZIPL", "20004"),
+            ("ZIPR", "20500"),
+        ];
+        let len = 20;
+        let mut bytes = vec![3, 124, 1, 1];
+        bytes.extend_from_slice(&1i32.to_le_bytes());
+        bytes.extend_from_slice(&(32 * fields.len() as u16 + 33).to_le_bytes());
+        bytes.extend_from_slice(&(len * fields.len() as u16 + 1).to_le_bytes());
+        bytes.extend_from_slice(&[0; 20]);
+        for (name, _) in fields {
+            let mut descriptor = [0; 32];
+            descriptor[..name.len()].copy_from_slice(name.as_bytes());
+            descriptor[11] = b'C';
+            descriptor[16] = len as u8;
+            bytes.extend_from_slice(&descriptor);
+        }
+        bytes.push(0x0D);
+        bytes.push(b' ');
+        for (_, value) in fields {
+            bytes.extend_from_slice(format!("{:<20}", value).as_bytes());
+        }
+        bytes.push(0x1A);
+        bytes
+    }
+
+    #[test]
+    fn interpolation_test() {
+        let mut tiger = Tiger::new();
+        tiger.add_reader(&shp()[..], &dbf()[..]).unwrap();
+        assert_eq!(tiger.len(), 1);
+        let res =
+            tiger.forward_full::<f64>("1600 Pennsylvania Avenue Northwest, Washington, DC 20500");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].edge.tlid, 76225813);
+        assert_eq!(res[0].side, Side::Right);
+        assert!((res[0].point.x() - -77.035).abs() < 1e-9);
+        assert!((res[0].point.y() - 38.90).abs() < 1e-9);
+        // Odd numbers are on the left side, in another ZIP code
+        assert!(tiger
+            .lookup::<f64>(1551, "Pennsylvania Ave NW", Some("20500"))
+            .is_empty());
+        assert_eq!(
+            tiger.lookup::<f64>(1551, "pennsylvania ave. nw", None)[0].side,
+            Side::Left
+        );
+        assert!(tiger
+            .forward_full::<f64>("1800 Pennsylvania Ave NW")
+            .is_empty());
+    }
+
+    #[test]
+    fn malformed_shp_test() {
+        let invalid = |bytes: &[u8]| matches!(read_shp(bytes), Err(err) if err.kind() == io::ErrorKind::InvalidData);
+        let mut bytes = shp();
+        bytes[104..108].copy_from_slice(&(-1i32).to_be_bytes());
+        assert!(invalid(&bytes));
+        let mut bytes = shp();
+        bytes[144..148].copy_from_slice(&(-1i32).to_le_bytes());
+        assert!(invalid(&bytes));
+        let mut bytes = shp();
+        bytes[148..152].copy_from_slice(&i32::MAX.to_le_bytes());
+        assert!(invalid(&bytes));
+        let mut tiger = Tiger::new();
+        assert!(matches!(
+            tiger.add_reader(&bytes[..], &dbf()[..]),
+            Err(GeocodingError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn parse_address_test() {
+        assert_eq!(
+            parse_address("1600 Pennsylvania Ave NW, Washington, DC 20500-0003"),
+            Some((1600, "Pennsylvania Ave NW", Some("20500")))
+        );
+        assert_eq!(parse_address("12B Main St"), Some((12, "Main St", None)));
+        assert_eq!(parse_address("Main St, Springfield"), None);
+    }
+}
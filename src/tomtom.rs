@@ -0,0 +1,703 @@
+//! The [TomTom Search API](https://developer.tomtom.com/search-api/documentation/product-information/introduction) provider.
+//!
+//! Geocoding methods are implemented on the [`TomTom`](struct.TomTom.html) struct, covering the
+//! fuzzy search, geocode, structured geocode and reverse geocode endpoints.
+//! An API key is required.
+//!
+//! ### A Note on Coordinate Order
+//! TomTom returns coordinates as `lat`, `lon` objects (or `"lat,lon"` strings for reverse geocoding).
+//! `Geocoding` returns `Point` coordinates in `[Longitude, Latitude]` `(x, y)` order.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, Point, TomTom};
+//!
+//! let tomtom = TomTom::new("your-api-key".to_string());
+//! let address = "De Ruijterkade 154, Amsterdam";
+//! let res: Result<Vec<Point<f64>>, _> = tomtom.forward(address);
+//! ```
+use crate::info::{ProviderInfo, RateLimit};
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use serde::Deserializer;
+use std::fmt::Debug;
+
+/// An instance of the TomTom Search API
+pub struct TomTom {
+    api_key: String,
+    client: Client,
+    endpoint: String,
+}
+
+/// An instance of a parameter builder for TomTom fuzzy search and geocode queries
+pub struct TomTomParams<'a, T>
+where
+    T: Float + Debug,
+{
+    query: &'a str,
+    limit: Option<u8>,
+    country_set: Option<&'a str>,
+    bias: Option<&'a Point<T>>,
+    radius: Option<u32>,
+    typeahead: bool,
+}
+
+impl<'a, T> TomTomParams<'a, T>
+where
+    T: Float + Debug,
+{
+    /// Create a new TomTom parameter builder
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::Point;
+    /// use geocoding::tomtom::TomTomParams;
+    ///
+    /// let bias = Point::new(4.8897, 52.3740);
+    /// let params = TomTomParams::new(&"De Ruijterkade")
+    ///     .with_country_set("NL")
+    ///     .with_bias(&bias, Some(10_000))
+    ///     .with_limit(5)
+    ///     .build();
+    /// ```
+    pub fn new(query: &'a str) -> TomTomParams<'a, T> {
+        TomTomParams {
+            query,
+            limit: None,
+            country_set: None,
+            bias: None,
+            radius: None,
+            typeahead: false,
+        }
+    }
+
+    /// Set the `limit` property, the maximum number of results to return
+    pub fn with_limit(&mut self, limit: u8) -> &mut Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Set the `countrySet` property, a comma-separated list of country codes
+    pub fn with_country_set(&mut self, country_set: &'a str) -> &mut Self {
+        self.country_set = Some(country_set);
+        self
+    }
+
+    /// Set the `lat`, `lon` and optionally the `radius` (in meters) properties,
+    /// biasing (or, with a radius, restricting) results around a point
+    pub fn with_bias(&mut self, bias: &'a Point<T>, radius: Option<u32>) -> &mut Self {
+        self.bias = Some(bias);
+        self.radius = radius;
+        self
+    }
+
+    /// Set the `typeahead` property, treating the query as partial input
+    pub fn with_typeahead(&mut self, typeahead: bool) -> &mut Self {
+        self.typeahead = typeahead;
+        self
+    }
+
+    /// Build and return an instance of TomTomParams
+    pub fn build(&self) -> TomTomParams<'a, T> {
+        TomTomParams {
+            query: self.query,
+            limit: self.limit,
+            country_set: self.country_set,
+            bias: self.bias,
+            radius: self.radius,
+            typeahead: self.typeahead,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![];
+        if let Some(limit) = self.limit {
+            query.push(("limit", limit.to_string()));
+        }
+        if let Some(country_set) = self.country_set {
+            query.push(("countrySet", country_set.to_string()));
+        }
+        if let Some(bias) = self.bias {
+            query.push(("lat", bias.y().to_f64().unwrap().to_string()));
+            query.push(("lon", bias.x().to_f64().unwrap().to_string()));
+        }
+        if let Some(radius) = self.radius {
+            query.push(("radius", radius.to_string()));
+        }
+        if self.typeahead {
+            query.push(("typeahead", String::from("true")));
+        }
+        query
+    }
+}
+
+/// An instance of a parameter builder for TomTom structured geocode queries
+pub struct TomTomStructuredParams<'a> {
+    country_code: &'a str,
+    street_number: Option<&'a str>,
+    street_name: Option<&'a str>,
+    municipality: Option<&'a str>,
+    postal_code: Option<&'a str>,
+    country_subdivision: Option<&'a str>,
+    limit: Option<u8>,
+}
+
+impl<'a> TomTomStructuredParams<'a> {
+    /// Create a new TomTom structured parameter builder for a country (ISO 3166-1 alpha-2 or alpha-3)
+    /// # Example:
+    ///
+    /// ```
+    /// use geocoding::tomtom::TomTomStructuredParams;
+    ///
+    /// let params = TomTomStructuredParams::new(&"NL")
+    ///     .with_street_name(&"De Ruijterkade")
+    ///     .with_street_number(&"154")
+    ///     .with_municipality(&"Amsterdam")
+    ///     .build();
+    /// ```
+    pub fn new(country_code: &'a str) -> TomTomStructuredParams<'a> {
+        TomTomStructuredParams {
+            country_code,
+            street_number: None,
+            street_name: None,
+            municipality: None,
+            postal_code: None,
+            country_subdivision: None,
+            limit: None,
+        }
+    }
+
+    /// Set the `streetNumber` property
+    pub fn with_street_number(&mut self, street_number: &'a str) -> &mut Self {
+        self.street_number = Some(street_number);
+        self
+    }
+
+    /// Set the `streetName` property
+    pub fn with_street_name(&mut self, street_name: &'a str) -> &mut Self {
+        self.street_name = Some(street_name);
+        self
+    }
+
+    /// Set the `municipality` property
+    pub fn with_municipality(&mut self, municipality: &'a str) -> &mut Self {
+        self.municipality = Some(municipality);
+        self
+    }
+
+    /// Set the `postalCode` property
+    pub fn with_postal_code(&mut self, postal_code: &'a str) -> &mut Self {
+        self.postal_code = Some(postal_code);
+        self
+    }
+
+    /// Set the `countrySubdivision` property
+    pub fn with_country_subdivision(&mut self, country_subdivision: &'a str) -> &mut Self {
+        self.country_subdivision = Some(country_subdivision);
+        self
+    }
+
+    /// Set the `limit` property, the maximum number of results to return
+    pub fn with_limit(&mut self, limit: u8) -> &mut Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Build and return an instance of TomTomStructuredParams
+    pub fn build(&self) -> TomTomStructuredParams<'a> {
+        TomTomStructuredParams {
+            country_code: self.country_code,
+            street_number: self.street_number,
+            street_name: self.street_name,
+            municipality: self.municipality,
+            postal_code: self.postal_code,
+            country_subdivision: self.country_subdivision,
+            limit: self.limit,
+        }
+    }
+
+    fn as_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("countryCode", self.country_code.to_string())];
+        let optional = [
+            ("streetNumber", self.street_number),
+            ("streetName", self.street_name),
+            ("municipality", self.municipality),
+            ("postalCode", self.postal_code),
+            ("countrySubdivision", self.country_subdivision),
+        ];
+        for (name, value) in optional {
+            if let Some(value) = value {
+                query.push((name, value.to_string()));
+            }
+        }
+        if let Some(limit) = self.limit {
+            query.push(("limit", limit.to_string()));
+        }
+        query
+    }
+}
+
+// TomTom takes free-form queries as a path segment, which must be percent-encoded
+fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+impl TomTom {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "TomTom",
+        docs_url:
+            "https://developer.tomtom.com/search-api/documentation/product-information/introduction",
+        terms_url: Some("https://developer.tomtom.com/terms-and-conditions"),
+        attribution: Some("© TomTom"),
+        rate_limit: Some(RateLimit::per_second(5)),
+    };
+
+    /// Create a new TomTom geocoding instance using the default endpoint
+    pub fn new(api_key: String) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        TomTom {
+            api_key,
+            client,
+            endpoint: "https://api.tomtom.com/search/2/".to_string(),
+        }
+    }
+
+    /// Set a custom endpoint of a TomTom geocoding instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://api.tomtom.com/search/2/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// A fuzzy search, matching addresses as well as POIs and geographies
+    ///
+    /// Please see [the documentation](https://developer.tomtom.com/search-api/documentation/search-service/fuzzy-search) for details.
+    pub fn fuzzy_search<T>(
+        &self,
+        params: &TomTomParams<T>,
+    ) -> Result<TomTomResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let path = format!("search/{}.json", encode_path_segment(params.query));
+        self.get(&path, params.as_query())
+    }
+
+    /// A forward-geocoding lookup of an address, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://developer.tomtom.com/search-api/documentation/geocoding-service/geocode) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::TomTom;
+    /// use geocoding::tomtom::{TomTomParams, TomTomResponse};
+    ///
+    /// let tomtom = TomTom::new("your-api-key".to_string());
+    /// let params = TomTomParams::new(&"De Ruijterkade 154, Amsterdam")
+    ///     .with_country_set("NL")
+    ///     .build();
+    /// let res: Result<TomTomResponse<f64>, _> = tomtom.forward_full(&params);
+    /// ```
+    pub fn forward_full<T>(
+        &self,
+        params: &TomTomParams<T>,
+    ) -> Result<TomTomResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let path = format!("geocode/{}.json", encode_path_segment(params.query));
+        self.get(&path, params.as_query())
+    }
+
+    /// A forward-geocoding lookup of an address given as separate components
+    ///
+    /// Please see [the documentation](https://developer.tomtom.com/search-api/documentation/geocoding-service/structured-geocode) for details.
+    pub fn structured_geocode<T>(
+        &self,
+        params: &TomTomStructuredParams,
+    ) -> Result<TomTomResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        self.get("structuredGeocode.json", params.as_query())
+    }
+
+    /// A reverse lookup of a point, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://developer.tomtom.com/search-api/documentation/reverse-geocoding-service/reverse-geocode) for details.
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+    ) -> Result<TomTomReverseResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        // TomTom expects lat, lon order
+        let path = format!(
+            "reverseGeocode/{},{}.json",
+            point.y().to_f64().unwrap(),
+            point.x().to_f64().unwrap()
+        );
+        self.get(&path, vec![])
+    }
+
+    fn get<R>(
+        &self,
+        path: &str,
+        mut query: Vec<(&'static str, String)>,
+    ) -> Result<R, GeocodingError>
+    where
+        for<'de> R: Deserialize<'de>,
+    {
+        query.push(("key", self.api_key.clone()));
+        let resp = self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl<T> Forward<T> for TomTom
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of an address, using the geocode endpoint.
+    /// Please see [the documentation](https://developer.tomtom.com/search-api/documentation/geocoding-service/geocode) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(&TomTomParams::new(place))?;
+        Ok(res
+            .results
+            .iter()
+            .map(|result| Point::new(result.position.lon, result.position.lat))
+            .collect())
+    }
+}
+
+impl<T> Reverse<T> for TomTom
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the `freeformAddress` of the nearest address.
+    /// Please see [the documentation](https://developer.tomtom.com/search-api/documentation/reverse-geocoding-service/reverse-geocode) for details.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point)?;
+        Ok(res
+            .addresses
+            .into_iter()
+            .next()
+            .and_then(|result| result.address.freeform_address))
+    }
+}
+
+/// The top-level full JSON response returned by search and geocode requests
+///
+/// See [the documentation](https://developer.tomtom.com/search-api/documentation/geocoding-service/geocode#response-data) for more details
+///
+///```json
+///{
+///  "summary": {
+///    "query": "de ruijterkade 154 amsterdam",
+///    "queryType": "NON_NEAR",
+///    "queryTime": 42,
+///    "numResults": 1,
+///    "offset": 0,
+///    "totalResults": 1,
+///    "fuzzyLevel": 1
+///  },
+///  "results": [
+///    {
+///      "type": "Point Address",
+///      "id": "NL/PAD/p0/1279484",
+///      "score": 12.0658950806,
+///      "matchConfidence": { "score": 1 },
+///      "address": {
+///        "streetNumber": "154",
+///        "streetName": "De Ruijterkade",
+///        "municipality": "Amsterdam",
+///        "countrySubdivision": "Noord-Holland",
+///        "postalCode": "1011",
+///        "extendedPostalCode": "1011 AC",
+///        "countryCode": "NL",
+///        "country": "Nederland",
+///        "countryCodeISO3": "NLD",
+///        "freeformAddress": "De Ruijterkade 154, 1011 AC Amsterdam"
+///      },
+///      "position": { "lat": 52.37727, "lon": 4.90943 },
+///      "viewport": {
+///        "topLeftPoint": { "lat": 52.37817, "lon": 4.90796 },
+///        "btmRightPoint": { "lat": 52.37637, "lon": 4.9109 }
+///      }
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TomTomResponse<T>
+where
+    T: Float + Debug,
+{
+    pub summary: Summary,
+    pub results: Vec<TomTomResult<T>>,
+}
+
+/// Metadata about a search request
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    pub query: Option<String>,
+    pub query_type: Option<String>,
+    pub query_time: u32,
+    pub num_results: u32,
+    pub offset: Option<u32>,
+    pub total_results: Option<u32>,
+    pub fuzzy_level: Option<u8>,
+}
+
+/// A search or geocode result
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TomTomResult<T>
+where
+    T: Float + Debug,
+{
+    pub r#type: ResultType,
+    pub id: String,
+    pub score: f64,
+    pub match_confidence: Option<MatchConfidence>,
+    /// The kind of geography, for results of type `Geography`
+    pub entity_type: Option<EntityType>,
+    pub address: TomTomAddress,
+    pub position: LatLon<T>,
+    pub viewport: Option<Viewport<T>>,
+    pub poi: Option<serde_json::Value>,
+}
+
+/// The kind of a search result
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResultType {
+    #[serde(rename = "POI")]
+    Poi,
+    Street,
+    Geography,
+    #[serde(rename = "Point Address")]
+    PointAddress,
+    #[serde(rename = "Address Range")]
+    AddressRange,
+    #[serde(rename = "Cross Street")]
+    CrossStreet,
+    #[serde(other)]
+    Other,
+}
+
+/// The kind of geography of a `Geography` result
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EntityType {
+    Country,
+    CountrySubdivision,
+    CountrySecondarySubdivision,
+    CountryTertiarySubdivision,
+    Municipality,
+    MunicipalitySubdivision,
+    Neighbourhood,
+    PostalCodeArea,
+    #[serde(other)]
+    Other,
+}
+
+/// How well a result matches the query, from 0 to 1
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MatchConfidence {
+    pub score: f64,
+}
+
+/// A structured address
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TomTomAddress {
+    pub street_number: Option<String>,
+    pub street_name: Option<String>,
+    pub municipality_subdivision: Option<String>,
+    pub municipality: Option<String>,
+    pub country_secondary_subdivision: Option<String>,
+    pub country_subdivision: Option<String>,
+    pub postal_code: Option<String>,
+    pub extended_postal_code: Option<String>,
+    pub country_code: Option<String>,
+    pub country: Option<String>,
+    #[serde(rename = "countryCodeISO3")]
+    pub country_code_iso3: Option<String>,
+    pub freeform_address: Option<String>,
+    pub local_name: Option<String>,
+}
+
+/// A coordinate pair
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LatLon<T>
+where
+    T: Float + Debug,
+{
+    pub lat: T,
+    pub lon: T,
+}
+
+/// The recommended viewport for displaying a result
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Viewport<T>
+where
+    T: Float + Debug,
+{
+    pub top_left_point: LatLon<T>,
+    pub btm_right_point: LatLon<T>,
+}
+
+/// The top-level full JSON response returned by reverse geocode requests
+///
+/// See [the documentation](https://developer.tomtom.com/search-api/documentation/reverse-geocoding-service/reverse-geocode#response-data) for more details
+///
+///```json
+///{
+///  "summary": { "queryTime": 5, "numResults": 1 },
+///  "addresses": [
+///    {
+///      "address": {
+///        "streetNumber": "154",
+///        "streetName": "De Ruijterkade",
+///        "municipality": "Amsterdam",
+///        "countryCode": "NL",
+///        "freeformAddress": "De Ruijterkade 154, 1011 AC Amsterdam"
+///      },
+///      "position": "52.377270,4.909430"
+///    }
+///  ]
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TomTomReverseResponse<T>
+where
+    T: Float + Debug,
+{
+    pub summary: Summary,
+    pub addresses: Vec<TomTomReverseResult<T>>,
+}
+
+/// A reverse geocode result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TomTomReverseResult<T>
+where
+    T: Float + Debug,
+{
+    pub address: TomTomAddress,
+    #[serde(deserialize_with = "deserialize_lat_lon_string")]
+    pub position: LatLon<T>,
+}
+
+// Reverse geocode positions are "lat,lon" strings
+fn deserialize_lat_lon_string<'de, D, T>(deserializer: D) -> Result<LatLon<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Float + Debug,
+{
+    let position = String::deserialize(deserializer)?;
+    let parse = |value: Option<&str>| {
+        value
+            .and_then(|value| value.trim().parse::<f64>().ok())
+            .and_then(T::from)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid position {}", position)))
+    };
+    let mut parts = position.split(',');
+    let lat = parse(parts.next())?;
+    let lon = parse(parts.next())?;
+    Ok(LatLon { lat, lon })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encode_path_segment_test() {
+        assert_eq!(
+            encode_path_segment("De Ruijterkade 154/München"),
+            "De%20Ruijterkade%20154%2FM%C3%BCnchen"
+        );
+    }
+
+    #[test]
+    fn response_test() {
+        let res: TomTomResponse<f64> = serde_json::from_str(
+            r#"{
+                "summary": { "query": "amsterdam", "queryType": "NON_NEAR", "queryTime": 12, "numResults": 1 },
+                "results": [
+                    {
+                        "type": "Geography",
+                        "id": "NL/GEO/p0/12345",
+                        "score": 7.2,
+                        "matchConfidence": { "score": 0.93 },
+                        "entityType": "Municipality",
+                        "address": { "municipality": "Amsterdam", "countryCode": "NL", "freeformAddress": "Amsterdam" },
+                        "position": { "lat": 52.37317, "lon": 4.89066 }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let result = &res.results[0];
+        assert_eq!(result.r#type, ResultType::Geography);
+        assert_eq!(result.entity_type, Some(EntityType::Municipality));
+        assert_eq!(result.match_confidence.as_ref().unwrap().score, 0.93);
+    }
+
+    #[test]
+    fn reverse_response_test() {
+        let res: TomTomReverseResponse<f64> = serde_json::from_str(
+            r#"{
+                "summary": { "queryTime": 5, "numResults": 1 },
+                "addresses": [
+                    {
+                        "address": { "freeformAddress": "De Ruijterkade 154, 1011 AC Amsterdam" },
+                        "position": "52.377270,4.909430"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let position = &res.addresses[0].position;
+        assert_eq!(
+            Point::new(position.lon, position.lat),
+            Point::new(4.90943, 52.37727)
+        );
+    }
+}
@@ -0,0 +1,353 @@
+//! Conversions between WGS84 and the coordinate systems used by some providers.
+//!
+//! ### Chinese coordinate systems
+//!
+//! Maps published in mainland China must use GCJ-02, an obfuscated datum offsetting WGS84
+//! coordinates by up to several hundred meters. Baidu applies a further offset on top of it,
+//! BD-09. Providers returning coordinates in either system are converted back to WGS84, so
+//! that returned `Point`s remain consistent with the rest of the crate.
+//!
+//! The WGS84 to GCJ-02 conversion is a published formula; its inverse is computed iteratively
+//! and is accurate to well under a meter. Coordinates outside mainland China are left unchanged,
+//! as GCJ-02 isn't applied there.
+//!
+//! ```
+//! use geocoding::Point;
+//! use geocoding::transform::{bd09_to_wgs84, wgs84_to_bd09};
+//!
+//! let wgs84 = Point::new(116.397_f64, 39.908);
+//! let bd09 = wgs84_to_bd09(&wgs84);
+//! assert!((bd09.x() - wgs84.x()).abs() > 0.005);
+//! let back = bd09_to_wgs84(&bd09);
+//! assert!((back.x() - wgs84.x()).abs() < 1e-6);
+//! ```
+//!
+//! ### Transverse Mercator grids
+//!
+//! Several national grids, such as SWEREF 99 TM in Sweden or ETRS-TM35FIN in Finland, are
+//! transverse Mercator projections of the GRS 80 ellipsoid. Their ETRS89 coordinates may be
+//! treated as WGS84 at the accuracy of geocoding. A [`TransverseMercator`](struct.TransverseMercator.html)
+//! projects WGS84 points to eastings and northings, in metres, and back.
+//!
+//! ```
+//! use geocoding::Point;
+//! use geocoding::transform::SWEREF99_TM;
+//!
+//! let stockholm = Point::new(18.0686_f64, 59.3293);
+//! let grid = SWEREF99_TM.project(&stockholm);
+//! let back = SWEREF99_TM.unproject(&grid);
+//! assert!((back.x() - stockholm.x()).abs() < 1e-8);
+//! ```
+use crate::Point;
+use num_traits::Float;
+use std::f64::consts::PI;
+use std::fmt::Debug;
+
+// Krasovsky 1940 ellipsoid, used by GCJ-02
+const A: f64 = 6_378_245.0;
+const EE: f64 = 0.006_693_421_622_965_943;
+// Baidu's additional offset
+const X_PI: f64 = PI * 3000.0 / 180.0;
+
+/// Convert a WGS84 point to GCJ-02
+pub fn wgs84_to_gcj02<T>(point: &Point<T>) -> Point<T>
+where
+    T: Float + Debug,
+{
+    let (lon, lat) = to_f64(point);
+    if out_of_china(lon, lat) {
+        return *point;
+    }
+    let (dlon, dlat) = gcj02_offset(lon, lat);
+    from_f64(lon + dlon, lat + dlat)
+}
+
+/// Convert a GCJ-02 point to WGS84
+pub fn gcj02_to_wgs84<T>(point: &Point<T>) -> Point<T>
+where
+    T: Float + Debug,
+{
+    let (lon, lat) = to_f64(point);
+    if out_of_china(lon, lat) {
+        return *point;
+    }
+    // The offset varies slowly, so a fixed-point iteration converges within a few steps
+    let (mut wgs_lon, mut wgs_lat) = (lon, lat);
+    for _ in 0..10 {
+        let (dlon, dlat) = gcj02_offset(wgs_lon, wgs_lat);
+        let (next_lon, next_lat) = (lon - dlon, lat - dlat);
+        let converged = (next_lon - wgs_lon).abs() < 1e-9 && (next_lat - wgs_lat).abs() < 1e-9;
+        wgs_lon = next_lon;
+        wgs_lat = next_lat;
+        if converged {
+            break;
+        }
+    }
+    from_f64(wgs_lon, wgs_lat)
+}
+
+/// Convert a GCJ-02 point to BD-09
+pub fn gcj02_to_bd09<T>(point: &Point<T>) -> Point<T>
+where
+    T: Float + Debug,
+{
+    let (x, y) = to_f64(point);
+    let z = (x * x + y * y).sqrt() + 0.00002 * (y * X_PI).sin();
+    let theta = y.atan2(x) + 0.000003 * (x * X_PI).cos();
+    from_f64(z * theta.cos() + 0.0065, z * theta.sin() + 0.006)
+}
+
+/// Convert a BD-09 point to GCJ-02
+pub fn bd09_to_gcj02<T>(point: &Point<T>) -> Point<T>
+where
+    T: Float + Debug,
+{
+    let (lon, lat) = to_f64(point);
+    let (x, y) = (lon - 0.0065, lat - 0.006);
+    let z = (x * x + y * y).sqrt() - 0.00002 * (y * X_PI).sin();
+    let theta = y.atan2(x) - 0.000003 * (x * X_PI).cos();
+    from_f64(z * theta.cos(), z * theta.sin())
+}
+
+/// Convert a WGS84 point to BD-09
+pub fn wgs84_to_bd09<T>(point: &Point<T>) -> Point<T>
+where
+    T: Float + Debug,
+{
+    gcj02_to_bd09(&wgs84_to_gcj02(point))
+}
+
+/// Convert a BD-09 point to WGS84
+pub fn bd09_to_wgs84<T>(point: &Point<T>) -> Point<T>
+where
+    T: Float + Debug,
+{
+    gcj02_to_wgs84(&bd09_to_gcj02(point))
+}
+
+/// A transverse Mercator projection of the GRS 80 ellipsoid
+///
+/// Projections use the Krüger series to the fourth order, which are accurate to a millimetre
+/// within the usual extent of a zone.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TransverseMercator {
+    /// The longitude of the central meridian, in degrees
+    pub central_meridian: f64,
+    /// The scale factor on the central meridian
+    pub scale: f64,
+    /// The easting of the central meridian, in metres
+    pub false_easting: f64,
+    /// The northing of the equator, in metres
+    pub false_northing: f64,
+}
+
+/// SWEREF 99 TM ([EPSG:3006](https://epsg.io/3006)), the Swedish national grid
+pub const SWEREF99_TM: TransverseMercator = TransverseMercator {
+    central_meridian: 15.0,
+    scale: 0.9996,
+    false_easting: 500_000.0,
+    false_northing: 0.0,
+};
+
+/// ETRS89 / UTM zone 33N ([EPSG:25833](https://epsg.io/25833)), used nationwide in Norway
+pub const ETRS89_UTM33: TransverseMercator = TransverseMercator {
+    central_meridian: 15.0,
+    scale: 0.9996,
+    false_easting: 500_000.0,
+    false_northing: 0.0,
+};
+
+/// ETRS-TM35FIN ([EPSG:3067](https://epsg.io/3067)), the Finnish national grid
+pub const ETRS_TM35FIN: TransverseMercator = TransverseMercator {
+    central_meridian: 27.0,
+    scale: 0.9996,
+    false_easting: 500_000.0,
+    false_northing: 0.0,
+};
+
+// GRS 80 ellipsoid
+const GRS80_A: f64 = 6_378_137.0;
+const GRS80_F: f64 = 1.0 / 298.257_222_101;
+
+impl TransverseMercator {
+    /// Project a WGS84 point to an easting and northing
+    pub fn project<T>(&self, point: &Point<T>) -> Point<T>
+    where
+        T: Float + Debug,
+    {
+        let (lon, lat) = to_f64(point);
+        let (n, a_hat, e2) = ellipsoid();
+        let beta = [
+            n / 2.0 - 2.0 * n.powi(2) / 3.0 + 5.0 * n.powi(3) / 16.0 + 41.0 * n.powi(4) / 180.0,
+            13.0 * n.powi(2) / 48.0 - 3.0 * n.powi(3) / 5.0 + 557.0 * n.powi(4) / 1440.0,
+            61.0 * n.powi(3) / 240.0 - 103.0 * n.powi(4) / 140.0,
+            49561.0 * n.powi(4) / 161_280.0,
+        ];
+        // Conformal latitude
+        let a = e2;
+        let b = (5.0 * e2.powi(2) - e2.powi(3)) / 6.0;
+        let c = (104.0 * e2.powi(3) - 45.0 * e2.powi(4)) / 120.0;
+        let d = 1237.0 * e2.powi(4) / 1260.0;
+        let phi = lat.to_radians();
+        let sin2 = phi.sin().powi(2);
+        let phi_star = phi - phi.sin() * phi.cos() * (a + sin2 * (b + sin2 * (c + sin2 * d)));
+
+        let dlambda = (lon - self.central_meridian).to_radians();
+        let xi = (phi_star.tan() / dlambda.cos()).atan();
+        let eta = (phi_star.cos() * dlambda.sin()).atanh();
+        let (mut x, mut y) = (xi, eta);
+        for (j, beta) in beta.iter().enumerate() {
+            let k = 2.0 * (j + 1) as f64;
+            x += beta * (k * xi).sin() * (k * eta).cosh();
+            y += beta * (k * xi).cos() * (k * eta).sinh();
+        }
+        from_f64(
+            self.scale * a_hat * y + self.false_easting,
+            self.scale * a_hat * x + self.false_northing,
+        )
+    }
+
+    /// Convert an easting and northing to a WGS84 point
+    pub fn unproject<T>(&self, point: &Point<T>) -> Point<T>
+    where
+        T: Float + Debug,
+    {
+        let (easting, northing) = to_f64(point);
+        let (n, a_hat, e2) = ellipsoid();
+        let delta = [
+            n / 2.0 - 2.0 * n.powi(2) / 3.0 + 37.0 * n.powi(3) / 96.0 - n.powi(4) / 360.0,
+            n.powi(2) / 48.0 + n.powi(3) / 15.0 - 437.0 * n.powi(4) / 1440.0,
+            17.0 * n.powi(3) / 480.0 - 37.0 * n.powi(4) / 840.0,
+            4397.0 * n.powi(4) / 161_280.0,
+        ];
+        let xi = (northing - self.false_northing) / (self.scale * a_hat);
+        let eta = (easting - self.false_easting) / (self.scale * a_hat);
+        let (mut xi_prime, mut eta_prime) = (xi, eta);
+        for (j, delta) in delta.iter().enumerate() {
+            let k = 2.0 * (j + 1) as f64;
+            xi_prime -= delta * (k * xi).sin() * (k * eta).cosh();
+            eta_prime -= delta * (k * xi).cos() * (k * eta).sinh();
+        }
+        let phi_star = (xi_prime.sin() / eta_prime.cosh()).asin();
+        let dlambda = (eta_prime.sinh() / xi_prime.cos()).atan();
+
+        let a = e2 + e2.powi(2) + e2.powi(3) + e2.powi(4);
+        let b = -(7.0 * e2.powi(2) + 17.0 * e2.powi(3) + 30.0 * e2.powi(4)) / 6.0;
+        let c = (224.0 * e2.powi(3) + 889.0 * e2.powi(4)) / 120.0;
+        let d = -4279.0 * e2.powi(4) / 1260.0;
+        let sin2 = phi_star.sin().powi(2);
+        let phi =
+            phi_star + phi_star.sin() * phi_star.cos() * (a + sin2 * (b + sin2 * (c + sin2 * d)));
+        from_f64(
+            self.central_meridian + dlambda.to_degrees(),
+            phi.to_degrees(),
+        )
+    }
+}
+
+// The third flattening, rectifying radius and squared eccentricity of GRS 80
+fn ellipsoid() -> (f64, f64, f64) {
+    let n = GRS80_F / (2.0 - GRS80_F);
+    let a_hat = GRS80_A / (1.0 + n) * (1.0 + n.powi(2) / 4.0 + n.powi(4) / 64.0);
+    let e2 = GRS80_F * (2.0 - GRS80_F);
+    (n, a_hat, e2)
+}
+
+// A coarse bounding box of mainland China
+fn out_of_china(lon: f64, lat: f64) -> bool {
+    !(72.004..=137.8347).contains(&lon) || !(0.8293..=55.8271).contains(&lat)
+}
+
+// The GCJ-02 offset of a WGS84 coordinate, in degrees
+fn gcj02_offset(lon: f64, lat: f64) -> (f64, f64) {
+    let (x, y) = (lon - 105.0, lat - 35.0);
+    let mut dlat = -100.0 + 2.0 * x + 3.0 * y + 0.2 * y * y + 0.1 * x * y + 0.2 * x.abs().sqrt();
+    dlat += (20.0 * (6.0 * x * PI).sin() + 20.0 * (2.0 * x * PI).sin()) * 2.0 / 3.0;
+    dlat += (20.0 * (y * PI).sin() + 40.0 * (y / 3.0 * PI).sin()) * 2.0 / 3.0;
+    dlat += (160.0 * (y / 12.0 * PI).sin() + 320.0 * (y * PI / 30.0).sin()) * 2.0 / 3.0;
+    let mut dlon = 300.0 + x + 2.0 * y + 0.1 * x * x + 0.1 * x * y + 0.1 * x.abs().sqrt();
+    dlon += (20.0 * (6.0 * x * PI).sin() + 20.0 * (2.0 * x * PI).sin()) * 2.0 / 3.0;
+    dlon += (20.0 * (x * PI).sin() + 40.0 * (x / 3.0 * PI).sin()) * 2.0 / 3.0;
+    dlon += (150.0 * (x / 12.0 * PI).sin() + 300.0 * (x / 30.0 * PI).sin()) * 2.0 / 3.0;
+
+    let rad_lat = lat / 180.0 * PI;
+    let magic = 1.0 - EE * rad_lat.sin() * rad_lat.sin();
+    let sqrt_magic = magic.sqrt();
+    dlat = (dlat * 180.0) / ((A * (1.0 - EE)) / (magic * sqrt_magic) * PI);
+    dlon = (dlon * 180.0) / (A / sqrt_magic * rad_lat.cos() * PI);
+    (dlon, dlat)
+}
+
+fn to_f64<T>(point: &Point<T>) -> (f64, f64)
+where
+    T: Float + Debug,
+{
+    (point.x().to_f64().unwrap(), point.y().to_f64().unwrap())
+}
+
+fn from_f64<T>(x: f64, y: f64) -> Point<T>
+where
+    T: Float + Debug,
+{
+    Point::new(T::from(x).unwrap(), T::from(y).unwrap())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gcj02_test() {
+        // Tiananmen Square
+        let wgs84 = Point::new(116.391_275, 39.907_23);
+        let gcj02 = wgs84_to_gcj02(&wgs84);
+        assert!((gcj02.x() - 116.397_51).abs() < 1e-4);
+        assert!((gcj02.y() - 39.908_63).abs() < 1e-4);
+        let back = gcj02_to_wgs84(&gcj02);
+        assert!((back.x() - wgs84.x()).abs() < 1e-7);
+        assert!((back.y() - wgs84.y()).abs() < 1e-7);
+    }
+
+    #[test]
+    fn bd09_round_trip_test() {
+        let gcj02 = Point::new(121.473_701, 31.230_416);
+        let back = bd09_to_gcj02(&gcj02_to_bd09(&gcj02));
+        assert!((back.x() - gcj02.x()).abs() < 1e-5);
+        assert!((back.y() - gcj02.y()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn sweref99_tm_test() {
+        // The central meridian on the equator is the false origin
+        let origin = SWEREF99_TM.project(&Point::new(15.0, 0.0));
+        assert!((origin.x() - 500_000.0).abs() < 1e-6);
+        assert!(origin.y().abs() < 1e-6);
+        // 66°N 24°E, towards the eastern edge of the zone
+        let point = Point::new(24.0, 66.0);
+        let grid = SWEREF99_TM.project(&point);
+        assert!((grid.x() - 907_351.981).abs() < 0.001);
+        assert!((grid.y() - 7_349_217.668).abs() < 0.001);
+        let back = SWEREF99_TM.unproject(&grid);
+        assert!((back.x() - point.x()).abs() < 1e-9);
+        assert!((back.y() - point.y()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn etrs_tm35fin_test() {
+        // Helsinki Cathedral
+        let helsinki = Point::new(24.952_222, 60.170_278);
+        let grid = ETRS_TM35FIN.project(&helsinki);
+        assert!((grid.x() - 386_379.38).abs() < 0.01);
+        assert!((grid.y() - 6_672_136.60).abs() < 0.01);
+        let back = ETRS_TM35FIN.unproject(&grid);
+        assert!((back.x() - helsinki.x()).abs() < 1e-9);
+        assert!((back.y() - helsinki.y()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn out_of_china_test() {
+        let berlin = Point::new(13.4, 52.5);
+        assert_eq!(wgs84_to_gcj02(&berlin), berlin);
+        assert_eq!(gcj02_to_wgs84(&berlin), berlin);
+    }
+}
@@ -0,0 +1,435 @@
+//! The [US Census Bureau Geocoder](https://geocoding.geo.census.gov/geocoder/) provider.
+//!
+//! Geocoding methods are implemented on the [`UsCensus`](struct.UsCensus.html) struct.
+//! The service is free, requires no key, and only covers US addresses, matched against
+//! the Census Bureau's TIGER/Line address ranges. Please see the
+//! [API documentation](https://geocoding.geo.census.gov/geocoder/Geocoding_Services_API.html) for details.
+//!
+//! Every request is made against a *benchmark*, a snapshot of the address data. Setting a
+//! *vintage* as well returns the census geographies (state, county, tract, block…) containing
+//! each match. The coordinates endpoint only returns geographies, not addresses, so this
+//! provider doesn't implement `Reverse`; use [`geographies`](struct.UsCensus.html#method.geographies)
+//! to look up the census tract and block of a point.
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, Point, UsCensus};
+//!
+//! let census = UsCensus::new();
+//! let address = "4600 Silver Hill Rd, Washington, DC 20233";
+//! let res: Result<Vec<Point<f64>>, _> = census.forward(address);
+//! ```
+use crate::info::ProviderInfo;
+use crate::Forward;
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, DeserializeOwned, Serialize};
+use num_traits::Float;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// An instance of the US Census Bureau geocoding service
+pub struct UsCensus {
+    client: Client,
+    endpoint: String,
+    benchmark: String,
+    vintage: Option<String>,
+}
+
+/// A US address, split into components
+#[derive(Debug, Default)]
+pub struct UsCensusAddress<'a> {
+    pub street: &'a str,
+    pub city: Option<&'a str>,
+    /// The two-letter state abbreviation
+    pub state: Option<&'a str>,
+    pub zip: Option<&'a str>,
+}
+
+impl UsCensus {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "US Census Bureau Geocoder",
+        docs_url: "https://geocoding.geo.census.gov/geocoder/Geocoding_Services_API.html",
+        terms_url: None,
+        attribution: None,
+        rate_limit: None,
+    };
+
+    /// Create a new US Census geocoding instance using the default endpoint and the
+    /// `Public_AR_Current` benchmark, without geographies
+    pub fn new() -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        UsCensus {
+            client,
+            endpoint: "https://geocoding.geo.census.gov/geocoder/".to_string(),
+            benchmark: "Public_AR_Current".to_string(),
+            vintage: None,
+        }
+    }
+
+    /// Set a custom endpoint of a US Census geocoding instance
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://geocoding.geo.census.gov/geocoder/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// Set the benchmark (address data snapshot), by name or ID, e.g. `Public_AR_Census2020`
+    pub fn with_benchmark(mut self, benchmark: &str) -> Self {
+        self.benchmark = benchmark.to_owned();
+        self
+    }
+
+    /// Set the vintage (geography snapshot), by name or ID, e.g. `Census2020_Current`
+    ///
+    /// Forward-geocoding matches then include the census geographies containing them.
+    pub fn with_vintage(mut self, vintage: &str) -> Self {
+        self.vintage = Some(vintage.to_owned());
+        self
+    }
+
+    /// A forward-geocoding lookup of a one-line address, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://geocoding.geo.census.gov/geocoder/Geocoding_Services_API.html) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::UsCensus;
+    /// use geocoding::uscensus::UsCensusResponse;
+    ///
+    /// let census = UsCensus::new().with_vintage("Current_Current");
+    /// let res: Result<UsCensusResponse<f64>, _> =
+    ///     census.forward_full("4600 Silver Hill Rd, Washington, DC 20233");
+    /// ```
+    pub fn forward_full<T>(&self, address: &str) -> Result<UsCensusResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let query = vec![("address", address.to_string())];
+        self.get("onelineaddress", query)
+    }
+
+    /// A forward-geocoding lookup of an address split into components, returning a full detailed response
+    ///
+    /// Please see [the documentation](https://geocoding.geo.census.gov/geocoder/Geocoding_Services_API.html) for details.
+    pub fn forward_structured<T>(
+        &self,
+        address: &UsCensusAddress,
+    ) -> Result<UsCensusResponse<T>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let mut query = vec![("street", address.street.to_string())];
+        if let Some(city) = address.city {
+            query.push(("city", city.to_string()));
+        }
+        if let Some(state) = address.state {
+            query.push(("state", state.to_string()));
+        }
+        if let Some(zip) = address.zip {
+            query.push(("zip", zip.to_string()));
+        }
+        self.get("address", query)
+    }
+
+    /// Look up the census geographies containing a point
+    ///
+    /// Uses the configured vintage, or `Current_Current` if none was set.
+    pub fn geographies<T>(&self, point: &Point<T>) -> Result<Geographies, GeocodingError>
+    where
+        T: Float + Debug,
+    {
+        let query = vec![
+            ("x", point.x().to_f64().unwrap().to_string()),
+            ("y", point.y().to_f64().unwrap().to_string()),
+            ("benchmark", self.benchmark.clone()),
+            (
+                "vintage",
+                self.vintage
+                    .clone()
+                    .unwrap_or_else(|| "Current_Current".to_string()),
+            ),
+            ("format", String::from("json")),
+        ];
+        let resp = self
+            .client
+            .get(format!("{}geographies/coordinates", self.endpoint))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: CoordinatesResponse = resp.json()?;
+        Ok(res.result.geographies)
+    }
+
+    // Addresses are looked up as `locations`, or as `geographies` when a vintage is set
+    fn get<R>(
+        &self,
+        search_type: &str,
+        mut query: Vec<(&'static str, String)>,
+    ) -> Result<R, GeocodingError>
+    where
+        R: DeserializeOwned,
+    {
+        query.push(("benchmark", self.benchmark.clone()));
+        let return_type = match &self.vintage {
+            Some(vintage) => {
+                query.push(("vintage", vintage.clone()));
+                "geographies"
+            }
+            None => "locations",
+        };
+        query.push(("format", String::from("json")));
+        let resp = self
+            .client
+            .get(format!("{}{}/{}", self.endpoint, return_type, search_type))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: R = resp.json()?;
+        Ok(res)
+    }
+}
+
+impl Default for UsCensus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Forward<T> for UsCensus
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding lookup of a one-line address.
+    /// Please see [the documentation](https://geocoding.geo.census.gov/geocoder/Geocoding_Services_API.html) for details.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(place)?;
+        Ok(res
+            .result
+            .address_matches
+            .iter()
+            .map(|address_match| {
+                Point::new(address_match.coordinates.x, address_match.coordinates.y)
+            })
+            .collect())
+    }
+}
+
+/// The top-level full JSON response returned by an address lookup
+///
+/// See [the documentation](https://geocoding.geo.census.gov/geocoder/Geocoding_Services_API.html) for more details
+///
+///```json
+///{
+///  "result": {
+///    "input": {
+///      "address": { "address": "4600 Silver Hill Rd, Washington, DC 20233" },
+///      "benchmark": { "id": "4", "benchmarkName": "Public_AR_Current", "isDefault": true }
+///    },
+///    "addressMatches": [
+///      {
+///        "matchedAddress": "4600 SILVER HILL RD, WASHINGTON, DC, 20233",
+///        "coordinates": { "x": -76.92748724230096, "y": 38.84601622386617 },
+///        "tigerLine": { "tigerLineId": "76355984", "side": "L" },
+///        "addressComponents": {
+///          "fromAddress": "4600",
+///          "toAddress": "4700",
+///          "preQualifier": "",
+///          "preDirection": "",
+///          "preType": "",
+///          "streetName": "SILVER HILL",
+///          "suffixType": "RD",
+///          "suffixDirection": "",
+///          "suffixQualifier": "",
+///          "city": "WASHINGTON",
+///          "state": "DC",
+///          "zip": "20233"
+///        }
+///      }
+///    ]
+///  }
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UsCensusResponse<T>
+where
+    T: Float + Debug,
+{
+    pub result: UsCensusResult<T>,
+}
+
+/// The result of an address lookup
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsCensusResult<T>
+where
+    T: Float + Debug,
+{
+    /// The query, as interpreted by the service
+    pub input: serde_json::Value,
+    pub address_matches: Vec<AddressMatch<T>>,
+}
+
+/// An address match
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressMatch<T>
+where
+    T: Float + Debug,
+{
+    pub matched_address: String,
+    pub coordinates: Coordinates<T>,
+    pub tiger_line: TigerLine,
+    pub address_components: AddressComponents,
+    /// The census geographies containing the match, if a vintage was set
+    pub geographies: Option<Geographies>,
+}
+
+/// A coordinate pair
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Coordinates<T>
+where
+    T: Float + Debug,
+{
+    pub x: T,
+    pub y: T,
+}
+
+/// The TIGER/Line edge the match was interpolated along
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TigerLine {
+    pub tiger_line_id: String,
+    /// The side of the street: `L` or `R`
+    pub side: String,
+}
+
+/// The components of a matched address
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressComponents {
+    /// The lowest house number of the matched address range
+    pub from_address: String,
+    /// The highest house number of the matched address range
+    pub to_address: String,
+    pub pre_qualifier: String,
+    pub pre_direction: String,
+    pub pre_type: String,
+    pub street_name: String,
+    pub suffix_type: String,
+    pub suffix_direction: String,
+    pub suffix_qualifier: String,
+    pub city: String,
+    pub state: String,
+    pub zip: String,
+}
+
+/// Census geographies, by layer name (e.g. `Census Tracts`, `2020 Census Blocks`, `Counties`)
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Geographies(pub HashMap<String, Vec<Geography>>);
+
+impl Geographies {
+    /// The census tract, whose `geoid` is its 11-digit FIPS code
+    pub fn tract(&self) -> Option<&Geography> {
+        self.0.get("Census Tracts")?.first()
+    }
+
+    /// The census block, whose `geoid` is its 15-digit FIPS code
+    ///
+    /// The name of the block layer depends on the vintage, e.g. `2020 Census Blocks`.
+    pub fn block(&self) -> Option<&Geography> {
+        self.0
+            .iter()
+            .find(|(layer, _)| layer.ends_with("Census Blocks"))?
+            .1
+            .first()
+    }
+}
+
+/// A census geography
+///
+/// Only the identifying attributes are typed; each layer returns others, such as its area.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct Geography {
+    /// The full FIPS code of the geography
+    pub geoid: String,
+    pub name: String,
+    /// The two-digit state FIPS code
+    pub state: Option<String>,
+    /// The three-digit county FIPS code
+    pub county: Option<String>,
+    /// The six-digit tract code
+    pub tract: Option<String>,
+    /// The four-digit block code
+    pub block: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoordinatesResponse {
+    result: CoordinatesResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoordinatesResult {
+    geographies: Geographies,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn geographies_test() {
+        let res: UsCensusResponse<f64> = serde_json::from_str(
+            r#"{
+                "result": {
+                    "input": { "address": { "address": "4600 Silver Hill Rd, Washington, DC 20233" } },
+                    "addressMatches": [
+                        {
+                            "matchedAddress": "4600 SILVER HILL RD, WASHINGTON, DC, 20233",
+                            "coordinates": { "x": -76.92748724230096, "y": 38.84601622386617 },
+                            "tigerLine": { "tigerLineId": "76355984", "side": "L" },
+                            "addressComponents": {
+                                "fromAddress": "4600", "toAddress": "4700",
+                                "preQualifier": "", "preDirection": "", "preType": "",
+                                "streetName": "SILVER HILL", "suffixType": "RD",
+                                "suffixDirection": "", "suffixQualifier": "",
+                                "city": "WASHINGTON", "state": "DC", "zip": "20233"
+                            },
+                            "geographies": {
+                                "Census Tracts": [
+                                    { "GEOID": "24033802405", "NAME": "Census Tract 8024.05", "STATE": "24", "COUNTY": "033", "TRACT": "802405", "AREALAND": 2153953 }
+                                ],
+                                "2020 Census Blocks": [
+                                    { "GEOID": "240338024052004", "NAME": "Block 2004", "STATE": "24", "COUNTY": "033", "TRACT": "802405", "BLOCK": "2004" }
+                                ]
+                            }
+                        }
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+        let address_match = &res.result.address_matches[0];
+        let geographies = address_match.geographies.as_ref().unwrap();
+        assert_eq!(geographies.tract().unwrap().geoid, "24033802405");
+        assert_eq!(geographies.block().unwrap().geoid, "240338024052004");
+        assert_eq!(address_match.address_components.street_name, "SILVER HILL");
+    }
+}
@@ -0,0 +1,342 @@
+//! The [Wikidata](https://www.wikidata.org/) and Wikipedia geosearch provider.
+//!
+//! Geocoding methods are implemented on the [`Wikidata`](struct.Wikidata.html) struct, using the
+//! [MediaWiki Action API](https://www.mediawiki.org/wiki/API:Main_page) of a Wikipedia edition.
+//! Forward lookups search articles by name, and reverse lookups find articles near a point;
+//! both return the coordinates of each article along with the Q-id of its Wikidata item, for
+//! entity linking. The API is free and requires no key. Please see the
+//! [GeoData documentation](https://www.mediawiki.org/wiki/Extension:GeoData) for details.
+//!
+//! Wikimedia asks that clients identify themselves; if making many requests, please follow the
+//! [User-Agent policy](https://meta.wikimedia.org/wiki/User-Agent_policy).
+//!
+//! ### Example
+//!
+//! ```
+//! use geocoding::{Forward, Point, Wikidata};
+//!
+//! let wikidata = Wikidata::new();
+//! let res: Result<Vec<Point<f64>>, _> = wikidata.forward("Sagrada Família");
+//! ```
+use crate::info::ProviderInfo;
+use crate::GeocodingError;
+use crate::Point;
+use crate::UA_STRING;
+use crate::{Client, HeaderMap, HeaderValue, USER_AGENT};
+use crate::{Deserialize, Serialize};
+use crate::{Forward, Reverse};
+use num_traits::Float;
+use serde::de::IgnoredAny;
+use std::fmt::Debug;
+
+/// An instance of the Wikipedia geosearch service
+pub struct Wikidata {
+    client: Client,
+    endpoint: String,
+}
+
+impl Wikidata {
+    /// Metadata about the service
+    pub const INFO: ProviderInfo = ProviderInfo {
+        name: "Wikidata",
+        docs_url: "https://www.mediawiki.org/wiki/Extension:GeoData",
+        terms_url: Some("https://foundation.wikimedia.org/wiki/Policy:Terms_of_Use"),
+        attribution: Some("Wikipedia, CC BY-SA 4.0"),
+        rate_limit: None,
+    };
+
+    /// Create a new Wikidata instance, searching the English Wikipedia
+    pub fn new() -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static(UA_STRING));
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Couldn't build a client!");
+        Wikidata {
+            client,
+            endpoint: "https://en.wikipedia.org/w/".to_string(),
+        }
+    }
+
+    /// Search another edition of Wikipedia, by its language code (e.g. "de")
+    pub fn with_language(mut self, language: &str) -> Self {
+        self.endpoint = format!("https://{}.wikipedia.org/w/", language);
+        self
+    }
+
+    /// Set a custom endpoint of a Wikidata instance, such as another MediaWiki installation
+    ///
+    /// Endpoint should include a trailing slash (i.e. "https://en.wikipedia.org/w/")
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_owned();
+        self
+    }
+
+    /// Search articles by name, returning at most `limit` pages in order of relevance
+    ///
+    /// Articles about subjects without a location are included, without coordinates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geocoding::Wikidata;
+    /// use geocoding::wikidata::WikiPage;
+    ///
+    /// let wikidata = Wikidata::new();
+    /// let res: Result<Vec<WikiPage<f64>>, _> = wikidata.forward_full("Sagrada Família", 5);
+    /// ```
+    pub fn forward_full<T>(
+        &self,
+        query: &str,
+        limit: u8,
+    ) -> Result<Vec<WikiPage<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let params = vec![
+            ("generator", "search".to_string()),
+            ("gsrsearch", query.to_string()),
+            ("gsrlimit", limit.to_string()),
+        ];
+        let mut pages = self.get(params, GeocodingError::Forward)?;
+        pages.sort_by_key(|page| page.index);
+        Ok(pages)
+    }
+
+    /// Find articles within `radius` metres of a point, returning at most `limit` pages,
+    /// nearest first
+    ///
+    /// The radius must be between 10 and 10,000 metres.
+    pub fn reverse_full<T>(
+        &self,
+        point: &Point<T>,
+        radius: u16,
+        limit: u8,
+    ) -> Result<Vec<WikiPage<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        let coord = format!(
+            "{}|{}",
+            point.y().to_f64().unwrap(),
+            point.x().to_f64().unwrap()
+        );
+        let params = vec![
+            ("generator", "geosearch".to_string()),
+            ("ggscoord", coord.clone()),
+            ("ggsradius", radius.to_string()),
+            ("ggslimit", limit.to_string()),
+            ("codistancefrompoint", coord),
+        ];
+        let mut pages = self.get(params, GeocodingError::Reverse)?;
+        pages.sort_by(|a, b| {
+            a.distance()
+                .partial_cmp(&b.distance())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        Ok(pages)
+    }
+
+    // The API reports errors with a 200 status, so `api_error` is returned in their place
+    fn get<T>(
+        &self,
+        mut query: Vec<(&'static str, String)>,
+        api_error: GeocodingError,
+    ) -> Result<Vec<WikiPage<T>>, GeocodingError>
+    where
+        T: Float + Debug,
+        for<'de> T: Deserialize<'de>,
+    {
+        query.extend(vec![
+            ("action", "query".to_string()),
+            ("prop", "coordinates|pageprops".to_string()),
+            ("ppprop", "wikibase_item".to_string()),
+            ("format", "json".to_string()),
+            ("formatversion", "2".to_string()),
+        ]);
+        let resp = self
+            .client
+            .get(format!("{}api.php", self.endpoint))
+            .query(&query)
+            .send()?
+            .error_for_status()?;
+        let res: WikiResponse<T> = resp.json()?;
+        if res.error.is_some() {
+            return Err(api_error);
+        }
+        Ok(res.query.map(|query| query.pages).unwrap_or_default())
+    }
+}
+
+impl Default for Wikidata {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Forward<T> for Wikidata
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A forward-geocoding search of articles by name, returning the locations of the ten most
+    /// relevant articles which have one.
+    fn forward(&self, place: &str) -> Result<Vec<Point<T>>, GeocodingError> {
+        let res = self.forward_full(place, 10)?;
+        Ok(res.iter().filter_map(WikiPage::point).collect())
+    }
+}
+
+impl<T> Reverse<T> for Wikidata
+where
+    T: Float + Debug,
+    for<'de> T: Deserialize<'de>,
+{
+    /// A reverse lookup of a point, returning the title of the nearest article within
+    /// 1 kilometre.
+    fn reverse(&self, point: &Point<T>) -> Result<Option<String>, GeocodingError> {
+        let res = self.reverse_full(point, 1000, 1)?;
+        Ok(res.into_iter().next().map(|page| page.title))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct WikiResponse<T>
+where
+    T: Float + Debug,
+{
+    query: Option<WikiQuery<T>>,
+    error: Option<IgnoredAny>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WikiQuery<T>
+where
+    T: Float + Debug,
+{
+    #[serde(default = "Vec::new")]
+    pages: Vec<WikiPage<T>>,
+}
+
+/// A Wikipedia article
+///
+///```json
+///{
+///  "pageid": 52497,
+///  "ns": 0,
+///  "title": "Sagrada Família",
+///  "index": 1,
+///  "coordinates": [
+///    {
+///      "lat": 41.403611,
+///      "lon": 2.174444,
+///      "primary": true,
+///      "globe": "earth",
+///      "dist": 231.4
+///    }
+///  ],
+///  "pageprops": {
+///    "wikibase_item": "Q48435"
+///  }
+///}
+///```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WikiPage<T>
+where
+    T: Float + Debug,
+{
+    pub pageid: u64,
+    pub title: String,
+    /// The rank of the page in search results
+    pub index: Option<u32>,
+    #[serde(default = "Vec::new")]
+    pub coordinates: Vec<WikiCoordinates<T>>,
+    pub pageprops: Option<PageProps>,
+}
+
+impl<T> WikiPage<T>
+where
+    T: Float + Debug,
+{
+    /// The Q-id of the Wikidata item about the article's subject, e.g. `Q48435`
+    pub fn qid(&self) -> Option<&str> {
+        self.pageprops.as_ref()?.wikibase_item.as_deref()
+    }
+
+    /// The primary location of the article's subject on Earth, if any
+    pub fn point(&self) -> Option<Point<T>> {
+        let coordinates = self.primary_coordinates()?;
+        Some(Point::new(coordinates.lon, coordinates.lat))
+    }
+
+    /// The distance from the searched point to the article's subject, in metres, for reverse
+    /// lookups
+    pub fn distance(&self) -> Option<T> {
+        self.primary_coordinates()?.dist
+    }
+
+    fn primary_coordinates(&self) -> Option<&WikiCoordinates<T>> {
+        self.coordinates
+            .iter()
+            .find(|coordinates| coordinates.primary && coordinates.globe == "earth")
+    }
+}
+
+/// The coordinates of an article's subject
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WikiCoordinates<T>
+where
+    T: Float + Debug,
+{
+    pub lat: T,
+    pub lon: T,
+    /// Whether these are the coordinates of the article's subject, rather than of something
+    /// mentioned in it
+    #[serde(default)]
+    pub primary: bool,
+    /// The celestial body, usually `earth`
+    pub globe: String,
+    pub dist: Option<T>,
+}
+
+/// The properties of an article
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageProps {
+    pub wikibase_item: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn geosearch_test() {
+        let res: WikiResponse<f64> = serde_json::from_str(
+            r#"{
+                "batchcomplete": true,
+                "query": {
+                    "pages": [{
+                        "pageid": 52497, "ns": 0, "title": "Sagrada Família", "index": 0,
+                        "coordinates": [{
+                            "lat": 41.403611, "lon": 2.174444, "primary": true,
+                            "globe": "earth", "dist": 231.4
+                        }],
+                        "pageprops": { "wikibase_item": "Q48435" }
+                    }, {
+                        "pageid": 1, "ns": 0, "title": "Olympus Mons", "index": 1,
+                        "coordinates": [{ "lat": 18.65, "lon": 226.2, "primary": true, "globe": "mars" }]
+                    }]
+                }
+            }"#,
+        )
+        .unwrap();
+        let pages = res.query.unwrap().pages;
+        assert_eq!(pages[0].qid(), Some("Q48435"));
+        assert_eq!(pages[0].point(), Some(Point::new(2.174444, 41.403611)));
+        assert_eq!(pages[0].distance(), Some(231.4));
+        assert_eq!(pages[1].point(), None);
+    }
+}